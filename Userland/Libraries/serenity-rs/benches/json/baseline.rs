@@ -0,0 +1,340 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// The parser as it was before it scanned bytes, kept only to measure the current one against. It
+// decodes the input char by char and clones the Chars iterator for every lookahead. Only the
+// error type is its own, as the current one no longer has all of these kinds.
+
+// Kept as it was, lints and all.
+#![allow(clippy::all)]
+
+use std::collections::HashMap;
+use std::str::Chars;
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum ParseErrorKind {
+    ExpectedCharacter(char),
+    ExpectedFalse,
+    ExpectedNull,
+    ExpectedTrue,
+    InvalidNumber,
+    LoneSurrogate(u16),
+    MultiplePeriodsInNumber,
+    UnexpectedCharacter(char),
+    UnexpectedControlCharacter,
+    UnexpectedEof,
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum Value {
+    Null,
+    Number(Number),
+    Bool(bool),
+    String(String),
+    Array(Vec<Value>),
+    Object(HashMap<String, Value>),
+}
+
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum Number {
+    Integer64(i64),
+    Float64(f64),
+}
+
+struct JsonParser<'a> {
+    source: &'a str,
+    input: Chars<'a>,
+}
+
+impl<'a> JsonParser<'a> {
+    fn peek(&self) -> Option<char> { self.input.clone().next() }
+
+    fn next(&mut self) -> Option<char> { self.input.next() }
+
+    fn offset(&self) -> usize { self.source.len() - self.input.as_str().len() }
+
+    fn parse(&mut self) -> Result<Value, ParseErrorKind> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.parse_object(),
+            Some('[') => self.parse_array(),
+            Some('"') => self.parse_string(),
+            Some('0'..='9' | '-') => self.parse_number(),
+            Some('t') => self.parse_true(),
+            Some('f') => self.parse_false(),
+            Some('n') => self.parse_null(),
+            Some(ch) => Err(ParseErrorKind::UnexpectedCharacter(ch)),
+            None => Err(ParseErrorKind::UnexpectedEof),
+        }
+    }
+
+    fn must_consume(&mut self, ch: char) -> Result<(), ParseErrorKind> {
+        match self.peek() {
+            Some(peek_ch) if ch == peek_ch => {
+                self.next();
+                Ok(())
+            }
+            _ => Err(ParseErrorKind::ExpectedCharacter(ch)),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Value, ParseErrorKind> {
+        self.must_consume('{')?;
+
+        let mut object = HashMap::new();
+
+        self.skip_whitespace();
+        if self.consume("}") {
+            return Ok(Value::Object(object));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let name = self.consume_and_unescape_string()?;
+            self.skip_whitespace();
+            self.must_consume(':')?;
+            self.skip_whitespace();
+            let value = self.parse()?;
+            object.insert(name, value);
+            self.skip_whitespace();
+            if let Some('}') = self.peek() {
+                break;
+            }
+            self.must_consume(',')?;
+        }
+        self.skip_whitespace();
+        self.must_consume('}')?;
+        Ok(Value::Object(object))
+    }
+
+    fn parse_array(&mut self) -> Result<Value, ParseErrorKind> {
+        self.must_consume('[')?;
+        let mut array = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            if self.next_is("]") {
+                break;
+            }
+            let element = self.parse()?;
+            array.push(element);
+            self.skip_whitespace();
+            if self.next_is("]") {
+                break;
+            }
+            self.must_consume(',')?;
+            self.skip_whitespace();
+            if self.next_is("]") {
+                return Err(ParseErrorKind::UnexpectedCharacter(']'));
+            }
+        }
+        self.skip_whitespace();
+        self.must_consume(']')?;
+        Ok(Value::Array(array))
+    }
+
+    fn parse_number(&mut self) -> Result<Value, ParseErrorKind> {
+        let mut string = String::with_capacity(64);
+        while let Some(ch @ ('-' | '.' | '0'..='9')) = self.peek() {
+            string.push(ch);
+            self.next();
+        }
+        Ok(Value::Number(parse_number_literal(&string)?))
+    }
+
+    fn parse_string(&mut self) -> Result<Value, ParseErrorKind> {
+        let string = self.consume_and_unescape_string()?;
+        Ok(Value::String(string))
+    }
+
+    fn parse_true(&mut self) -> Result<Value, ParseErrorKind> {
+        if self.consume("true") {
+            Ok(Value::Bool(true))
+        } else {
+            Err(ParseErrorKind::ExpectedTrue)
+        }
+    }
+
+    fn parse_false(&mut self) -> Result<Value, ParseErrorKind> {
+        if self.consume("false") {
+            Ok(Value::Bool(false))
+        } else {
+            Err(ParseErrorKind::ExpectedFalse)
+        }
+    }
+
+    fn parse_null(&mut self) -> Result<Value, ParseErrorKind> {
+        if self.consume("null") {
+            Ok(Value::Null)
+        } else {
+            Err(ParseErrorKind::ExpectedNull)
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        loop {
+            match self.peek() {
+                Some(' ' | '\n' | '\r' | '\t') => {
+                    self.next();
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn consume(&mut self, expected: &str) -> bool {
+        if !self.next_is(expected) {
+            return false;
+        }
+        for _ in 0..expected.len() {
+            self.next();
+        }
+        return true;
+    }
+
+    fn next_is(&self, expected: &str) -> bool {
+        let mut lookahead_chars = self.input.clone();
+        let mut expected_chars = expected.chars();
+        loop {
+            let lookahead_character = lookahead_chars.next();
+            let expected_character = expected_chars.next();
+            if expected_character.is_none() {
+                return true;
+            }
+            if lookahead_character.is_none() {
+                return false;
+            }
+            if lookahead_character != expected_character {
+                return false;
+            }
+        }
+    }
+
+    fn consume_and_unescape_string(&mut self) -> Result<String, ParseErrorKind> {
+        self.must_consume('"')?;
+
+        let mut string = String::new();
+        loop {
+            match self.peek() {
+                Some('"') => break,
+                Some('\\') => {
+                    self.next();
+                    string.push(self.consume_escape()?);
+                }
+                Some(ch) if ch < 0x20 as char => {
+                    return Err(ParseErrorKind::UnexpectedControlCharacter)
+                }
+                Some(ch) => {
+                    self.next();
+                    string.push(ch);
+                }
+                None => return Err(ParseErrorKind::UnexpectedEof),
+            }
+        }
+        self.must_consume('"')?;
+        Ok(string)
+    }
+
+    fn consume_escape(&mut self) -> Result<char, ParseErrorKind> {
+        let unescaped = match self.peek() {
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('/') => '/',
+            Some('b') => 0x8 as char,
+            Some('f') => 0xc as char,
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('u') => {
+                self.next();
+                return self.consume_unicode_escape();
+            }
+            Some(ch) => return Err(ParseErrorKind::UnexpectedCharacter(ch)),
+            None => return Err(ParseErrorKind::UnexpectedEof),
+        };
+        self.next();
+        Ok(unescaped)
+    }
+
+    fn consume_unicode_escape(&mut self) -> Result<char, ParseErrorKind> {
+        let code_unit = self.consume_hex_code_unit()?;
+        match code_unit {
+            0xd800..=0xdbff => {
+                // A high surrogate has to be followed by an escaped low surrogate.
+                if !self.consume("\\u") {
+                    return Err(ParseErrorKind::LoneSurrogate(code_unit));
+                }
+                let low_surrogate = self.consume_hex_code_unit()?;
+                if !(0xdc00..=0xdfff).contains(&low_surrogate) {
+                    return Err(ParseErrorKind::LoneSurrogate(code_unit));
+                }
+                let code_point = 0x10000
+                    + (((code_unit as u32) - 0xd800) << 10)
+                    + ((low_surrogate as u32) - 0xdc00);
+                Ok(char::from_u32(code_point).unwrap())
+            }
+            0xdc00..=0xdfff => Err(ParseErrorKind::LoneSurrogate(code_unit)),
+            _ => Ok(char::from_u32(code_unit as u32).unwrap()),
+        }
+    }
+
+    fn consume_hex_code_unit(&mut self) -> Result<u16, ParseErrorKind> {
+        let mut code_unit = 0u16;
+        for _ in 0..4 {
+            let digit = match self.peek() {
+                Some(ch) => ch
+                    .to_digit(16)
+                    .ok_or(ParseErrorKind::UnexpectedCharacter(ch))?,
+                None => return Err(ParseErrorKind::UnexpectedEof),
+            };
+            self.next();
+            code_unit = (code_unit << 4) | digit as u16;
+        }
+        Ok(code_unit)
+    }
+}
+
+fn parse_number_literal(string: &str) -> Result<Number, ParseErrorKind> {
+    let (integer_part, fractional_part) = match string.split_once('.') {
+        Some((_, fractional_part)) if fractional_part.contains('.') => {
+            return Err(ParseErrorKind::MultiplePeriodsInNumber)
+        }
+        Some((integer_part, fractional_part)) => (integer_part, Some(fractional_part)),
+        None => (string, None),
+    };
+
+    let digits = integer_part.strip_prefix('-').unwrap_or(integer_part);
+    if digits.is_empty() || (digits.len() > 1 && digits.starts_with('0')) {
+        return Err(ParseErrorKind::InvalidNumber);
+    }
+    if fractional_part.map_or(false, |fractional_part| fractional_part.contains('-')) {
+        return Err(ParseErrorKind::InvalidNumber);
+    }
+
+    if string.starts_with('-') && string[1..].chars().all(|ch| ch == '0' || ch == '.') {
+        return Ok(Number::Float64(-0.0));
+    }
+
+    if fractional_part.is_some() {
+        let value = string.parse().map_err(|_| ParseErrorKind::InvalidNumber)?;
+        Ok(Number::Float64(value))
+    } else {
+        let value = string.parse().map_err(|_| ParseErrorKind::InvalidNumber)?;
+        Ok(Number::Integer64(value))
+    }
+}
+
+// Fails with the error and the byte offset it was found at.
+pub fn parse(input: &str) -> Result<Value, (ParseErrorKind, usize)> {
+    let mut parser = JsonParser {
+        source: input,
+        input: input.chars(),
+    };
+    parser.parse().map_err(|kind| (kind, parser.offset()))
+}
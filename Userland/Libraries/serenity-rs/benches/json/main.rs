@@ -0,0 +1,106 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Measures json::parse() and json::Reader against the char-based parser they replaced, which is
+// kept in baseline.rs, on the same documents:
+//
+//     cargo bench --bench json -- [path]
+//
+// The first document is a /proc/all. By default this is tests/fixtures/proc/all, which is in the
+// kernel's exact format but was written by hand. To measure a real capture, run
+// `cat /proc/all > /home/anon/all.json` in a busy Serenity session, copy the file out of the disk
+// image (Meta/serenity.sh run leaves it in Build/<arch>/_disk_image, which can be mounted) and pass
+// its path. The second document is generated here: an array of long strings, with an escape now
+// and then, which is where the old parser's lookahead cost the most.
+
+mod baseline;
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use serenity::json;
+
+const ITERATIONS: u32 = 50;
+
+// The mean and the best time of `run`.
+fn measure(mut run: impl FnMut()) -> (Duration, Duration) {
+    // Warm up the caches and the allocator.
+    run();
+
+    let mut best = Duration::MAX;
+    let mut total = Duration::ZERO;
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        run();
+        let elapsed = start.elapsed();
+        best = best.min(elapsed);
+        total += elapsed;
+    }
+    (total / ITERATIONS, best)
+}
+
+fn report(name: &str, document: &str, (mean, best): (Duration, Duration), baseline: Duration) {
+    let throughput = document.len() as f64 / mean.as_secs_f64() / (1024.0 * 1024.0);
+    println!(
+        "  {:<10} mean {:>9.2?}  best {:>9.2?}  {:>7.1} MiB/s  {:>5.2}x",
+        name,
+        mean,
+        best,
+        throughput,
+        baseline.as_secs_f64() / mean.as_secs_f64()
+    );
+}
+
+fn bench(title: &str, document: &str) {
+    println!("{} ({} bytes)", title, document.len());
+    let baseline = measure(|| {
+        black_box(baseline::parse(black_box(document)).unwrap());
+    });
+    report("baseline", document, baseline, baseline.0);
+    let parse = measure(|| {
+        black_box(json::parse(black_box(document)).unwrap());
+    });
+    report("parse", document, parse, baseline.0);
+    let reader = measure(|| {
+        let reader = json::Reader::new(black_box(document.as_bytes()));
+        for event in reader {
+            black_box(event.unwrap());
+        }
+    });
+    report("reader", document, reader, baseline.0);
+}
+
+fn long_strings() -> String {
+    let text = "The quick brown fox jumps over the lazy dog. ".repeat(100);
+    let strings: Vec<String> = (0..500)
+        .map(|index| {
+            if index % 10 == 0 {
+                format!("\"{}\\n\\u00e9\\\"{}\"", text, index)
+            } else {
+                format!("\"{}{}\"", text, index)
+            }
+        })
+        .collect();
+    format!("[{}]", strings.join(","))
+}
+
+fn main() {
+    let path = std::env::args()
+        .skip(1)
+        .find(|argument| !argument.starts_with('-'));
+    let (title, document) = match &path {
+        Some(path) => (
+            path.as_str(),
+            std::fs::read_to_string(path).expect("Failed to read document"),
+        ),
+        None => (
+            "tests/fixtures/proc/all",
+            include_str!("../../tests/fixtures/proc/all").to_string(),
+        ),
+    };
+    bench(title, &document);
+    bench("long strings", &long_strings());
+}
@@ -0,0 +1,27 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Parses arbitrary input with the default options. Anything that parses has to survive a round
+// trip through the serializer unchanged.
+//
+//     cargo +nightly fuzz run json_parse
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use serenity::json;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(value) = json::parse(input) else {
+        return;
+    };
+    let serialized = value.to_string();
+    let reparsed = json::parse(&serialized).expect("serialized output does not parse");
+    assert_eq!(serialized, reparsed.to_string());
+});
@@ -0,0 +1,55 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Parses arbitrary input with a combination of options picked by the first byte, including
+// small limits, so the syntax extensions and the limit checks are exercised as well.
+//
+//     cargo +nightly fuzz run json_parse_relaxed
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use serenity::json::{self, DuplicateKeys, ParseOptions};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&flags, input)) = data.split_first() else {
+        return;
+    };
+    let Ok(input) = std::str::from_utf8(input) else {
+        return;
+    };
+
+    let mut options = ParseOptions {
+        allow_comments: flags & 0x01 != 0,
+        allow_trailing_commas: flags & 0x02 != 0,
+        allow_single_quotes: flags & 0x04 != 0,
+        allow_hex_numbers: flags & 0x08 != 0,
+        allow_unquoted_keys: flags & 0x10 != 0,
+        raw_numbers: flags & 0x20 != 0,
+        ..ParseOptions::default()
+    };
+    if flags & 0x40 != 0 {
+        options = options
+            .with_max_depth(4)
+            .with_max_string_length(16)
+            .with_max_elements(32);
+    }
+    if flags & 0x80 != 0 {
+        options = options.with_duplicate_keys(DuplicateKeys::Error);
+    }
+
+    // Whatever was accepted is serialized as strict JSON, which the default options must accept.
+    if let Ok(value) = json::parse_with_options(input, &options) {
+        let serialized = value.to_string();
+        let strict = ParseOptions {
+            raw_numbers: options.raw_numbers,
+            ..ParseOptions::default()
+        };
+        let reparsed = json::parse_with_options(&serialized, &strict)
+            .expect("serialized output does not parse");
+        assert_eq!(serialized, reparsed.to_string());
+    }
+});
@@ -0,0 +1,36 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Feeds arbitrary input to json::Reader and checks that it agrees with json::parse() on which
+// documents are valid and what they contain.
+//
+//     cargo +nightly fuzz run json_reader
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use serenity::json::{self, Reader};
+
+fuzz_target!(|data: &[u8]| {
+    let mut reader = Reader::new(data);
+    let read = match reader.read_value() {
+        // The document has to end after the value, as json::parse() requires.
+        Ok(Some(value)) => match reader.next_event() {
+            Ok(None) => Some(value),
+            _ => None,
+        },
+        _ => None,
+    };
+    let parsed = std::str::from_utf8(data)
+        .ok()
+        .and_then(|input| json::parse(input).ok());
+    match (read, parsed) {
+        (Some(read), Some(parsed)) => assert_eq!(read.to_string(), parsed.to_string()),
+        (Some(_), None) => panic!("only the reader accepted the input"),
+        (None, Some(_)) => panic!("only the parser accepted the input"),
+        (None, None) => {}
+    }
+});
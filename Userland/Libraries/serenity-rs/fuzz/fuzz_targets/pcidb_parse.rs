@@ -0,0 +1,34 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Parses arbitrary input as a pci.ids database. Anything that parses has to answer lookups
+// without panicking, whether or not the IDs are in it.
+//
+//     cargo +nightly fuzz run pcidb_parse
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use serenity::pcidb::Database;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(input) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(database) = Database::parse(input) else {
+        return;
+    };
+    for id in [0x0000, 0x1af4, 0x8086, 0xffff] {
+        let _ = database.vendor(id);
+        let _ = database.device(id, id);
+        let _ = database.subsystem(id, id, id, id);
+    }
+    for id in [0x00, 0x01, 0x06, 0xff] {
+        let _ = database.class(id);
+        let _ = database.subclass(id, id);
+        let _ = database.programming_interface(id, id, id);
+    }
+});
@@ -0,0 +1,85 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Fluent construction of objects and arrays, in the spirit of AK's JsonObjectSerializer:
+//
+//     let process = ObjectBuilder::new()
+//         .insert("pid", pid)
+//         .insert("name", name)
+//         .insert("threads", ArrayBuilder::new().push(thread_one).push(thread_two))
+//         .build();
+
+use super::{Map, Value};
+
+#[derive(Clone, Debug, Default)]
+pub struct ObjectBuilder {
+    object: Map,
+}
+
+impl ObjectBuilder {
+    pub fn new() -> ObjectBuilder { ObjectBuilder::default() }
+
+    pub fn with_capacity(capacity: usize) -> ObjectBuilder {
+        ObjectBuilder {
+            object: Map::with_capacity(capacity),
+        }
+    }
+
+    // Adding a key that is already present replaces its value but keeps its position.
+    pub fn insert(mut self, key: impl Into<String>, value: impl Into<Value>) -> ObjectBuilder {
+        self.object.insert(key.into(), value.into());
+        self
+    }
+
+    // Adds the member only if there is a value, instead of writing null.
+    pub fn insert_optional<V: Into<Value>>(
+        self,
+        key: impl Into<String>,
+        value: Option<V>,
+    ) -> ObjectBuilder {
+        match value {
+            Some(value) => self.insert(key, value),
+            None => self,
+        }
+    }
+
+    pub fn build(self) -> Value { Value::Object(self.object) }
+}
+
+impl From<ObjectBuilder> for Value {
+    fn from(builder: ObjectBuilder) -> Value { builder.build() }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ArrayBuilder {
+    array: Vec<Value>,
+}
+
+impl ArrayBuilder {
+    pub fn new() -> ArrayBuilder { ArrayBuilder::default() }
+
+    pub fn with_capacity(capacity: usize) -> ArrayBuilder {
+        ArrayBuilder {
+            array: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn push(mut self, value: impl Into<Value>) -> ArrayBuilder {
+        self.array.push(value.into());
+        self
+    }
+
+    pub fn extend<V: Into<Value>>(mut self, values: impl IntoIterator<Item = V>) -> ArrayBuilder {
+        self.array.extend(values.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn build(self) -> Value { Value::Array(self.array) }
+}
+
+impl From<ArrayBuilder> for Value {
+    fn from(builder: ArrayBuilder) -> Value { builder.build() }
+}
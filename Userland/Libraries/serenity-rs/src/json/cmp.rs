@@ -0,0 +1,315 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Equality, ordering and hashing of values.
+//
+// Numbers are compared by their exact mathematical value, so 1, 1.0, 1e0 and a u64 1 are all equal
+// and hash alike, while raw numbers that round to the same f64, like 1e400 and 2e400, do not. To
+// make the order total, NaN and the infinities (which can only get in through Number::from) sort
+// after and before every finite number, NaN is equal to itself, and -0.0 equals 0.
+//
+// Values of different types are ordered null < booleans < numbers < strings < arrays < objects.
+// Arrays are ordered element by element. Objects are ordered as if their members were sorted by
+// key, so member order affects neither equality, ordering nor the hash.
+
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+
+use super::decimal::Decimal;
+use super::pointer::escape_token;
+use super::{Map, Number, Value};
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Number) -> bool { self.cmp(other) == Ordering::Equal }
+}
+
+impl Eq for Number {}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Number) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Number {
+    fn cmp(&self, other: &Number) -> Ordering {
+        // Raw numbers are rare, so only they pay for exact decimal comparison.
+        if self.is_raw() || other.is_raw() {
+            return self.exact().cmp(&other.exact());
+        }
+        match (self.as_i128(), other.as_i128()) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(a), None) => compare_integer_with_float(a, other.float()),
+            (None, Some(b)) => compare_integer_with_float(b, self.float()).reverse(),
+            (None, None) => compare_floats(self.float(), other.float()),
+        }
+    }
+}
+
+// Equal numbers have to hash alike whatever their representation, so every float that equals an
+// integer is hashed as that integer, and every raw number that equals a float as that float.
+impl Hash for Number {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        if let Some(integer) = self.as_i128() {
+            return integer.hash(state);
+        }
+        let float = match self {
+            Number::Raw(string) => match Decimal::parse(string) {
+                Some(decimal) => match decimal.to_f64() {
+                    Some(float) => float,
+                    None => return decimal.hash(state),
+                },
+                None => f64::NAN,
+            },
+            _ => self.float(),
+        };
+        if float.fract() == 0.0 && float >= i128::MIN as f64 && float < i128::MAX as f64 {
+            (float as i128).hash(state);
+        } else if float.is_nan() {
+            f64::NAN.to_bits().hash(state);
+        } else {
+            float.to_bits().hash(state);
+        }
+    }
+}
+
+// A number's exact value, ordered like numbers are.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum Exact {
+    NegativeInfinity,
+    Finite(Decimal),
+    Infinity,
+    NotANumber,
+}
+
+impl Number {
+    // Raw numbers are valid JSON numbers and always parse, at worst to an infinity.
+    fn float(&self) -> f64 { self.as_f64().unwrap_or(f64::NAN) }
+
+    fn exact(&self) -> Exact {
+        let float = match self {
+            Number::Integer64(value) => {
+                return Exact::Finite(Decimal::parse(&value.to_string()).unwrap())
+            }
+            Number::UInt64(value) => {
+                return Exact::Finite(Decimal::parse(&value.to_string()).unwrap())
+            }
+            Number::Raw(string) => {
+                return Decimal::parse(string).map_or(Exact::NotANumber, Exact::Finite)
+            }
+            Number::Float64(value) => *value,
+        };
+        if float.is_nan() {
+            Exact::NotANumber
+        } else if float == f64::INFINITY {
+            Exact::Infinity
+        } else if float == f64::NEG_INFINITY {
+            Exact::NegativeInfinity
+        } else {
+            Exact::Finite(Decimal::from_f64(float))
+        }
+    }
+}
+
+fn compare_integer_with_float(integer: i128, float: f64) -> Ordering {
+    if float.is_nan() {
+        return Ordering::Less;
+    }
+    // Floats beyond the range of i128 are larger or smaller than any integer it can hold.
+    if float >= i128::MAX as f64 {
+        return Ordering::Less;
+    }
+    if float < i128::MIN as f64 {
+        return Ordering::Greater;
+    }
+    match integer.cmp(&(float.trunc() as i128)) {
+        Ordering::Equal => 0.0.partial_cmp(&float.fract()).unwrap_or(Ordering::Equal),
+        ordering => ordering,
+    }
+}
+
+fn compare_floats(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+    }
+}
+
+impl PartialEq for Map {
+    fn eq(&self, other: &Map) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl Eq for Map {}
+
+impl PartialOrd for Map {
+    fn partial_cmp(&self, other: &Map) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Map {
+    fn cmp(&self, other: &Map) -> Ordering { sorted_members(self).cmp(&sorted_members(other)) }
+}
+
+impl Hash for Map {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let members = sorted_members(self);
+        members.len().hash(state);
+        for (key, value) in members {
+            key.hash(state);
+            value.hash(state);
+        }
+    }
+}
+
+fn sorted_members(map: &Map) -> Vec<(&String, &Value)> {
+    let mut members: Vec<_> = map.iter().collect();
+    members.sort_unstable_by_key(|(key, _)| *key);
+    members
+}
+
+impl Value {
+    fn type_rank(&self) -> u8 {
+        match self {
+            Value::Null => 0,
+            Value::Bool(_) => 1,
+            Value::Number(_) => 2,
+            Value::String(_) => 3,
+            Value::Array(_) => 4,
+            Value::Object(_) => 5,
+        }
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Value) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Value {
+    fn cmp(&self, other: &Value) -> Ordering {
+        match (self, other) {
+            (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+            (Value::Number(a), Value::Number(b)) => a.cmp(b),
+            (Value::String(a), Value::String(b)) => a.cmp(b),
+            (Value::Array(a), Value::Array(b)) => a.cmp(b),
+            (Value::Object(a), Value::Object(b)) => a.cmp(b),
+            _ => self.type_rank().cmp(&other.type_rank()),
+        }
+    }
+}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.type_rank().hash(state);
+        match self {
+            Value::Null => {}
+            Value::Bool(value) => value.hash(state),
+            Value::Number(number) => number.hash(state),
+            Value::String(string) => string.hash(state),
+            Value::Array(array) => array.hash(state),
+            Value::Object(object) => object.hash(state),
+        }
+    }
+}
+
+// One place where two values differ, as reported by differences().
+#[derive(Clone, Debug, PartialEq)]
+pub struct Difference<'a> {
+    // A JSON Pointer to the differing value.
+    pub pointer: String,
+    // None if the value only exists on the other side.
+    pub left: Option<&'a Value>,
+    pub right: Option<&'a Value>,
+}
+
+impl Display for Difference<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let describe = |value: Option<&Value>| match value {
+            Some(value) => value.to_string(),
+            None => "(missing)".to_string(),
+        };
+        let pointer = if self.pointer.is_empty() {
+            "(root)"
+        } else {
+            &self.pointer
+        };
+        write!(
+            f,
+            "{}: {} != {}",
+            pointer,
+            describe(self.left),
+            describe(self.right)
+        )
+    }
+}
+
+// Lists where two values differ, for test failures that point at the problem instead of dumping
+// both documents. Objects are compared member by member and arrays position by position, and the
+// list is empty exactly when the values are equal. See also assert_json_eq!.
+pub fn differences<'a>(left: &'a Value, right: &'a Value) -> Vec<Difference<'a>> {
+    let mut differences = Vec::new();
+    collect_differences(String::new(), left, right, &mut differences);
+    differences
+}
+
+fn collect_differences<'a>(
+    pointer: String,
+    left: &'a Value,
+    right: &'a Value,
+    differences: &mut Vec<Difference<'a>>,
+) {
+    match (left, right) {
+        (Value::Object(left), Value::Object(right)) => {
+            for (key, left_value) in left {
+                let member = format!("{}/{}", pointer, escape_token(key));
+                match right.get(key) {
+                    Some(right_value) => {
+                        collect_differences(member, left_value, right_value, differences)
+                    }
+                    None => differences.push(Difference {
+                        pointer: member,
+                        left: Some(left_value),
+                        right: None,
+                    }),
+                }
+            }
+            for (key, right_value) in right {
+                if !left.contains_key(key) {
+                    differences.push(Difference {
+                        pointer: format!("{}/{}", pointer, escape_token(key)),
+                        left: None,
+                        right: Some(right_value),
+                    });
+                }
+            }
+        }
+        (Value::Array(left), Value::Array(right)) => {
+            for index in 0..left.len().max(right.len()) {
+                let element = format!("{}/{}", pointer, index);
+                match (left.get(index), right.get(index)) {
+                    (Some(left_value), Some(right_value)) => {
+                        collect_differences(element, left_value, right_value, differences)
+                    }
+                    (left, right) => differences.push(Difference {
+                        pointer: element,
+                        left,
+                        right,
+                    }),
+                }
+            }
+        }
+        _ if left == right => {}
+        _ => differences.push(Difference {
+            pointer,
+            left: Some(left),
+            right: Some(right),
+        }),
+    }
+}
@@ -0,0 +1,153 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Conversions from Rust types into Number and Value, used by the builders and the json! macro.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+
+use super::{Map, Number, Value};
+
+macro_rules! from_signed {
+    ($($type:ty)*) => {
+        $(
+            impl From<$type> for Number {
+                fn from(value: $type) -> Number { Number::Integer64(value as i64) }
+            }
+        )*
+    };
+}
+
+// Unsigned values that fit are stored as Integer64, so equal numbers share a representation.
+macro_rules! from_unsigned {
+    ($($type:ty)*) => {
+        $(
+            impl From<$type> for Number {
+                fn from(value: $type) -> Number {
+                    match i64::try_from(value) {
+                        Ok(value) => Number::Integer64(value),
+                        Err(_) => Number::UInt64(value as u64),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_signed!(i8 i16 i32 i64 isize);
+from_unsigned!(u8 u16 u32 u64 usize);
+
+impl From<f32> for Number {
+    fn from(value: f32) -> Number { Number::Float64(value as f64) }
+}
+
+impl From<f64> for Number {
+    fn from(value: f64) -> Number { Number::Float64(value) }
+}
+
+macro_rules! value_from_number {
+    ($($type:ty)*) => {
+        $(
+            impl From<$type> for Value {
+                fn from(value: $type) -> Value { Value::Number(Number::from(value)) }
+            }
+        )*
+    };
+}
+
+value_from_number!(i8 i16 i32 i64 isize u8 u16 u32 u64 usize f32 f64);
+
+impl From<Number> for Value {
+    fn from(number: Number) -> Value { Value::Number(number) }
+}
+
+impl From<()> for Value {
+    fn from(_: ()) -> Value { Value::Null }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Value { Value::Bool(value) }
+}
+
+impl From<char> for Value {
+    fn from(value: char) -> Value { Value::String(value.to_string()) }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Value { Value::String(value) }
+}
+
+impl From<&String> for Value {
+    fn from(value: &String) -> Value { Value::String(value.clone()) }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Value { Value::String(value.to_string()) }
+}
+
+impl From<Cow<'_, str>> for Value {
+    fn from(value: Cow<'_, str>) -> Value { Value::String(value.into_owned()) }
+}
+
+impl From<Map> for Value {
+    fn from(object: Map) -> Value { Value::Object(object) }
+}
+
+// None becomes null.
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Value { value.map_or(Value::Null, Into::into) }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(elements: Vec<T>) -> Value {
+        Value::Array(elements.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<Value>, const N: usize> From<[T; N]> for Value {
+    fn from(elements: [T; N]) -> Value {
+        Value::Array(elements.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Clone + Into<Value>> From<&[T]> for Value {
+    fn from(elements: &[T]) -> Value {
+        Value::Array(elements.iter().cloned().map(Into::into).collect())
+    }
+}
+
+// HashMap has no order of its own, so its members are sorted by key to keep output stable.
+impl<K: Into<String>, V: Into<Value>, S> From<HashMap<K, V, S>> for Value {
+    fn from(members: HashMap<K, V, S>) -> Value {
+        let mut members = members
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.into()))
+            .collect::<Vec<(String, Value)>>();
+        members.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Value::Object(members.into_iter().collect())
+    }
+}
+
+impl<K: Into<String>, V: Into<Value>> From<BTreeMap<K, V>> for Value {
+    fn from(members: BTreeMap<K, V>) -> Value { members.into_iter().collect() }
+}
+
+impl<T: Into<Value>> FromIterator<T> for Value {
+    fn from_iter<I: IntoIterator<Item = T>>(iterator: I) -> Value {
+        Value::Array(iterator.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<K: Into<String>, V: Into<Value>> FromIterator<(K, V)> for Value {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iterator: I) -> Value {
+        Value::Object(
+            iterator
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        )
+    }
+}
@@ -0,0 +1,204 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Exact decimal values, for comparing raw numbers with each other and with the other
+// representations without rounding any of them to an f64 first.
+
+use std::cmp::Ordering;
+
+// The value ±0.d₁d₂…dₙ × 10^exponent. The digits have no leading or trailing zeros, and zero has
+// no digits and is never negative, so every value has exactly one form and the derived equality
+// and hash are by value.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(super) struct Decimal {
+    negative: bool,
+    digits: Vec<u8>,
+    exponent: i64,
+}
+
+// Exponents are clamped to this, far beyond anything that could still be compared digit by digit.
+const MAX_EXPONENT: i64 = 1 << 60;
+
+impl Decimal {
+    // Expects JSON number syntax, and returns None for anything else.
+    pub(super) fn parse(string: &str) -> Option<Decimal> {
+        let (negative, unsigned) = match string.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, string),
+        };
+        let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+            Some(index) => (&unsigned[..index], parse_exponent(&unsigned[index + 1..])?),
+            None => (unsigned, 0),
+        };
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if integer.is_empty()
+            || !integer
+                .bytes()
+                .chain(fraction.bytes())
+                .all(|byte| byte.is_ascii_digit())
+        {
+            return None;
+        }
+        let digits = integer
+            .bytes()
+            .chain(fraction.bytes())
+            .map(|byte| byte - b'0')
+            .collect();
+        Some(Decimal::new(
+            negative,
+            digits,
+            exponent.saturating_add(integer.len() as i64),
+        ))
+    }
+
+    // The exact value of a finite float, which may take hundreds of digits.
+    pub(super) fn from_f64(value: f64) -> Decimal {
+        debug_assert!(value.is_finite());
+        let bits = value.to_bits();
+        let biased_exponent = ((bits >> 52) & 0x7ff) as i64;
+        let fraction = bits & ((1 << 52) - 1);
+        // value = mantissa × 2^binary_exponent
+        let (mantissa, binary_exponent) = if biased_exponent == 0 {
+            (fraction, -1074)
+        } else {
+            (fraction | (1 << 52), biased_exponent - 1075)
+        };
+
+        // Little-endian digits of the integer mantissa × 2^binary_exponent, or of
+        // mantissa × 5^-binary_exponent, which is the value × 10^-binary_exponent.
+        let mut digits = mantissa
+            .to_string()
+            .bytes()
+            .rev()
+            .map(|byte| byte - b'0')
+            .collect::<Vec<_>>();
+        let (factor, power) = if binary_exponent >= 0 {
+            (2, 0)
+        } else {
+            (5, binary_exponent)
+        };
+        for _ in 0..binary_exponent.unsigned_abs() {
+            let mut carry = 0;
+            for digit in digits.iter_mut() {
+                let product = *digit * factor + carry;
+                *digit = product % 10;
+                carry = product / 10;
+            }
+            if carry > 0 {
+                digits.push(carry);
+            }
+        }
+        digits.reverse();
+        let exponent = digits.len() as i64 + power;
+        Decimal::new(value.is_sign_negative(), digits, exponent)
+    }
+
+    fn new(negative: bool, mut digits: Vec<u8>, mut exponent: i64) -> Decimal {
+        let leading_zeros = digits.iter().take_while(|digit| **digit == 0).count();
+        digits.drain(..leading_zeros);
+        exponent = exponent.saturating_sub(leading_zeros as i64);
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        if digits.is_empty() {
+            return Decimal {
+                negative: false,
+                digits,
+                exponent: 0,
+            };
+        }
+        Decimal {
+            negative,
+            digits,
+            exponent: exponent.clamp(-MAX_EXPONENT, MAX_EXPONENT),
+        }
+    }
+
+    pub(super) fn is_integer(&self) -> bool { self.exponent >= self.digits.len() as i64 }
+
+    pub(super) fn to_i128(&self) -> Option<i128> {
+        if !self.is_integer() || self.exponent > 39 {
+            return None;
+        }
+        // Accumulated as a negative number, as i128::MIN has no positive counterpart.
+        let mut value: i128 = 0;
+        for position in 0..self.exponent as usize {
+            let digit = self.digits.get(position).copied().unwrap_or(0);
+            value = value.checked_mul(10)?.checked_sub(digit as i128)?;
+        }
+        if self.negative {
+            Some(value)
+        } else {
+            value.checked_neg()
+        }
+    }
+
+    // The float with exactly this value, if there is one.
+    pub(super) fn to_f64(&self) -> Option<f64> {
+        if self.digits.is_empty() {
+            return Some(0.0);
+        }
+        let digits = self
+            .digits
+            .iter()
+            .map(|digit| char::from(b'0' + digit))
+            .collect::<String>();
+        let sign = if self.negative { "-" } else { "" };
+        let value: f64 = format!("{}0.{}e{}", sign, digits, self.exponent)
+            .parse()
+            .ok()?;
+        (value.is_finite() && Decimal::from_f64(value) == *self).then_some(value)
+    }
+
+    fn sign(&self) -> i8 {
+        match (self.digits.is_empty(), self.negative) {
+            (true, _) => 0,
+            (false, true) => -1,
+            (false, false) => 1,
+        }
+    }
+}
+
+fn parse_exponent(string: &str) -> Option<i64> {
+    let (negative, digits) = match string.as_bytes().first() {
+        Some(b'-') => (true, &string[1..]),
+        Some(b'+') => (false, &string[1..]),
+        _ => (false, string),
+    };
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    // Too many digits for an i64 means too large to matter.
+    let magnitude = digits.parse::<i64>().unwrap_or(MAX_EXPONENT);
+    let magnitude = magnitude.min(MAX_EXPONENT);
+    Some(if negative { -magnitude } else { magnitude })
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Decimal) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Decimal) -> Ordering {
+        let sign = self.sign();
+        match sign.cmp(&other.sign()) {
+            Ordering::Equal if sign == 0 => return Ordering::Equal,
+            Ordering::Equal => {}
+            ordering => return ordering,
+        }
+        // With equal signs, a larger exponent means a larger magnitude, and with equal exponents
+        // the digits decide, a missing digit counting as a zero.
+        let magnitude = self
+            .exponent
+            .cmp(&other.exponent)
+            .then_with(|| self.digits.cmp(&other.digits));
+        if self.negative {
+            magnitude.reverse()
+        } else {
+            magnitude
+        }
+    }
+}
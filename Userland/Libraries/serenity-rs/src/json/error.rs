@@ -0,0 +1,158 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug)]
+pub enum ParseErrorKind {
+    NotImplemented,
+    // One of the limits in ParseOptions was exceeded. The limit is included.
+    DepthLimitExceeded(usize),
+    StringTooLong(usize),
+    TooManyElements(usize),
+    DuplicateKey(String),
+    ExpectedCharacter(char),
+    ExpectedFalse,
+    ExpectedNull,
+    ExpectedTrue,
+    InvalidNumber,
+    InvalidUtf8,
+    LoneSurrogate(u16),
+    MultiplePeriodsInNumber,
+    // A number that overflows an f64 to infinity or underflows a nonzero one to zero. Subnormals
+    // are kept. With ParseOptions::raw_numbers, these are kept as raw numbers instead.
+    NumberOutOfRange,
+    UnexpectedCharacter(char),
+    UnexpectedControlCharacter,
+    UnexpectedEof,
+}
+
+impl Display for ParseErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseErrorKind::NotImplemented => write!(f, "not implemented"),
+            ParseErrorKind::DepthLimitExceeded(limit) => {
+                write!(f, "nesting exceeds the maximum depth of {}", limit)
+            }
+            ParseErrorKind::StringTooLong(limit) => {
+                write!(f, "string exceeds the maximum length of {} bytes", limit)
+            }
+            ParseErrorKind::TooManyElements(limit) => {
+                write!(f, "document exceeds the maximum of {} elements", limit)
+            }
+            ParseErrorKind::DuplicateKey(key) => {
+                write!(f, "duplicate key \"{}\"", key.escape_debug())
+            }
+            ParseErrorKind::ExpectedCharacter(ch) => write!(f, "expected '{}'", ch.escape_debug()),
+            ParseErrorKind::ExpectedFalse => write!(f, "expected 'false'"),
+            ParseErrorKind::ExpectedNull => write!(f, "expected 'null'"),
+            ParseErrorKind::ExpectedTrue => write!(f, "expected 'true'"),
+            ParseErrorKind::InvalidNumber => write!(f, "invalid number"),
+            ParseErrorKind::InvalidUtf8 => write!(f, "invalid UTF-8 in string"),
+            ParseErrorKind::LoneSurrogate(code_unit) => {
+                write!(f, "lone surrogate \\u{:04x} in string", code_unit)
+            }
+            ParseErrorKind::MultiplePeriodsInNumber => write!(f, "multiple periods in number"),
+            ParseErrorKind::NumberOutOfRange => write!(f, "number out of range"),
+            ParseErrorKind::UnexpectedCharacter(ch) => {
+                write!(f, "unexpected character '{}'", ch.escape_debug())
+            }
+            ParseErrorKind::UnexpectedControlCharacter => {
+                write!(f, "unexpected control character in string")
+            }
+            ParseErrorKind::UnexpectedEof => write!(f, "unexpected end of input"),
+        }
+    }
+}
+
+// How much of a long line is shown on either side of the error.
+const EXCERPT_CONTEXT: usize = 40;
+
+#[derive(Debug)]
+pub struct ParseError {
+    kind: ParseErrorKind,
+    offset: usize,
+    line: usize,
+    column: usize,
+    // The offending line (or part of it) and the column of the error within it.
+    excerpt: Option<(String, usize)>,
+}
+
+impl ParseError {
+    pub(crate) fn new(kind: ParseErrorKind, input: &str, offset: usize) -> ParseError {
+        let offset = offset.min(input.len());
+        let line_start = input[..offset].rfind('\n').map_or(0, |index| index + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |index| offset + index);
+        let line = input[..line_start].matches('\n').count() + 1;
+        let column = input[line_start..offset].chars().count() + 1;
+
+        // Tabs and carriage returns would throw off the caret, so show them as spaces.
+        let chars = input[line_start..line_end]
+            .trim_end_matches('\r')
+            .chars()
+            .map(|ch| if ch.is_control() { ' ' } else { ch })
+            .collect::<Vec<_>>();
+        // Trimming carriage returns can leave the line shorter than the column of the error.
+        let first = (column - 1)
+            .saturating_sub(EXCERPT_CONTEXT)
+            .min(chars.len());
+        let last = (column - 1 + EXCERPT_CONTEXT).min(chars.len());
+        let excerpt = chars[first..last.max(first)].iter().collect();
+
+        ParseError {
+            kind,
+            offset,
+            line,
+            column,
+            excerpt: Some((excerpt, column - 1 - first)),
+        }
+    }
+
+    // For input that is not available as a whole, like a stream.
+    pub(crate) fn without_excerpt(
+        kind: ParseErrorKind,
+        offset: usize,
+        line: usize,
+        column: usize,
+    ) -> ParseError {
+        ParseError {
+            kind,
+            offset,
+            line,
+            column,
+            excerpt: None,
+        }
+    }
+
+    pub fn kind(&self) -> &ParseErrorKind { &self.kind }
+
+    // Byte offset into the input.
+    pub fn offset(&self) -> usize { self.offset }
+
+    // 1-based line number.
+    pub fn line(&self) -> usize { self.line }
+
+    // 1-based column, counted in characters.
+    pub fn column(&self) -> usize { self.column }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.kind, self.line, self.column
+        )?;
+        if let Some((excerpt, excerpt_column)) = &self.excerpt {
+            write!(f, "\n    {}\n    {:>2$}", excerpt, "^", excerpt_column + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -0,0 +1,73 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+use super::Value;
+
+// Something that can look up a member of a Value: a key for objects, or a position for arrays.
+pub trait ValueIndex {
+    fn index_into<'a>(&self, value: &'a Value) -> Option<&'a Value>;
+    fn index_into_mut<'a>(&self, value: &'a mut Value) -> Option<&'a mut Value>;
+}
+
+impl ValueIndex for usize {
+    fn index_into<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        match value {
+            Value::Array(array) => array.get(*self),
+            _ => None,
+        }
+    }
+
+    fn index_into_mut<'a>(&self, value: &'a mut Value) -> Option<&'a mut Value> {
+        match value {
+            Value::Array(array) => array.get_mut(*self),
+            _ => None,
+        }
+    }
+}
+
+impl ValueIndex for str {
+    fn index_into<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        match value {
+            Value::Object(object) => object.get(self),
+            _ => None,
+        }
+    }
+
+    fn index_into_mut<'a>(&self, value: &'a mut Value) -> Option<&'a mut Value> {
+        match value {
+            Value::Object(object) => object.get_mut(self),
+            _ => None,
+        }
+    }
+}
+
+impl ValueIndex for String {
+    fn index_into<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        self.as_str().index_into(value)
+    }
+
+    fn index_into_mut<'a>(&self, value: &'a mut Value) -> Option<&'a mut Value> {
+        self.as_str().index_into_mut(value)
+    }
+}
+
+impl<T: ValueIndex + ?Sized> ValueIndex for &T {
+    fn index_into<'a>(&self, value: &'a Value) -> Option<&'a Value> { (**self).index_into(value) }
+
+    fn index_into_mut<'a>(&self, value: &'a mut Value) -> Option<&'a mut Value> {
+        (**self).index_into_mut(value)
+    }
+}
+
+// Indexing never panics: a missing member or a type mismatch yields Value::Null, so lookups
+// like value["processors"][0]["brand"] can be chained freely.
+static NULL: Value = Value::Null;
+
+impl<I: ValueIndex> std::ops::Index<I> for Value {
+    type Output = Value;
+
+    fn index(&self, index: I) -> &Value { index.index_into(self).unwrap_or(&NULL) }
+}
@@ -0,0 +1,146 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Builds a Value from JSON-like syntax:
+//
+//     let value = json!({
+//         "purpose": name,
+//         "count": 3,
+//         "tags": ["a", "b"],
+//         "parent": null,
+//     });
+//
+// Keys are string expressions, and must be parenthesized unless they are a single token.
+// Values are either nested JSON syntax or Rust expressions, which are converted with Value::from.
+#[macro_export]
+macro_rules! json {
+    ($($json:tt)+) => {
+        $crate::json_internal!($($json)+)
+    };
+}
+
+// The object and array rules munch one token at a time, as a value expression can only be
+// recognized once the comma that ends it has been seen.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! json_internal {
+    // Arrays. The first bracket holds the elements parsed so far.
+    (@array [$($elements:expr,)*]) => {
+        vec![$($elements,)*]
+    };
+    (@array [$($elements:expr),*]) => {
+        vec![$($elements),*]
+    };
+    (@array [$($elements:expr,)*] null $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elements,)* $crate::json_internal!(null)] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] true $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elements,)* $crate::json_internal!(true)] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] false $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elements,)* $crate::json_internal!(false)] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] [$($array:tt)*] $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elements,)* $crate::json_internal!([$($array)*])] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] {$($object:tt)*} $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elements,)* $crate::json_internal!({$($object)*})] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elements,)* $crate::json_internal!($next),] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] $last:expr) => {
+        $crate::json_internal!(@array [$($elements,)* $crate::json_internal!($last)])
+    };
+    (@array [$($elements:expr),*] , $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elements,)*] $($rest)*)
+    };
+
+    // Objects. The parentheses hold the key tokens seen so far, the brackets a complete key.
+    (@object $object:ident () ()) => {};
+    (@object $object:ident [$($key:tt)+] ($value:expr) , $($rest:tt)*) => {
+        let _ = $object.insert(($($key)+).into(), $value);
+        $crate::json_internal!(@object $object () ($($rest)*));
+    };
+    (@object $object:ident [$($key:tt)+] ($value:expr)) => {
+        let _ = $object.insert(($($key)+).into(), $value);
+    };
+    (@object $object:ident ($($key:tt)+) (: null $($rest:tt)*)) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!(null)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: true $($rest:tt)*)) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!(true)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: false $($rest:tt)*)) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!(false)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: [$($array:tt)*] $($rest:tt)*)) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!([$($array)*])) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: {$($inner:tt)*} $($rest:tt)*)) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!({$($inner)*})) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*)) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!($value)) , $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: $value:expr)) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!($value)));
+    };
+    (@object $object:ident () (($key:expr) : $($rest:tt)*)) => {
+        $crate::json_internal!(@object $object ($key) (: $($rest)*));
+    };
+    (@object $object:ident ($($key:tt)*) ($next:tt $($rest:tt)*)) => {
+        $crate::json_internal!(@object $object ($($key)* $next) ($($rest)*));
+    };
+
+    (null) => {
+        $crate::json::Value::Null
+    };
+    (true) => {
+        $crate::json::Value::Bool(true)
+    };
+    (false) => {
+        $crate::json::Value::Bool(false)
+    };
+    ([]) => {
+        $crate::json::Value::Array(vec![])
+    };
+    ([ $($tt:tt)+ ]) => {
+        $crate::json::Value::Array($crate::json_internal!(@array [] $($tt)+))
+    };
+    ({}) => {
+        $crate::json::Value::Object($crate::json::Map::new())
+    };
+    ({ $($tt:tt)+ }) => {
+        $crate::json::Value::Object({
+            let mut object = $crate::json::Map::new();
+            $crate::json_internal!(@object object () ($($tt)+));
+            object
+        })
+    };
+    ($other:expr) => {
+        $crate::json::Value::from($other)
+    };
+}
+
+// Like assert_eq!, but for two Values. On failure, lists where they differ by JSON Pointer rather
+// than printing both of them:
+//
+//     assert_json_eq!(json::parse(input)?, json!({ "pid": 1, "name": "init" }));
+#[macro_export]
+macro_rules! assert_json_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                let differences = $crate::json::differences(left, right);
+                if !differences.is_empty() {
+                    let lines: Vec<String> = differences.iter().map(|d| d.to_string()).collect();
+                    panic!("JSON values are not equal:\n    {}", lines.join("\n    "));
+                }
+            }
+        }
+    };
+}
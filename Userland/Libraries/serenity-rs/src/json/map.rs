@@ -0,0 +1,228 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// The member storage of Value::Object. Members are kept in the order they were inserted, so
+// parsing and serializing a document does not shuffle it, and output is the same on every run.
+
+use std::collections::HashMap;
+
+use super::Value;
+
+// Small objects are searched linearly. Larger ones get a hash index, so building an object with
+// many members does not take quadratic time.
+const INDEX_THRESHOLD: usize = 8;
+
+#[derive(Clone, Default)]
+pub struct Map {
+    entries: Vec<(String, Value)>,
+    indices: Option<HashMap<String, usize>>,
+}
+
+impl Map {
+    pub fn new() -> Map { Map::default() }
+
+    pub fn with_capacity(capacity: usize) -> Map {
+        Map {
+            entries: Vec::with_capacity(capacity),
+            indices: None,
+        }
+    }
+
+    pub fn len(&self) -> usize { self.entries.len() }
+
+    pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+    pub fn contains_key(&self, key: &str) -> bool { self.position(key).is_some() }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.position(key).map(|index| &self.entries[index].1)
+    }
+
+    pub fn get_key_value(&self, key: &str) -> Option<(&String, &Value)> {
+        self.position(key).map(|index| {
+            let (key, value) = &self.entries[index];
+            (key, value)
+        })
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Value> {
+        self.position(key).map(|index| &mut self.entries[index].1)
+    }
+
+    // Returns the member at the given position in insertion order.
+    pub fn get_index(&self, index: usize) -> Option<(&String, &Value)> {
+        self.entries.get(index).map(|(key, value)| (key, value))
+    }
+
+    // A new key is appended. An existing key keeps its position, and its previous value is
+    // returned.
+    pub fn insert(&mut self, key: String, value: Value) -> Option<Value> {
+        if let Some(index) = self.position(&key) {
+            return Some(std::mem::replace(&mut self.entries[index].1, value));
+        }
+        if let Some(indices) = &mut self.indices {
+            indices.insert(key.clone(), self.entries.len());
+        }
+        self.entries.push((key, value));
+        if self.indices.is_none() && self.entries.len() > INDEX_THRESHOLD {
+            self.rebuild_indices();
+        }
+        None
+    }
+
+    // Removes a member and shifts the ones after it, so the order of the others is kept.
+    pub fn remove(&mut self, key: &str) -> Option<Value> {
+        let index = self.position(key)?;
+        let (_, value) = self.entries.remove(index);
+        self.rebuild_indices();
+        Some(value)
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(&String, &mut Value) -> bool) {
+        self.entries.retain_mut(|(key, value)| keep(key, value));
+        self.rebuild_indices();
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.indices = None;
+    }
+
+    pub fn sort_keys(&mut self) {
+        self.entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        self.rebuild_indices();
+    }
+
+    pub fn iter(&self) -> Iter<'_> { Iter(self.entries.iter()) }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_> { IterMut(self.entries.iter_mut()) }
+
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &String> + ExactSizeIterator {
+        self.entries.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &Value> + ExactSizeIterator {
+        self.entries.iter().map(|(_, value)| value)
+    }
+
+    pub fn values_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = &mut Value> + ExactSizeIterator {
+        self.entries.iter_mut().map(|(_, value)| value)
+    }
+
+    fn position(&self, key: &str) -> Option<usize> {
+        match &self.indices {
+            Some(indices) => indices.get(key).copied(),
+            None => self.entries.iter().position(|(other, _)| other == key),
+        }
+    }
+
+    fn rebuild_indices(&mut self) {
+        self.indices = (self.entries.len() > INDEX_THRESHOLD).then(|| {
+            self.entries
+                .iter()
+                .enumerate()
+                .map(|(index, (key, _))| (key.clone(), index))
+                .collect()
+        });
+    }
+}
+
+impl std::fmt::Debug for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<(String, Value)> for Map {
+    fn from_iter<I: IntoIterator<Item = (String, Value)>>(iterator: I) -> Map {
+        let mut map = Map::new();
+        map.extend(iterator);
+        map
+    }
+}
+
+impl Extend<(String, Value)> for Map {
+    fn extend<I: IntoIterator<Item = (String, Value)>>(&mut self, iterator: I) {
+        for (key, value) in iterator {
+            self.insert(key, value);
+        }
+    }
+}
+
+pub struct Iter<'a>(std::slice::Iter<'a, (String, Value)>);
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a String, &'a Value);
+
+    fn next(&mut self) -> Option<Self::Item> { self.0.next().map(|(key, value)| (key, value)) }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.0.size_hint() }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(key, value)| (key, value))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+pub struct IterMut<'a>(std::slice::IterMut<'a, (String, Value)>);
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = (&'a String, &'a mut Value);
+
+    fn next(&mut self) -> Option<Self::Item> { self.0.next().map(|(key, value)| (&*key, value)) }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.0.size_hint() }
+}
+
+impl DoubleEndedIterator for IterMut<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back().map(|(key, value)| (&*key, value))
+    }
+}
+
+impl ExactSizeIterator for IterMut<'_> {}
+
+pub struct IntoIter(std::vec::IntoIter<(String, Value)>);
+
+impl Iterator for IntoIter {
+    type Item = (String, Value);
+
+    fn next(&mut self) -> Option<Self::Item> { self.0.next() }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.0.size_hint() }
+}
+
+impl DoubleEndedIterator for IntoIter {
+    fn next_back(&mut self) -> Option<Self::Item> { self.0.next_back() }
+}
+
+impl ExactSizeIterator for IntoIter {}
+
+impl<'a> IntoIterator for &'a Map {
+    type IntoIter = Iter<'a>;
+    type Item = (&'a String, &'a Value);
+
+    fn into_iter(self) -> Iter<'a> { self.iter() }
+}
+
+impl<'a> IntoIterator for &'a mut Map {
+    type IntoIter = IterMut<'a>;
+    type Item = (&'a String, &'a mut Value);
+
+    fn into_iter(self) -> IterMut<'a> { self.iter_mut() }
+}
+
+impl IntoIterator for Map {
+    type IntoIter = IntoIter;
+    type Item = (String, Value);
+
+    fn into_iter(self) -> IntoIter { IntoIter(self.entries.into_iter()) }
+}
@@ -4,389 +4,227 @@
  * SPDX-License-Identifier: BSD-2-Clause
  */
 
-use std::collections::HashMap;
-use std::iter::Peekable;
-use std::str::Chars;
-
-#[derive(Debug)]
+mod builder;
+mod cmp;
+mod convert;
+mod decimal;
+mod error;
+mod index;
+mod macros;
+pub mod map;
+mod parser;
+pub mod patch;
+pub mod pointer;
+pub mod query;
+pub mod reader;
+pub mod schema;
+#[cfg(feature = "serde")]
+pub mod serde;
+pub mod serializer;
+
+pub use builder::{ArrayBuilder, ObjectBuilder};
+pub use cmp::{differences, Difference};
+use decimal::Decimal;
+pub use error::{ParseError, ParseErrorKind};
+pub use index::ValueIndex;
+pub use map::Map;
+pub use parser::{parse, parse_with_options, DuplicateKeys, ParseOptions, DEFAULT_MAX_DEPTH};
+pub use reader::{Event, Reader, ReaderError};
+pub use serializer::{
+    serialize,
+    serialize_canonical,
+    serialize_pretty,
+    serialize_with_options,
+    SerializeOptions,
+};
+
+// Equality follows JSON semantics: numbers compare by value regardless of their representation,
+// and objects compare without regard to member order. Values are also totally ordered and hashable
+// consistently with that, so they can be kept in sets and used as map keys; see cmp.rs.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Value {
     Null,
     Number(Number),
     Bool(bool),
     String(String),
     Array(Vec<Value>),
-    Object(HashMap<String, Value>),
+    Object(Map),
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum Number {
     Integer64(i64),
+    // Only used for integers above i64::MAX. Everything else that fits is an Integer64.
+    UInt64(u64),
     Float64(f64),
+    // The original text of a number that cannot be held exactly by any of the above. Only
+    // produced when parsing with ParseOptions::raw_numbers, and serialized verbatim, so it has to
+    // be a valid JSON number.
+    Raw(String),
 }
 
-#[derive(Debug)]
-pub enum ParseError {
-    NotImplemented,
-    ExpectedCharacter(char),
-    ExpectedFalse,
-    ExpectedNull,
-    ExpectedTrue,
-    InvalidNumber,
-    MultiplePeriodsInNumber,
-    UnexpectedCharacter(char),
-    UnexpectedControlCharacter,
-    UnexpectedEof,
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let options = if f.alternate() {
+            SerializeOptions::pretty()
+        } else {
+            SerializeOptions::compact()
+        };
+        serializer::serialize_into(f, self, &options)
+    }
 }
 
-impl std::fmt::Display for ParseError {
-    fn fmt(&self, _: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> { todo!() }
-}
+impl Value {
+    pub fn is_null(&self) -> bool { matches!(self, Value::Null) }
 
-impl std::error::Error for ParseError {}
+    pub fn is_bool(&self) -> bool { matches!(self, Value::Bool(_)) }
 
-struct JsonParser<'a> {
-    input: &'a mut Peekable<Chars<'a>>,
-}
+    pub fn is_number(&self) -> bool { matches!(self, Value::Number(_)) }
 
-impl<'a> JsonParser<'a> {
-    fn parse(&mut self) -> Result<Value, ParseError> {
-        self.skip_whitespace();
-        match self.input.peek() {
-            Some('{') => self.parse_object(),
-            Some('[') => self.parse_array(),
-            Some('"') => self.parse_string(),
-            Some('0'..='9' | '-') => self.parse_number(),
-            Some('t') => self.parse_true(),
-            Some('f') => self.parse_false(),
-            Some('n') => self.parse_null(),
-            Some(ch) => Err(ParseError::UnexpectedCharacter(*ch)),
-            None => Err(ParseError::UnexpectedEof),
-        }
-    }
+    pub fn is_string(&self) -> bool { matches!(self, Value::String(_)) }
 
-    fn must_consume(&mut self, ch: char) -> Result<(), ParseError> {
-        match self.input.peek() {
-            Some(peek_ch) if ch == *peek_ch => {
-                self.input.next();
-                Ok(())
-            }
-            _ => Err(ParseError::ExpectedCharacter(ch)),
+    pub fn is_array(&self) -> bool { matches!(self, Value::Array(_)) }
+
+    pub fn is_object(&self) -> bool { matches!(self, Value::Object(_)) }
+
+    pub fn as_null(&self) -> Option<()> {
+        match self {
+            Value::Null => Some(()),
+            _ => None,
         }
     }
 
-    fn parse_object(&mut self) -> Result<Value, ParseError> {
-        self.must_consume('{')?;
-
-        let mut object = HashMap::new();
-
-        loop {
-            self.skip_whitespace();
-            let name = self.consume_and_unescape_string()?;
-            self.skip_whitespace();
-            self.must_consume(':')?;
-            self.skip_whitespace();
-            let value = self.parse()?;
-            object.insert(name, value);
-            self.skip_whitespace();
-            if let Some('}') = self.input.peek() {
-                break;
-            }
-            self.must_consume(',')?;
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(value) => Some(*value),
+            _ => None,
         }
-        self.skip_whitespace();
-        self.must_consume('}')?;
-        Ok(Value::Object(object))
     }
 
-    fn parse_array(&mut self) -> Result<Value, ParseError> {
-        self.must_consume('[')?;
-        let mut array = Vec::new();
-
-        loop {
-            self.skip_whitespace();
-            if self.next_is("]") {
-                break;
-            }
-            let element = self.parse()?;
-            array.push(element);
-            self.skip_whitespace();
-            if self.next_is("]") {
-                break;
-            }
-            self.must_consume(',')?;
-            self.skip_whitespace();
-            if self.next_is("]") {
-                return Err(ParseError::UnexpectedCharacter(']'));
-            }
+    pub fn as_number(&self) -> Option<&Number> {
+        match self {
+            Value::Number(number) => Some(number),
+            _ => None,
         }
-        self.skip_whitespace();
-        self.must_consume(']')?;
-        Ok(Value::Array(array))
     }
 
-    fn parse_number(&mut self) -> Result<Value, ParseError> {
-        let mut string = String::with_capacity(64);
-
-        let mut has_decimals = false;
-        let mut all_zero = true;
-
-        loop {
-            let ch = self.input.peek();
-            match ch {
-                Some('.') => {
-                    if has_decimals {
-                        return Err(ParseError::MultiplePeriodsInNumber);
-                    }
-                    has_decimals = true;
-                }
-                ch @ Some('-' | '0'..='9') => {
-                    let ch = *ch.unwrap();
-                    if ch != '-' && ch != '0' {
-                        all_zero = false;
-                    }
-                    if has_decimals {
-                        if ch == '-' {
-                            return Err(ParseError::InvalidNumber);
-                        }
-                    } else {
-                        if string.starts_with("0") || string.starts_with("-0") {
-                            return Err(ParseError::InvalidNumber);
-                        }
-                    }
-                }
-                _ => break,
-            }
-            string.push(*ch.unwrap());
-            self.input.next();
-        }
+    pub fn as_i64(&self) -> Option<i64> { self.as_number()?.as_i64() }
 
-        if string.starts_with("-") && all_zero {
-            return Ok(Value::Number(Number::Float64(-0.0)));
-        }
+    pub fn as_u64(&self) -> Option<u64> { self.as_number()?.as_u64() }
 
-        if has_decimals {
-            let result = string.parse::<f64>();
-            if result.is_err() {
-                Err(ParseError::InvalidNumber)
-            } else {
-                Ok(Value::Number(Number::Float64(result.unwrap())))
-            }
-        } else {
-            let result = string.parse::<i64>();
-            if result.is_err() {
-                Err(ParseError::InvalidNumber)
-            } else {
-                Ok(Value::Number(Number::Integer64(result.unwrap())))
-            }
-        }
-    }
+    pub fn as_f64(&self) -> Option<f64> { self.as_number()?.as_f64() }
 
-    fn parse_string(&mut self) -> Result<Value, ParseError> {
-        let string = self.consume_and_unescape_string()?;
-        Ok(Value::String(string))
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
     }
 
-    fn parse_true(&mut self) -> Result<Value, ParseError> {
-        if self.consume("true") {
-            Ok(Value::Bool(true))
-        } else {
-            Err(ParseError::ExpectedTrue)
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        match self {
+            Value::Array(array) => Some(array),
+            _ => None,
         }
     }
 
-    fn parse_false(&mut self) -> Result<Value, ParseError> {
-        if self.consume("false") {
-            Ok(Value::Bool(false))
-        } else {
-            Err(ParseError::ExpectedFalse)
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match self {
+            Value::Array(array) => Some(array),
+            _ => None,
         }
     }
 
-    fn parse_null(&mut self) -> Result<Value, ParseError> {
-        if self.consume("null") {
-            Ok(Value::Null)
-        } else {
-            Err(ParseError::ExpectedNull)
+    pub fn as_object(&self) -> Option<&Map> {
+        match self {
+            Value::Object(object) => Some(object),
+            _ => None,
         }
     }
 
-    fn skip_whitespace(&mut self) {
-        loop {
-            match self.input.peek() {
-                Some(' ' | '\n' | '\r' | '\t') => {
-                    self.input.next();
-                }
-                _ => break,
-            }
+    pub fn as_object_mut(&mut self) -> Option<&mut Map> {
+        match self {
+            Value::Object(object) => Some(object),
+            _ => None,
         }
     }
 
-    fn consume(&mut self, expected: &str) -> bool {
-        if !self.next_is(expected) {
-            return false;
-        }
-        for _ in 0..expected.len() {
-            self.input.next();
-        }
-        return true;
+    pub fn get<I: ValueIndex>(&self, index: I) -> Option<&Value> { index.index_into(self) }
+
+    pub fn get_mut<I: ValueIndex>(&mut self, index: I) -> Option<&mut Value> {
+        index.index_into_mut(self)
     }
 
-    fn next_is(&self, expected: &str) -> bool {
-        let mut lookahead_chars = self.input.clone();
-        let mut expected_chars = expected.chars();
-        loop {
-            let lookahead_character = lookahead_chars.next();
-            let expected_character = expected_chars.next();
-            if expected_character.is_none() {
-                return true;
-            }
-            if lookahead_character.is_none() {
-                return false;
-            }
-            if lookahead_character != expected_character {
-                return false;
-            }
-        }
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> { pointer::resolve(self, pointer) }
+
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value> {
+        pointer::resolve_mut(self, pointer)
     }
+}
 
-    fn consume_and_unescape_string(&mut self) -> Result<String, ParseError> {
-        self.must_consume('"')?;
-
-        let mut string = String::new();
-        let mut skip_count = 0;
-        loop {
-            let mut lookahead = self.input.clone();
-            let mut ch = '\0';
-            loop {
-                let peek_ch = lookahead.next();
-                if peek_ch.is_none() {
-                    break;
-                }
-                ch = peek_ch.unwrap();
-                if ch == '"' || ch == '\\' {
-                    break;
-                }
-                if ch < 0x20 as char {
-                    return Err(ParseError::UnexpectedControlCharacter);
-                }
-                skip_count += 1;
-            }
+impl Number {
+    pub fn is_i64(&self) -> bool { self.as_i64().is_some() }
 
-            for _ in 0..skip_count {
-                string.push(self.input.next().unwrap());
-            }
+    pub fn is_u64(&self) -> bool { self.as_u64().is_some() }
 
-            if self.input.peek().is_none() {
-                break;
-            }
+    pub fn is_f64(&self) -> bool { matches!(self, Number::Float64(_)) }
 
-            match ch {
-                '"' => break,
-                '\\' => {
-                    // Skip the \
-                    self.input.next();
-
-                    match self.input.next() {
-                        Some('\\') => string.push('\\'),
-                        Some('/') => string.push('/'),
-                        Some('n') => string.push('\n'),
-                        Some('r') => string.push('\r'),
-                        Some('b') => string.push(0x8 as char),
-                        Some('f') => string.push(0xc as char),
-                        Some(ch) => return Err(ParseError::UnexpectedCharacter(ch)),
-                        None => return Err(ParseError::UnexpectedEof),
-                    }
-                }
-                _ => string.push(self.input.next().unwrap()),
-            }
+    pub fn is_raw(&self) -> bool { matches!(self, Number::Raw(_)) }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Number::Integer64(value) => Some(*value),
+            Number::UInt64(value) => i64::try_from(*value).ok(),
+            Number::Float64(_) => None,
+            Number::Raw(string) => string.parse().ok(),
         }
-        self.must_consume('"')?;
-        Ok(string)
     }
-}
 
-pub fn parse(input: &str) -> Result<Value, ParseError> {
-    let mut parser = JsonParser {
-        input: &mut input.chars().peekable(),
-    };
-    parser.parse()
-}
-
-impl std::fmt::Display for Value {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_string())
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Number::Integer64(value) => u64::try_from(*value).ok(),
+            Number::UInt64(value) => Some(*value),
+            Number::Float64(_) => None,
+            Number::Raw(string) => string.parse().ok(),
+        }
     }
-}
 
-impl Value {
-    pub fn as_string(&self) -> &String {
+    // Integers and raw numbers are converted, which may lose precision.
+    pub fn as_f64(&self) -> Option<f64> {
         match self {
-            Value::String(value) => value,
-            _ => panic!(),
+            Number::Integer64(value) => Some(*value as f64),
+            Number::UInt64(value) => Some(*value as f64),
+            Number::Float64(value) => Some(*value),
+            Number::Raw(string) => string.parse().ok(),
         }
     }
 
-    pub fn as_array(&self) -> &Vec<Value> {
+    pub fn as_raw(&self) -> Option<&str> {
         match self {
-            Value::Array(array) => array,
-            _ => panic!(),
+            Number::Raw(string) => Some(string),
+            _ => None,
         }
     }
 
-    pub fn as_object(&self) -> &HashMap<String, Value> {
+    // True for integers, and for floats and raw numbers without a fractional part.
+    pub fn is_integral(&self) -> bool {
         match self {
-            Value::Object(object) => object,
-            _ => panic!(),
+            Number::Integer64(_) | Number::UInt64(_) => true,
+            Number::Float64(value) => value.fract() == 0.0,
+            Number::Raw(string) => {
+                matches!(Decimal::parse(string), Some(value) if value.is_integer())
+            }
         }
     }
 
-    pub fn to_string(&self) -> String {
-        match &self {
-            Value::Null => String::from("null"),
-            Value::String(value) => value.clone(),
-            Value::Bool(value) => {
-                if *value {
-                    String::from("true")
-                } else {
-                    String::from("false")
-                }
-            }
-            Value::Number(number) => match number {
-                Number::Integer64(value) => format!("{}", value),
-                Number::Float64(value) => format!("{}", value),
-            },
-            Value::Array(array) => {
-                let mut string = String::new();
-                string.push('[');
-                for i in 0..array.len() {
-                    string.push_str(&array[i].to_string());
-                    if i != array.len() - 1 {
-                        string.push(',');
-                    }
-                }
-                string.push(']');
-                string
-            }
-            Value::Object(object) => {
-                let key_count = object.len();
-                let mut string = String::new();
-                string.push('{');
-                let mut i = 0;
-                for (name, value) in object.into_iter() {
-                    string.push('"');
-                    // FIXME: Escape the string.
-                    string.push_str(&name);
-                    string.push_str("\":\"");
-                    string.push_str(&value.to_string());
-                    string.push('"');
-                    if i != key_count - 1 {
-                        string.push(',');
-                    }
-                    i += 1;
-                }
-                string.push('}');
-                string
-            }
+    // Every integer an Integer64 or UInt64 can hold fits, as do raw integers up to 38 digits,
+    // however they are written (1e20 and 100000000000000000000.0 included).
+    fn as_i128(&self) -> Option<i128> {
+        match self {
+            Number::Integer64(value) => Some(*value as i128),
+            Number::UInt64(value) => Some(*value as i128),
+            Number::Float64(_) => None,
+            Number::Raw(string) => Decimal::parse(string)?.to_i128(),
         }
     }
 }
@@ -0,0 +1,698 @@
+/*
+ * Copyright (c) 2022, Andreas Kling <kling@serenityos.org>
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+use std::borrow::Cow;
+
+use super::{Map, Number, ParseError, ParseErrorKind, Value};
+
+// What to do when an object contains the same key more than once.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicateKeys {
+    // Keep the value that appears first and ignore the others.
+    FirstWins,
+    // Keep the value that appears last. The key stays at the position where it first appeared.
+    #[default]
+    LastWins,
+    // Fail with ParseErrorKind::DuplicateKey.
+    Error,
+}
+
+// Nesting deeper than this is rejected unless ParseOptions::max_depth says otherwise, so a hostile
+// document cannot exhaust the stack of the parser or of the code that walks the result.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+// By default, only RFC 8259 JSON is accepted. The allow_* extensions are taken from JSON5 and can
+// be enabled individually, e.g. for hand-edited config files.
+#[derive(Clone, Debug)]
+pub struct ParseOptions {
+    pub duplicate_keys: DuplicateKeys,
+    // Keep numbers that an f64 cannot hold exactly as Number::Raw instead of rounding them.
+    pub raw_numbers: bool,
+    // `// line` and `/* block */` comments wherever whitespace is allowed.
+    pub allow_comments: bool,
+    // A comma after the last element of an array or the last member of an object.
+    pub allow_trailing_commas: bool,
+    // Strings and keys in single quotes, in which \' is an escape and " needs none.
+    pub allow_single_quotes: bool,
+    // Integers like 0x1F or -0xff.
+    pub allow_hex_numbers: bool,
+    // Object keys that are identifiers, like {name: "lsirq"}.
+    pub allow_unquoted_keys: bool,
+    // Limits for input from untrusted peers. None means unlimited.
+    // How many objects and arrays may be nested inside each other.
+    pub max_depth: Option<usize>,
+    // Length in bytes of the longest string, key or number literal.
+    pub max_string_length: Option<usize>,
+    // Total number of array elements and object members in the document.
+    pub max_elements: Option<usize>,
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions {
+            duplicate_keys: DuplicateKeys::default(),
+            raw_numbers: false,
+            allow_comments: false,
+            allow_trailing_commas: false,
+            allow_single_quotes: false,
+            allow_hex_numbers: false,
+            allow_unquoted_keys: false,
+            max_depth: Some(DEFAULT_MAX_DEPTH),
+            max_string_length: None,
+            max_elements: None,
+        }
+    }
+}
+
+impl ParseOptions {
+    // Enables all of the syntax extensions.
+    pub fn relaxed() -> ParseOptions {
+        ParseOptions::default()
+            .with_comments()
+            .with_trailing_commas()
+            .with_single_quotes()
+            .with_hex_numbers()
+            .with_unquoted_keys()
+    }
+
+    pub fn with_comments(mut self) -> ParseOptions {
+        self.allow_comments = true;
+        self
+    }
+
+    pub fn with_trailing_commas(mut self) -> ParseOptions {
+        self.allow_trailing_commas = true;
+        self
+    }
+
+    pub fn with_single_quotes(mut self) -> ParseOptions {
+        self.allow_single_quotes = true;
+        self
+    }
+
+    pub fn with_hex_numbers(mut self) -> ParseOptions {
+        self.allow_hex_numbers = true;
+        self
+    }
+
+    pub fn with_unquoted_keys(mut self) -> ParseOptions {
+        self.allow_unquoted_keys = true;
+        self
+    }
+
+    pub fn with_raw_numbers(mut self) -> ParseOptions {
+        self.raw_numbers = true;
+        self
+    }
+
+    pub fn with_duplicate_keys(mut self, duplicate_keys: DuplicateKeys) -> ParseOptions {
+        self.duplicate_keys = duplicate_keys;
+        self
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> ParseOptions {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    pub fn with_max_string_length(mut self, max_string_length: usize) -> ParseOptions {
+        self.max_string_length = Some(max_string_length);
+        self
+    }
+
+    pub fn with_max_elements(mut self, max_elements: usize) -> ParseOptions {
+        self.max_elements = Some(max_elements);
+        self
+    }
+
+    pub(super) fn check_depth(&self, depth: usize) -> Result<(), ParseErrorKind> {
+        match self.max_depth {
+            Some(max_depth) if depth > max_depth => {
+                Err(ParseErrorKind::DepthLimitExceeded(max_depth))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(super) fn check_string_length(&self, length: usize) -> Result<(), ParseErrorKind> {
+        match self.max_string_length {
+            Some(max_string_length) if length > max_string_length => {
+                Err(ParseErrorKind::StringTooLong(max_string_length))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(super) fn check_element_count(&self, count: usize) -> Result<(), ParseErrorKind> {
+        match self.max_elements {
+            Some(max_elements) if count > max_elements => {
+                Err(ParseErrorKind::TooManyElements(max_elements))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// Adds a member to an object being built, applying the duplicate key policy.
+pub(super) fn insert_member(
+    object: &mut Map,
+    key: String,
+    value: Value,
+    duplicate_keys: DuplicateKeys,
+) -> Result<(), ParseErrorKind> {
+    match duplicate_keys {
+        DuplicateKeys::LastWins => {
+            object.insert(key, value);
+        }
+        _ if !object.contains_key(&key) => {
+            object.insert(key, value);
+        }
+        DuplicateKeys::FirstWins => {}
+        DuplicateKeys::Error => return Err(ParseErrorKind::DuplicateKey(key)),
+    }
+    Ok(())
+}
+
+// Scans the UTF-8 bytes of the input directly. Everything the parser looks at to make a decision
+// is ASCII, so slicing the input at those positions always yields valid UTF-8.
+struct JsonParser<'a> {
+    input: &'a str,
+    bytes: &'a [u8],
+    position: usize,
+    options: &'a ParseOptions,
+    depth: usize,
+    element_count: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn peek(&self) -> Option<u8> { self.bytes.get(self.position).copied() }
+
+    fn parse(&mut self) -> Result<Value, ParseErrorKind> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.parse_object(),
+            Some(b'[') => self.parse_array(),
+            Some(b'"') => self.parse_string(b'"'),
+            Some(b'\'') if self.options.allow_single_quotes => self.parse_string(b'\''),
+            Some(b'0'..=b'9' | b'-') => self.parse_number(),
+            Some(b't') => self.parse_true(),
+            Some(b'f') => self.parse_false(),
+            Some(b'n') => self.parse_null(),
+            Some(_) => Err(self.unexpected_character()),
+            None => Err(ParseErrorKind::UnexpectedEof),
+        }
+    }
+
+    fn enter_container(&mut self) -> Result<(), ParseErrorKind> {
+        self.depth += 1;
+        self.options.check_depth(self.depth)
+    }
+
+    fn count_element(&mut self) -> Result<(), ParseErrorKind> {
+        self.element_count += 1;
+        self.options.check_element_count(self.element_count)
+    }
+
+    fn must_consume(&mut self, ch: u8) -> Result<(), ParseErrorKind> {
+        if self.peek() != Some(ch) {
+            return Err(ParseErrorKind::ExpectedCharacter(ch as char));
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    fn parse_object(&mut self) -> Result<Value, ParseErrorKind> {
+        self.enter_container()?;
+        self.must_consume(b'{')?;
+
+        let mut object = Map::new();
+
+        self.skip_whitespace();
+        if self.consume(b"}") {
+            self.depth -= 1;
+            return Ok(Value::Object(object));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let name_start = self.position;
+            self.count_element()?;
+            let name = self.consume_key()?;
+            self.skip_whitespace();
+            self.must_consume(b':')?;
+            self.skip_whitespace();
+            let value = self.parse()?;
+            let duplicate_keys = self.options.duplicate_keys;
+            if let Err(error) = insert_member(&mut object, name.into_owned(), value, duplicate_keys)
+            {
+                // Point at the repeated key rather than at the end of its value.
+                self.position = name_start;
+                return Err(error);
+            }
+            self.skip_whitespace();
+            if self.peek() == Some(b'}') {
+                break;
+            }
+            self.must_consume(b',')?;
+            self.skip_whitespace();
+            if self.options.allow_trailing_commas && self.peek() == Some(b'}') {
+                break;
+            }
+        }
+        self.must_consume(b'}')?;
+        self.depth -= 1;
+        Ok(Value::Object(object))
+    }
+
+    fn parse_array(&mut self) -> Result<Value, ParseErrorKind> {
+        self.enter_container()?;
+        self.must_consume(b'[')?;
+        let mut array = Vec::new();
+
+        self.skip_whitespace();
+        if self.consume(b"]") {
+            self.depth -= 1;
+            return Ok(Value::Array(array));
+        }
+
+        loop {
+            self.count_element()?;
+            let element = self.parse()?;
+            array.push(element);
+            self.skip_whitespace();
+            if self.peek() == Some(b']') {
+                break;
+            }
+            self.must_consume(b',')?;
+            self.skip_whitespace();
+            if self.peek() == Some(b']') {
+                if self.options.allow_trailing_commas {
+                    break;
+                }
+                return Err(ParseErrorKind::UnexpectedCharacter(']'));
+            }
+        }
+        self.must_consume(b']')?;
+        self.depth -= 1;
+        Ok(Value::Array(array))
+    }
+
+    fn parse_number(&mut self) -> Result<Value, ParseErrorKind> {
+        let start = self.position;
+
+        // Fast path: most numbers in ProcFS documents are small non-negative integers, which can
+        // be accumulated directly. Anything else goes through the general conversion below.
+        let mut value: i64 = 0;
+        let mut digit_count = 0;
+        while let Some(digit @ b'0'..=b'9') = self.peek() {
+            value = value.wrapping_mul(10).wrapping_add((digit - b'0') as i64);
+            digit_count += 1;
+            self.position += 1;
+        }
+        let is_simple = digit_count > 0
+            && digit_count <= 18
+            && (digit_count == 1 || self.bytes[start] != b'0')
+            && !matches!(
+                self.peek(),
+                Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'x' | b'X')
+            );
+        if is_simple {
+            self.check_number_length(start)?;
+            return Ok(Value::Number(Number::Integer64(value)));
+        }
+
+        let allow_hex_numbers = self.options.allow_hex_numbers;
+        while let Some(byte) = self.peek() {
+            let is_number_character = matches!(byte, b'-' | b'+' | b'.' | b'0'..=b'9')
+                || if allow_hex_numbers {
+                    byte.is_ascii_hexdigit() || byte == b'x' || byte == b'X'
+                } else {
+                    byte == b'e' || byte == b'E'
+                };
+            if !is_number_character {
+                break;
+            }
+            self.position += 1;
+        }
+        self.check_number_length(start)?;
+        let number = parse_number_literal(&self.input[start..self.position], self.options)?;
+        Ok(Value::Number(number))
+    }
+
+    fn check_number_length(&mut self, start: usize) -> Result<(), ParseErrorKind> {
+        let result = self.options.check_string_length(self.position - start);
+        if result.is_err() {
+            self.position = start;
+        }
+        result
+    }
+
+    fn parse_string(&mut self, quote: u8) -> Result<Value, ParseErrorKind> {
+        let string = self.consume_and_unescape_string(quote)?;
+        Ok(Value::String(string.into_owned()))
+    }
+
+    fn consume_key(&mut self) -> Result<Cow<'a, str>, ParseErrorKind> {
+        match self.peek() {
+            Some(b'\'') if self.options.allow_single_quotes => {
+                self.consume_and_unescape_string(b'\'')
+            }
+            Some(b'"') | None => self.consume_and_unescape_string(b'"'),
+            Some(_) if self.options.allow_unquoted_keys => {
+                let identifier = identifier_prefix(&self.input[self.position..]);
+                if identifier.is_empty() {
+                    return Err(self.unexpected_character());
+                }
+                self.options.check_string_length(identifier.len())?;
+                self.position += identifier.len();
+                Ok(Cow::Borrowed(identifier))
+            }
+            Some(_) => Err(ParseErrorKind::ExpectedCharacter('"')),
+        }
+    }
+
+    fn parse_true(&mut self) -> Result<Value, ParseErrorKind> {
+        if self.consume(b"true") {
+            Ok(Value::Bool(true))
+        } else {
+            Err(ParseErrorKind::ExpectedTrue)
+        }
+    }
+
+    fn parse_false(&mut self) -> Result<Value, ParseErrorKind> {
+        if self.consume(b"false") {
+            Ok(Value::Bool(false))
+        } else {
+            Err(ParseErrorKind::ExpectedFalse)
+        }
+    }
+
+    fn parse_null(&mut self) -> Result<Value, ParseErrorKind> {
+        if self.consume(b"null") {
+            Ok(Value::Null)
+        } else {
+            Err(ParseErrorKind::ExpectedNull)
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        loop {
+            match self.peek() {
+                Some(b' ' | b'\n' | b'\r' | b'\t') => self.position += 1,
+                Some(b'/') if self.options.allow_comments => {
+                    if self.consume(b"//") {
+                        while !matches!(self.peek(), Some(b'\n') | None) {
+                            self.position += 1;
+                        }
+                    } else if self.consume(b"/*") {
+                        // An unterminated comment runs to the end of the input, where the caller
+                        // reports the missing value or closing bracket.
+                        self.position = match self.input[self.position..].find("*/") {
+                            Some(index) => self.position + index + 2,
+                            None => self.input.len(),
+                        };
+                    } else {
+                        return;
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn consume(&mut self, expected: &[u8]) -> bool {
+        if !self.bytes[self.position..].starts_with(expected) {
+            return false;
+        }
+        self.position += expected.len();
+        true
+    }
+
+    fn unexpected_character(&self) -> ParseErrorKind {
+        match self.input[self.position..].chars().next() {
+            Some(ch) => ParseErrorKind::UnexpectedCharacter(ch),
+            None => ParseErrorKind::UnexpectedEof,
+        }
+    }
+
+    // Strings without escapes are borrowed straight from the input.
+    fn consume_and_unescape_string(&mut self, quote: u8) -> Result<Cow<'a, str>, ParseErrorKind> {
+        let string_start = self.position;
+        self.must_consume(quote)?;
+
+        let mut start = self.position;
+        let mut unescaped = String::new();
+        loop {
+            let end = self.bytes[self.position..]
+                .iter()
+                .position(|byte| *byte == quote || matches!(byte, b'\\' | 0..=0x1f))
+                .map(|index| self.position + index);
+            let end = match end {
+                Some(end) => end,
+                None => {
+                    self.position = self.bytes.len();
+                    return Err(ParseErrorKind::UnexpectedEof);
+                }
+            };
+            self.position = end;
+            match self.bytes[end] {
+                byte if byte == quote => break,
+                b'\\' => {
+                    unescaped.push_str(&self.input[start..end]);
+                    self.position += 1;
+                    unescaped.push(self.consume_escape()?);
+                    start = self.position;
+                }
+                _ => return Err(ParseErrorKind::UnexpectedControlCharacter),
+            }
+        }
+
+        let string = if unescaped.is_empty() {
+            Cow::Borrowed(&self.input[start..self.position])
+        } else {
+            unescaped.push_str(&self.input[start..self.position]);
+            Cow::Owned(unescaped)
+        };
+        if let Err(error) = self.options.check_string_length(string.len()) {
+            self.position = string_start;
+            return Err(error);
+        }
+        self.position += 1;
+        Ok(string)
+    }
+
+    fn consume_escape(&mut self) -> Result<char, ParseErrorKind> {
+        let unescaped = match self.peek() {
+            Some(b'"') => '"',
+            Some(b'\'') if self.options.allow_single_quotes => '\'',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => 0x8 as char,
+            Some(b'f') => 0xc as char,
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                self.position += 1;
+                return self.consume_unicode_escape();
+            }
+            Some(_) => return Err(self.unexpected_character()),
+            None => return Err(ParseErrorKind::UnexpectedEof),
+        };
+        self.position += 1;
+        Ok(unescaped)
+    }
+
+    fn consume_unicode_escape(&mut self) -> Result<char, ParseErrorKind> {
+        let code_unit = self.consume_hex_code_unit()?;
+        match code_unit {
+            0xd800..=0xdbff => {
+                // A high surrogate has to be followed by an escaped low surrogate.
+                if !self.consume(b"\\u") {
+                    return Err(ParseErrorKind::LoneSurrogate(code_unit));
+                }
+                let low_surrogate = self.consume_hex_code_unit()?;
+                if !(0xdc00..=0xdfff).contains(&low_surrogate) {
+                    return Err(ParseErrorKind::LoneSurrogate(code_unit));
+                }
+                let code_point = 0x10000
+                    + (((code_unit as u32) - 0xd800) << 10)
+                    + ((low_surrogate as u32) - 0xdc00);
+                Ok(char::from_u32(code_point).unwrap())
+            }
+            0xdc00..=0xdfff => Err(ParseErrorKind::LoneSurrogate(code_unit)),
+            _ => Ok(char::from_u32(code_unit as u32).unwrap()),
+        }
+    }
+
+    fn consume_hex_code_unit(&mut self) -> Result<u16, ParseErrorKind> {
+        let mut code_unit = 0u16;
+        for _ in 0..4 {
+            let digit = match self.peek() {
+                Some(byte) => (byte as char)
+                    .to_digit(16)
+                    .ok_or_else(|| self.unexpected_character())?,
+                None => return Err(ParseErrorKind::UnexpectedEof),
+            };
+            self.position += 1;
+            code_unit = (code_unit << 4) | digit as u16;
+        }
+        Ok(code_unit)
+    }
+}
+
+// Converts the characters of a number token, as collected by the parser or the reader.
+// Integers become an Integer64 or, above i64::MAX, a UInt64. Everything else is a Float64 unless
+// raw_numbers is set and the conversion would lose precision.
+pub(super) fn parse_number_literal(
+    string: &str,
+    options: &ParseOptions,
+) -> Result<Number, ParseErrorKind> {
+    if options.allow_hex_numbers {
+        if let Some(number) = parse_hex_literal(string) {
+            return number;
+        }
+    }
+    if string.matches('.').count() > 1 {
+        return Err(ParseErrorKind::MultiplePeriodsInNumber);
+    }
+    let significant_digits =
+        validate_number_literal(string).ok_or(ParseErrorKind::InvalidNumber)?;
+
+    let is_integer = !string.contains(['.', 'e', 'E']);
+    if is_integer && string != "-0" {
+        if let Ok(value) = string.parse() {
+            return Ok(Number::Integer64(value));
+        }
+        if let Ok(value) = string.parse() {
+            return Ok(Number::UInt64(value));
+        }
+    }
+
+    let value: f64 = string.parse().map_err(|_| ParseErrorKind::InvalidNumber)?;
+    // Too large a magnitude overflows to infinity, and too small a nonzero one underflows to zero.
+    let out_of_range = !value.is_finite() || (value == 0.0 && significant_digits > 0);
+    // Every decimal with up to 15 significant digits survives the round trip through an f64.
+    if options.raw_numbers && (significant_digits > f64::DIGITS as usize || out_of_range) {
+        return Ok(Number::Raw(string.to_string()));
+    }
+    if out_of_range {
+        return Err(ParseErrorKind::NumberOutOfRange);
+    }
+    Ok(Number::Float64(value))
+}
+
+// Returns None if the string does not have a hexadecimal prefix.
+fn parse_hex_literal(string: &str) -> Option<Result<Number, ParseErrorKind>> {
+    let (negative, unsigned) = match string.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, string),
+    };
+    let digits = unsigned
+        .strip_prefix("0x")
+        .or_else(|| unsigned.strip_prefix("0X"))?;
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return Some(Err(ParseErrorKind::InvalidNumber));
+    }
+
+    let value = match u64::from_str_radix(digits, 16) {
+        Ok(value) => value as i128,
+        Err(_) => return Some(Err(ParseErrorKind::NumberOutOfRange)),
+    };
+    let value = if negative { -value } else { value };
+    let number = if let Ok(value) = i64::try_from(value) {
+        Ok(Number::Integer64(value))
+    } else if let Ok(value) = u64::try_from(value) {
+        Ok(Number::UInt64(value))
+    } else {
+        Err(ParseErrorKind::NumberOutOfRange)
+    };
+    Some(number)
+}
+
+// The longest prefix that is an identifier, for unquoted keys. This follows ECMAScript's
+// IdentifierName, minus escape sequences.
+pub(super) fn identifier_prefix(string: &str) -> &str {
+    let is_identifier_character = |(index, ch): &(usize, char)| {
+        ch.is_alphabetic() || *ch == '_' || *ch == '$' || (*index > 0 && ch.is_alphanumeric())
+    };
+    let end = string
+        .char_indices()
+        .find(|character| !is_identifier_character(character))
+        .map_or(string.len(), |(index, _)| index);
+    &string[..end]
+}
+
+// Checks the number grammar of RFC 8259, -?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][-+]?[0-9]+)?, and
+// returns the number of significant digits in the mantissa.
+fn validate_number_literal(string: &str) -> Option<usize> {
+    let bytes = string.as_bytes();
+    let skip_digits = |start: usize| {
+        start
+            + bytes[start..]
+                .iter()
+                .take_while(|byte| byte.is_ascii_digit())
+                .count()
+    };
+
+    let integer_start = usize::from(bytes.first() == Some(&b'-'));
+    let integer_end = skip_digits(integer_start);
+    let integer_length = integer_end - integer_start;
+    if integer_length == 0 || (integer_length > 1 && bytes[integer_start] == b'0') {
+        return None;
+    }
+
+    let mut mantissa_end = integer_end;
+    if bytes.get(mantissa_end) == Some(&b'.') {
+        let fraction_end = skip_digits(mantissa_end + 1);
+        if fraction_end == mantissa_end + 1 {
+            return None;
+        }
+        mantissa_end = fraction_end;
+    }
+
+    let mut end = mantissa_end;
+    if let Some(b'e' | b'E') = bytes.get(end) {
+        end += 1;
+        if let Some(b'+' | b'-') = bytes.get(end) {
+            end += 1;
+        }
+        let exponent_end = skip_digits(end);
+        if exponent_end == end {
+            return None;
+        }
+        end = exponent_end;
+    }
+    if end != bytes.len() {
+        return None;
+    }
+
+    let digits = string[integer_start..mantissa_end].replace('.', "");
+    Some(digits.trim_start_matches('0').trim_end_matches('0').len())
+}
+
+pub fn parse(input: &str) -> Result<Value, ParseError> {
+    parse_with_options(input, &ParseOptions::default())
+}
+
+pub fn parse_with_options(input: &str, options: &ParseOptions) -> Result<Value, ParseError> {
+    let mut parser = JsonParser {
+        input,
+        bytes: input.as_bytes(),
+        position: 0,
+        options,
+        depth: 0,
+        element_count: 0,
+    };
+    let result = parser.parse().and_then(|value| {
+        parser.skip_whitespace();
+        match parser.peek() {
+            None => Ok(value),
+            Some(_) => Err(parser.unexpected_character()),
+        }
+    });
+    result.map_err(|kind| ParseError::new(kind, input, parser.position))
+}
@@ -0,0 +1,500 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// JSON Patch (RFC 6902), JSON Merge Patch (RFC 7386), and computing the patch between two
+// documents, so settings can be updated and shipped as deltas instead of whole documents.
+//
+//     let patch = json::patch::Patch::from_value(&json::parse(request)?)?;
+//     json::patch::apply(&mut settings, &patch)?;
+//
+//     let delta = json::patch::diff(&old_settings, &new_settings);
+//     client.send(&delta.to_value().to_string());
+
+use std::fmt::{Display, Formatter};
+
+use super::pointer::{escape_token, parse_array_index, parse_pointer};
+use super::{Map, ObjectBuilder, Value};
+
+// Arrays whose differing middle parts would need a larger table than this are diffed position by
+// position instead of searching for the smallest set of insertions and removals.
+const MAX_ARRAY_DIFF_CELLS: usize = 1 << 20;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Patch {
+    operations: Vec<Operation>,
+}
+
+#[derive(Debug)]
+pub enum PatchErrorKind {
+    // The patch document is not an array of operation objects.
+    NotAnArray,
+    NotAnOperation,
+    UnknownOperation(String),
+    // An operation lacks a member it requires, or has one of the wrong type.
+    MissingMember(&'static str),
+    InvalidMember(&'static str),
+    InvalidPointer(String),
+    PathNotFound(String),
+    // A "move" whose target lies inside the value being moved.
+    MoveIntoItself(String),
+    CannotRemoveRoot,
+    TestFailed(String),
+}
+
+impl Display for PatchErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PatchErrorKind::NotAnArray => write!(f, "a patch must be an array of operations"),
+            PatchErrorKind::NotAnOperation => write!(f, "an operation must be an object"),
+            PatchErrorKind::UnknownOperation(op) => write!(f, "unknown operation \"{}\"", op),
+            PatchErrorKind::MissingMember(member) => write!(f, "missing \"{}\"", member),
+            PatchErrorKind::InvalidMember(member) => write!(f, "\"{}\" must be a string", member),
+            PatchErrorKind::InvalidPointer(pointer) => {
+                write!(f, "\"{}\" is not a valid JSON Pointer", pointer)
+            }
+            PatchErrorKind::PathNotFound(path) => write!(f, "path \"{}\" does not exist", path),
+            PatchErrorKind::MoveIntoItself(path) => {
+                write!(f, "cannot move a value into itself at \"{}\"", path)
+            }
+            PatchErrorKind::CannotRemoveRoot => write!(f, "cannot remove the whole document"),
+            PatchErrorKind::TestFailed(path) => write!(f, "test failed at \"{}\"", path),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PatchError {
+    kind: PatchErrorKind,
+    operation: Option<usize>,
+}
+
+impl PatchError {
+    pub fn kind(&self) -> &PatchErrorKind { &self.kind }
+
+    // Position of the offending operation in the patch, if the error is about a single one.
+    pub fn operation(&self) -> Option<usize> { self.operation }
+}
+
+impl Display for PatchError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.operation {
+            Some(operation) => write!(f, "operation {}: {}", operation, self.kind),
+            None => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+impl Operation {
+    pub fn from_value(value: &Value) -> Result<Operation, PatchErrorKind> {
+        let object = value.as_object().ok_or(PatchErrorKind::NotAnOperation)?;
+        let string = |member: &'static str| match object.get(member) {
+            Some(Value::String(string)) => Ok(string.clone()),
+            Some(_) => Err(PatchErrorKind::InvalidMember(member)),
+            None => Err(PatchErrorKind::MissingMember(member)),
+        };
+        // "value" may legitimately be null, so only its absence is an error.
+        let value = || {
+            object
+                .get("value")
+                .cloned()
+                .ok_or(PatchErrorKind::MissingMember("value"))
+        };
+        let path = string("path")?;
+        Ok(match string("op")?.as_str() {
+            "add" => Operation::Add {
+                path,
+                value: value()?,
+            },
+            "remove" => Operation::Remove { path },
+            "replace" => Operation::Replace {
+                path,
+                value: value()?,
+            },
+            "move" => Operation::Move {
+                from: string("from")?,
+                path,
+            },
+            "copy" => Operation::Copy {
+                from: string("from")?,
+                path,
+            },
+            "test" => Operation::Test {
+                path,
+                value: value()?,
+            },
+            op => return Err(PatchErrorKind::UnknownOperation(op.to_string())),
+        })
+    }
+
+    pub fn to_value(&self) -> Value {
+        let (op, path) = match self {
+            Operation::Add { path, .. } => ("add", path),
+            Operation::Remove { path } => ("remove", path),
+            Operation::Replace { path, .. } => ("replace", path),
+            Operation::Move { path, .. } => ("move", path),
+            Operation::Copy { path, .. } => ("copy", path),
+            Operation::Test { path, .. } => ("test", path),
+        };
+        let builder = ObjectBuilder::with_capacity(3)
+            .insert("op", op)
+            .insert("path", path);
+        match self {
+            Operation::Add { value, .. }
+            | Operation::Replace { value, .. }
+            | Operation::Test { value, .. } => builder.insert("value", value.clone()),
+            Operation::Move { from, .. } | Operation::Copy { from, .. } => {
+                builder.insert("from", from)
+            }
+            Operation::Remove { .. } => builder,
+        }
+        .build()
+    }
+}
+
+impl Patch {
+    pub fn new() -> Patch { Patch::default() }
+
+    pub fn from_value(value: &Value) -> Result<Patch, PatchError> {
+        let array = value.as_array().ok_or(PatchError {
+            kind: PatchErrorKind::NotAnArray,
+            operation: None,
+        })?;
+        let operations = array
+            .iter()
+            .enumerate()
+            .map(|(index, operation)| {
+                Operation::from_value(operation).map_err(|kind| PatchError {
+                    kind,
+                    operation: Some(index),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Patch { operations })
+    }
+
+    pub fn to_value(&self) -> Value { self.operations.iter().map(Operation::to_value).collect() }
+
+    pub fn operations(&self) -> &[Operation] { &self.operations }
+
+    pub fn push(&mut self, operation: Operation) { self.operations.push(operation) }
+
+    pub fn len(&self) -> usize { self.operations.len() }
+
+    pub fn is_empty(&self) -> bool { self.operations.is_empty() }
+}
+
+impl From<Vec<Operation>> for Patch {
+    fn from(operations: Vec<Operation>) -> Patch { Patch { operations } }
+}
+
+impl IntoIterator for Patch {
+    type IntoIter = std::vec::IntoIter<Operation>;
+    type Item = Operation;
+
+    fn into_iter(self) -> Self::IntoIter { self.operations.into_iter() }
+}
+
+// Applies every operation of the patch, or none of them: if one fails, the document is left as
+// it was.
+pub fn apply(document: &mut Value, patch: &Patch) -> Result<(), PatchError> {
+    let mut patched = document.clone();
+    for (index, operation) in patch.operations.iter().enumerate() {
+        apply_operation(&mut patched, operation).map_err(|kind| PatchError {
+            kind,
+            operation: Some(index),
+        })?;
+    }
+    *document = patched;
+    Ok(())
+}
+
+fn apply_operation(document: &mut Value, operation: &Operation) -> Result<(), PatchErrorKind> {
+    match operation {
+        Operation::Add { path, value } => add(document, path, value.clone()),
+        Operation::Remove { path } => remove(document, path).map(drop),
+        Operation::Replace { path, value } => {
+            *lookup_mut(document, path)? = value.clone();
+            Ok(())
+        }
+        Operation::Move { from, path } => {
+            if from == path {
+                return lookup_mut(document, from).map(drop);
+            }
+            let from_tokens = tokens(from)?;
+            if tokens(path)?.starts_with(&from_tokens) {
+                return Err(PatchErrorKind::MoveIntoItself(path.clone()));
+            }
+            let value = remove(document, from)?;
+            add(document, path, value)
+        }
+        Operation::Copy { from, path } => {
+            let value = lookup_mut(document, from)?.clone();
+            add(document, path, value)
+        }
+        Operation::Test { path, value } => {
+            if *lookup_mut(document, path)? != *value {
+                return Err(PatchErrorKind::TestFailed(path.clone()));
+            }
+            Ok(())
+        }
+    }
+}
+
+fn tokens(pointer: &str) -> Result<Vec<String>, PatchErrorKind> {
+    parse_pointer(pointer).ok_or_else(|| PatchErrorKind::InvalidPointer(pointer.to_string()))
+}
+
+fn resolve_tokens<'a>(value: &'a mut Value, tokens: &[String]) -> Option<&'a mut Value> {
+    tokens.iter().try_fold(value, |value, token| match value {
+        Value::Object(object) => object.get_mut(token),
+        Value::Array(array) => array.get_mut(parse_array_index(token)?),
+        _ => None,
+    })
+}
+
+fn lookup_mut<'a>(document: &'a mut Value, path: &str) -> Result<&'a mut Value, PatchErrorKind> {
+    resolve_tokens(document, &tokens(path)?)
+        .ok_or_else(|| PatchErrorKind::PathNotFound(path.to_string()))
+}
+
+fn add(document: &mut Value, path: &str, value: Value) -> Result<(), PatchErrorKind> {
+    let tokens = tokens(path)?;
+    let (last, parent) = match tokens.split_last() {
+        Some(split) => split,
+        None => {
+            *document = value;
+            return Ok(());
+        }
+    };
+    let not_found = || PatchErrorKind::PathNotFound(path.to_string());
+    match resolve_tokens(document, parent).ok_or_else(not_found)? {
+        Value::Object(object) => {
+            object.insert(last.clone(), value);
+        }
+        // "-" refers to the position after the last element.
+        Value::Array(array) if last == "-" => array.push(value),
+        Value::Array(array) => match parse_array_index(last) {
+            Some(index) if index <= array.len() => array.insert(index, value),
+            _ => return Err(not_found()),
+        },
+        _ => return Err(not_found()),
+    }
+    Ok(())
+}
+
+fn remove(document: &mut Value, path: &str) -> Result<Value, PatchErrorKind> {
+    let tokens = tokens(path)?;
+    let (last, parent) = tokens
+        .split_last()
+        .ok_or(PatchErrorKind::CannotRemoveRoot)?;
+    let not_found = || PatchErrorKind::PathNotFound(path.to_string());
+    match resolve_tokens(document, parent).ok_or_else(not_found)? {
+        Value::Object(object) => object.remove(last).ok_or_else(not_found),
+        Value::Array(array) => match parse_array_index(last) {
+            Some(index) if index < array.len() => Ok(array.remove(index)),
+            _ => Err(not_found()),
+        },
+        _ => Err(not_found()),
+    }
+}
+
+// Applies an RFC 7386 merge patch: objects are merged member by member, a null member removes
+// the member from the document, and anything else replaces the target outright. Merging cannot
+// fail.
+pub fn merge(document: &mut Value, patch: &Value) {
+    let patch = match patch {
+        Value::Object(patch) => patch,
+        _ => {
+            *document = patch.clone();
+            return;
+        }
+    };
+    if !document.is_object() {
+        *document = Value::Object(Map::new());
+    }
+    let object = match document {
+        Value::Object(object) => object,
+        _ => unreachable!(),
+    };
+    for (key, value) in patch {
+        if value.is_null() {
+            object.remove(key);
+            continue;
+        }
+        match object.get_mut(key) {
+            Some(member) => merge(member, value),
+            None => {
+                let mut member = Value::Null;
+                merge(&mut member, value);
+                object.insert(key.clone(), member);
+            }
+        }
+    }
+}
+
+// Computes a patch that turns `from` into `to`. Unchanged parts of the document are skipped,
+// objects are compared member by member, and arrays by the smallest sequence of insertions,
+// removals and changes of elements.
+pub fn diff(from: &Value, to: &Value) -> Patch {
+    let mut patch = Patch::new();
+    diff_into(&mut patch, String::new(), from, to);
+    patch
+}
+
+fn diff_into(patch: &mut Patch, path: String, from: &Value, to: &Value) {
+    match (from, to) {
+        _ if from == to => {}
+        (Value::Object(from), Value::Object(to)) => diff_objects(patch, &path, from, to),
+        (Value::Array(from), Value::Array(to)) => diff_arrays(patch, &path, from, to),
+        _ => patch.push(Operation::Replace {
+            path,
+            value: to.clone(),
+        }),
+    }
+}
+
+fn member_path(path: &str, key: &str) -> String { format!("{}/{}", path, escape_token(key)) }
+
+fn diff_objects(patch: &mut Patch, path: &str, from: &Map, to: &Map) {
+    for (key, value) in from {
+        match to.get(key) {
+            Some(other) => diff_into(patch, member_path(path, key), value, other),
+            None => patch.push(Operation::Remove {
+                path: member_path(path, key),
+            }),
+        }
+    }
+    for (key, value) in to {
+        if !from.contains_key(key) {
+            patch.push(Operation::Add {
+                path: member_path(path, key),
+                value: value.clone(),
+            });
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Edit {
+    Keep,
+    Change,
+    Remove,
+    Insert,
+}
+
+fn diff_arrays(patch: &mut Patch, path: &str, from: &[Value], to: &[Value]) {
+    let prefix = from.iter().zip(to).take_while(|(a, b)| a == b).count();
+    let (from_rest, to_rest) = (&from[prefix..], &to[prefix..]);
+    let suffix = from_rest
+        .iter()
+        .rev()
+        .zip(to_rest.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let from_middle = &from_rest[..from_rest.len() - suffix];
+    let to_middle = &to_rest[..to_rest.len() - suffix];
+
+    let edits = match (from_middle.len() + 1).checked_mul(to_middle.len() + 1) {
+        Some(cells) if cells <= MAX_ARRAY_DIFF_CELLS => shortest_edits(from_middle, to_middle),
+        _ => positional_edits(from_middle.len(), to_middle.len()),
+    };
+
+    // The edits are emitted back to front, so every index still refers to the original array
+    // when its operation is applied.
+    let element_path = |index: usize| format!("{}/{}", path, prefix + index);
+    let (mut i, mut j) = (from_middle.len(), to_middle.len());
+    for edit in edits.into_iter().rev() {
+        match edit {
+            Edit::Keep => {
+                i -= 1;
+                j -= 1;
+            }
+            Edit::Change => {
+                i -= 1;
+                j -= 1;
+                diff_into(patch, element_path(i), &from_middle[i], &to_middle[j]);
+            }
+            Edit::Remove => {
+                i -= 1;
+                patch.push(Operation::Remove {
+                    path: element_path(i),
+                });
+            }
+            Edit::Insert => {
+                j -= 1;
+                patch.push(Operation::Add {
+                    path: element_path(i),
+                    value: to_middle[j].clone(),
+                });
+            }
+        }
+    }
+}
+
+// Levenshtein distance over whole elements, returning the edits in order from the front.
+fn shortest_edits(from: &[Value], to: &[Value]) -> Vec<Edit> {
+    let columns = to.len() + 1;
+    let mut costs = vec![0usize; (from.len() + 1) * columns];
+    for i in 0..=from.len() {
+        for j in 0..=to.len() {
+            costs[i * columns + j] = match (i, j) {
+                (0, _) => j,
+                (_, 0) => i,
+                _ => {
+                    let change = usize::from(from[i - 1] != to[j - 1]);
+                    (costs[(i - 1) * columns + j - 1] + change)
+                        .min(costs[(i - 1) * columns + j] + 1)
+                        .min(costs[i * columns + j - 1] + 1)
+                }
+            };
+        }
+    }
+
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (from.len(), to.len());
+    while i > 0 || j > 0 {
+        let cost = costs[i * columns + j];
+        if i > 0 && j > 0 {
+            let equal = from[i - 1] == to[j - 1];
+            if cost == costs[(i - 1) * columns + j - 1] + usize::from(!equal) {
+                edits.push(if equal { Edit::Keep } else { Edit::Change });
+                i -= 1;
+                j -= 1;
+                continue;
+            }
+        }
+        if i > 0 && cost == costs[(i - 1) * columns + j] + 1 {
+            edits.push(Edit::Remove);
+            i -= 1;
+        } else {
+            edits.push(Edit::Insert);
+            j -= 1;
+        }
+    }
+    edits.reverse();
+    edits
+}
+
+fn positional_edits(from_length: usize, to_length: usize) -> Vec<Edit> {
+    let common = from_length.min(to_length);
+    let mut edits = vec![Edit::Change; common];
+    // At most one of these adds anything.
+    edits.resize(from_length, Edit::Remove);
+    edits.resize(from_length.max(to_length), Edit::Insert);
+    edits
+}
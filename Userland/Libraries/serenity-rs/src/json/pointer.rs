@@ -0,0 +1,71 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// JSON Pointer (RFC 6901) support.
+
+use super::Value;
+
+// Splits a pointer like "/0/features/3" into its unescaped reference tokens.
+// Returns None if the pointer is neither empty nor starts with a '/'.
+pub fn parse_pointer(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+    if !pointer.starts_with('/') {
+        return None;
+    }
+    pointer[1..].split('/').map(unescape_token).collect()
+}
+
+pub fn unescape_token(token: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(token.len());
+    let mut chars = token.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '~' {
+            unescaped.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('0') => unescaped.push('~'),
+            Some('1') => unescaped.push('/'),
+            _ => return None,
+        }
+    }
+    Some(unescaped)
+}
+
+pub fn escape_token(token: &str) -> String { token.replace('~', "~0").replace('/', "~1") }
+
+// Array indices in a pointer are plain decimal numbers without leading zeros.
+pub fn parse_array_index(token: &str) -> Option<usize> {
+    if token.is_empty() || (token.len() > 1 && token.starts_with('0')) {
+        return None;
+    }
+    if !token.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    token.parse().ok()
+}
+
+pub fn resolve<'a>(value: &'a Value, pointer: &str) -> Option<&'a Value> {
+    parse_pointer(pointer)?
+        .iter()
+        .try_fold(value, |value, token| match value {
+            Value::Object(object) => object.get(token),
+            Value::Array(array) => array.get(parse_array_index(token)?),
+            _ => None,
+        })
+}
+
+pub fn resolve_mut<'a>(value: &'a mut Value, pointer: &str) -> Option<&'a mut Value> {
+    parse_pointer(pointer)?
+        .iter()
+        .try_fold(value, |value, token| match value {
+            Value::Object(object) => object.get_mut(token),
+            Value::Array(array) => array.get_mut(parse_array_index(token)?),
+            _ => None,
+        })
+}
@@ -0,0 +1,415 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// A subset of JSONPath (RFC 9535), for picking values out of documents like /proc/all:
+//
+//     $.processes[?@.uid == 0].name
+//     $..threads[?@.state == 'Running' && @.cpu > 0].tid
+//     $.processes[-2:]
+//
+// Supported are the root `$`, child segments (`.name`, `.*`, `[...]`), descendant segments
+// (`..name`, `..*`, `..[...]`), and name, wildcard, index, slice and filter selectors. Filters
+// compare singular queries and literals with == != < <= > >=, test whether a query selects
+// anything, and combine tests with &&, || and !. Function extensions are not supported.
+//
+//     let query = json::query::compile("$.processes[?@.pid > 1].name")?;
+//     for name in query.select(&all) {
+//         println!("{}", name);
+//     }
+
+mod parser;
+
+use std::fmt::{Display, Formatter};
+
+use super::pointer::escape_token;
+use super::Value;
+
+#[derive(Debug)]
+pub enum QueryErrorKind {
+    ExpectedRoot,
+    UnexpectedCharacter(char),
+    UnexpectedEnd,
+    InvalidLiteral,
+    IndexOutOfRange,
+    // Only queries that select at most one value, like @.a[0], can be compared.
+    NotSingular,
+    TooDeeplyNested,
+}
+
+impl Display for QueryErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryErrorKind::ExpectedRoot => write!(f, "a query must start with '$'"),
+            QueryErrorKind::UnexpectedCharacter(ch) => {
+                write!(f, "unexpected character '{}'", ch.escape_debug())
+            }
+            QueryErrorKind::UnexpectedEnd => write!(f, "unexpected end of query"),
+            QueryErrorKind::InvalidLiteral => write!(f, "invalid literal"),
+            QueryErrorKind::IndexOutOfRange => write!(f, "index out of range"),
+            QueryErrorKind::NotSingular => {
+                write!(f, "only queries for a single value can be compared")
+            }
+            QueryErrorKind::TooDeeplyNested => write!(f, "filter is nested too deeply"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct QueryError {
+    kind: QueryErrorKind,
+    offset: usize,
+}
+
+impl QueryError {
+    pub fn kind(&self) -> &QueryErrorKind { &self.kind }
+
+    // Byte offset into the expression.
+    pub fn offset(&self) -> usize { self.offset }
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid query: {} at offset {}", self.kind, self.offset)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[derive(Clone, Debug)]
+enum Segment {
+    Child(Vec<Selector>),
+    Descendant(Vec<Selector>),
+}
+
+#[derive(Clone, Debug)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice {
+        start: Option<i64>,
+        end: Option<i64>,
+        step: Option<i64>,
+    },
+    Filter(Filter),
+}
+
+#[derive(Clone, Debug)]
+enum Filter {
+    Or(Box<Filter>, Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+    Exists(FilterQuery),
+    Compare(Comparable, Operator, Comparable),
+}
+
+// A query inside a filter, relative to the current value (@) or to the root ($).
+#[derive(Clone, Debug)]
+struct FilterQuery {
+    relative: bool,
+    segments: Vec<Segment>,
+}
+
+#[derive(Clone, Debug)]
+enum Comparable {
+    Literal(Value),
+    // Always singular, which the parser checks.
+    Query(FilterQuery),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Operator {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Clone, Debug)]
+pub struct Query {
+    segments: Vec<Segment>,
+}
+
+pub fn compile(expression: &str) -> Result<Query, QueryError> {
+    parser::parse_query(expression).map(|segments| Query { segments })
+}
+
+// Compiles and runs a query in one go.
+pub fn select<'a>(value: &'a Value, expression: &str) -> Result<Vec<&'a Value>, QueryError> {
+    Ok(compile(expression)?.select(value))
+}
+
+#[derive(Clone, Copy)]
+enum Step<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+struct Node<'a> {
+    value: &'a Value,
+    // Only tracked when the caller asked for the locations of the results.
+    path: Option<Vec<Step<'a>>>,
+}
+
+impl<'a> Node<'a> {
+    fn child(&self, step: Step<'a>, value: &'a Value) -> Node<'a> {
+        let path = self.path.as_ref().map(|path| {
+            let mut path = path.clone();
+            path.push(step);
+            path
+        });
+        Node { value, path }
+    }
+}
+
+impl Query {
+    // Returns the selected values in document order. Values selected more than once, e.g. by
+    // $[0,0], are returned more than once.
+    pub fn select<'a>(&self, value: &'a Value) -> Vec<&'a Value> {
+        let root = Node { value, path: None };
+        evaluate(&self.segments, root, value)
+            .into_iter()
+            .map(|node| node.value)
+            .collect()
+    }
+
+    // Like select(), but also returns the JSON Pointer of each value.
+    pub fn select_with_pointers<'a>(&self, value: &'a Value) -> Vec<(String, &'a Value)> {
+        let root = Node {
+            value,
+            path: Some(Vec::new()),
+        };
+        evaluate(&self.segments, root, value)
+            .into_iter()
+            .map(|node| {
+                let mut pointer = String::new();
+                for step in node.path.unwrap_or_default() {
+                    pointer.push('/');
+                    match step {
+                        Step::Key(key) => pointer.push_str(&escape_token(key)),
+                        Step::Index(index) => pointer.push_str(&index.to_string()),
+                    }
+                }
+                (pointer, node.value)
+            })
+            .collect()
+    }
+}
+
+fn evaluate<'a>(segments: &[Segment], start: Node<'a>, root: &'a Value) -> Vec<Node<'a>> {
+    let mut nodes = vec![start];
+    for segment in segments {
+        let mut selected = Vec::new();
+        for node in &nodes {
+            match segment {
+                Segment::Child(selectors) => select_children(selectors, node, root, &mut selected),
+                Segment::Descendant(selectors) => {
+                    select_descendants(selectors, node, root, &mut selected)
+                }
+            }
+        }
+        nodes = selected;
+    }
+    nodes
+}
+
+// The node itself comes first, then its descendants in document order.
+fn select_descendants<'a>(
+    selectors: &[Selector],
+    node: &Node<'a>,
+    root: &'a Value,
+    selected: &mut Vec<Node<'a>>,
+) {
+    select_children(selectors, node, root, selected);
+    for_each_child(node, |child| {
+        select_descendants(selectors, &child, root, selected)
+    });
+}
+
+fn for_each_child<'a>(node: &Node<'a>, mut callback: impl FnMut(Node<'a>)) {
+    match node.value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                callback(node.child(Step::Key(key), value));
+            }
+        }
+        Value::Array(array) => {
+            for (index, value) in array.iter().enumerate() {
+                callback(node.child(Step::Index(index), value));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn select_children<'a>(
+    selectors: &[Selector],
+    node: &Node<'a>,
+    root: &'a Value,
+    selected: &mut Vec<Node<'a>>,
+) {
+    for selector in selectors {
+        match (selector, node.value) {
+            (Selector::Name(name), Value::Object(object)) => {
+                if let Some((key, value)) = object.get_key_value(name) {
+                    selected.push(node.child(Step::Key(key), value));
+                }
+            }
+            (Selector::Wildcard, _) => for_each_child(node, |child| selected.push(child)),
+            (Selector::Index(index), Value::Array(array)) => {
+                if let Some(index) = normalize_index(*index, array.len()) {
+                    selected.push(node.child(Step::Index(index), &array[index]));
+                }
+            }
+            (Selector::Slice { start, end, step }, Value::Array(array)) => {
+                for index in slice_indices(*start, *end, *step, array.len()) {
+                    selected.push(node.child(Step::Index(index), &array[index]));
+                }
+            }
+            (Selector::Filter(filter), _) => for_each_child(node, |child| {
+                if filter.test(child.value, root) {
+                    selected.push(child);
+                }
+            }),
+            _ => {}
+        }
+    }
+}
+
+// Negative indices count from the end.
+fn normalize_index(index: i64, length: usize) -> Option<usize> {
+    let index = if index < 0 {
+        length as i64 + index
+    } else {
+        index
+    };
+    (0..length as i64)
+        .contains(&index)
+        .then_some(index as usize)
+}
+
+fn slice_indices(
+    start: Option<i64>,
+    end: Option<i64>,
+    step: Option<i64>,
+    length: usize,
+) -> Vec<usize> {
+    let length = length as i64;
+    let step = step.unwrap_or(1);
+    let normalize = |index: i64| if index < 0 { length + index } else { index };
+    let mut indices = Vec::new();
+    if step > 0 {
+        let lower = normalize(start.unwrap_or(0)).clamp(0, length);
+        let upper = normalize(end.unwrap_or(length)).clamp(0, length);
+        let mut index = lower;
+        while index < upper {
+            indices.push(index as usize);
+            index += step;
+        }
+    } else if step < 0 {
+        let upper = normalize(start.unwrap_or(length - 1)).clamp(-1, length - 1);
+        let lower = normalize(end.unwrap_or(-length - 1)).clamp(-1, length - 1);
+        let mut index = upper;
+        while lower < index {
+            indices.push(index as usize);
+            index += step;
+        }
+    }
+    indices
+}
+
+impl FilterQuery {
+    fn start<'a>(&self, current: &'a Value, root: &'a Value) -> &'a Value {
+        if self.relative {
+            current
+        } else {
+            root
+        }
+    }
+
+    fn is_singular(&self) -> bool {
+        self.segments.iter().all(|segment| match segment {
+            Segment::Child(selectors) => {
+                selectors.len() == 1
+                    && matches!(selectors[0], Selector::Name(_) | Selector::Index(_))
+            }
+            Segment::Descendant(_) => false,
+        })
+    }
+
+    // Only valid for singular queries, which select at most one value.
+    fn resolve<'a>(&self, current: &'a Value, root: &'a Value) -> Option<&'a Value> {
+        self.segments
+            .iter()
+            .try_fold(self.start(current, root), |value, segment| {
+                match (segment, value) {
+                    (Segment::Child(selectors), Value::Object(object)) => match &selectors[0] {
+                        Selector::Name(name) => object.get(name),
+                        _ => None,
+                    },
+                    (Segment::Child(selectors), Value::Array(array)) => match &selectors[0] {
+                        Selector::Index(index) => array.get(normalize_index(*index, array.len())?),
+                        _ => None,
+                    },
+                    _ => None,
+                }
+            })
+    }
+
+    fn selects_anything(&self, current: &Value, root: &Value) -> bool {
+        let start = Node {
+            value: self.start(current, root),
+            path: None,
+        };
+        !evaluate(&self.segments, start, root).is_empty()
+    }
+}
+
+impl Filter {
+    fn test(&self, current: &Value, root: &Value) -> bool {
+        match self {
+            Filter::Or(left, right) => left.test(current, root) || right.test(current, root),
+            Filter::And(left, right) => left.test(current, root) && right.test(current, root),
+            Filter::Not(filter) => !filter.test(current, root),
+            Filter::Exists(query) => query.selects_anything(current, root),
+            Filter::Compare(left, operator, right) => compare(
+                left.resolve(current, root),
+                *operator,
+                right.resolve(current, root),
+            ),
+        }
+    }
+}
+
+impl Comparable {
+    fn resolve<'a>(&'a self, current: &'a Value, root: &'a Value) -> Option<&'a Value> {
+        match self {
+            Comparable::Literal(value) => Some(value),
+            Comparable::Query(query) => query.resolve(current, root),
+        }
+    }
+}
+
+// A query that selects nothing only equals another one that selects nothing. Ordering is only
+// defined between two numbers or two strings; everything else compares as false.
+fn compare(left: Option<&Value>, operator: Operator, right: Option<&Value>) -> bool {
+    let equal = |left: Option<&Value>, right: Option<&Value>| left == right;
+    let less = |left: Option<&Value>, right: Option<&Value>| match (left, right) {
+        (Some(Value::Number(left)), Some(Value::Number(right))) => left < right,
+        (Some(Value::String(left)), Some(Value::String(right))) => left < right,
+        _ => false,
+    };
+    match operator {
+        Operator::Equal => equal(left, right),
+        Operator::NotEqual => !equal(left, right),
+        Operator::Less => less(left, right),
+        Operator::LessOrEqual => less(left, right) || equal(left, right),
+        Operator::Greater => less(right, left),
+        Operator::GreaterOrEqual => less(right, left) || equal(left, right),
+    }
+}
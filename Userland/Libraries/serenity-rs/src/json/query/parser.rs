@@ -0,0 +1,392 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+use super::{
+    Comparable,
+    Filter,
+    FilterQuery,
+    Operator,
+    QueryError,
+    QueryErrorKind,
+    Segment,
+    Selector,
+};
+use crate::json::{self, ParseOptions, Value};
+
+// How deeply filters and parentheses may nest, so a hostile expression cannot exhaust the stack.
+const MAX_NESTING: usize = 64;
+
+// Indices and slice bounds are limited to the integers an IEEE double can represent exactly.
+const MAX_INDEX: i64 = (1 << 53) - 1;
+
+pub(super) fn parse_query(expression: &str) -> Result<Vec<Segment>, QueryError> {
+    let mut parser = QueryParser {
+        input: expression,
+        bytes: expression.as_bytes(),
+        position: 0,
+        nesting: 0,
+    };
+    let result = parser.parse_root();
+    result.map_err(|kind| QueryError {
+        kind,
+        offset: parser.position,
+    })
+}
+
+struct QueryParser<'a> {
+    input: &'a str,
+    bytes: &'a [u8],
+    position: usize,
+    nesting: usize,
+}
+
+impl<'a> QueryParser<'a> {
+    fn peek(&self) -> Option<u8> { self.bytes.get(self.position).copied() }
+
+    fn peek_at(&self, offset: usize) -> Option<u8> {
+        self.bytes.get(self.position + offset).copied()
+    }
+
+    fn consume(&mut self, expected: &str) -> bool {
+        if !self.input[self.position..].starts_with(expected) {
+            return false;
+        }
+        self.position += expected.len();
+        true
+    }
+
+    fn must_consume(&mut self, expected: u8) -> Result<(), QueryErrorKind> {
+        if self.peek() != Some(expected) {
+            return Err(self.unexpected());
+        }
+        self.position += 1;
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.position += 1;
+        }
+    }
+
+    fn unexpected(&self) -> QueryErrorKind {
+        match self.input[self.position..].chars().next() {
+            Some(ch) => QueryErrorKind::UnexpectedCharacter(ch),
+            None => QueryErrorKind::UnexpectedEnd,
+        }
+    }
+
+    fn parse_root(&mut self) -> Result<Vec<Segment>, QueryErrorKind> {
+        if !self.consume("$") {
+            return Err(QueryErrorKind::ExpectedRoot);
+        }
+        let segments = self.parse_segments()?;
+        if self.position != self.bytes.len() {
+            return Err(self.unexpected());
+        }
+        Ok(segments)
+    }
+
+    fn parse_segments(&mut self) -> Result<Vec<Segment>, QueryErrorKind> {
+        let mut segments = Vec::new();
+        loop {
+            // Whitespace may separate segments, but must not be consumed if no segment follows,
+            // as it could be the end of the query.
+            let start = self.position;
+            self.skip_whitespace();
+            match (self.peek(), self.peek_at(1)) {
+                (Some(b'.'), Some(b'.')) => {
+                    self.position += 2;
+                    let selectors = match self.peek() {
+                        Some(b'[') => self.parse_bracketed_selectors()?,
+                        _ => vec![self.parse_shorthand_selector()?],
+                    };
+                    segments.push(Segment::Descendant(selectors));
+                }
+                (Some(b'.'), _) => {
+                    self.position += 1;
+                    segments.push(Segment::Child(vec![self.parse_shorthand_selector()?]));
+                }
+                (Some(b'['), _) => {
+                    segments.push(Segment::Child(self.parse_bracketed_selectors()?));
+                }
+                _ => {
+                    self.position = start;
+                    return Ok(segments);
+                }
+            }
+        }
+    }
+
+    // The part after a dot: a wildcard or a member name.
+    fn parse_shorthand_selector(&mut self) -> Result<Selector, QueryErrorKind> {
+        if self.consume("*") {
+            return Ok(Selector::Wildcard);
+        }
+        let name_length = self.input[self.position..]
+            .char_indices()
+            .find(|(index, ch)| {
+                let allowed = ch.is_alphabetic() || *ch == '_' || !ch.is_ascii();
+                !(allowed || (*index > 0 && ch.is_ascii_digit()))
+            })
+            .map_or(self.bytes.len() - self.position, |(index, _)| index);
+        if name_length == 0 {
+            return Err(self.unexpected());
+        }
+        let name = &self.input[self.position..self.position + name_length];
+        self.position += name_length;
+        Ok(Selector::Name(name.to_string()))
+    }
+
+    fn parse_bracketed_selectors(&mut self) -> Result<Vec<Selector>, QueryErrorKind> {
+        self.must_consume(b'[')?;
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_whitespace();
+            selectors.push(self.parse_selector()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(selectors);
+                }
+                _ => return Err(self.unexpected()),
+            }
+        }
+    }
+
+    fn parse_selector(&mut self) -> Result<Selector, QueryErrorKind> {
+        match self.peek() {
+            Some(b'\'' | b'"') => Ok(Selector::Name(self.parse_string()?)),
+            Some(b'*') => {
+                self.position += 1;
+                Ok(Selector::Wildcard)
+            }
+            Some(b'?') => {
+                self.position += 1;
+                self.skip_whitespace();
+                Ok(Selector::Filter(self.parse_filter()?))
+            }
+            Some(b'-' | b'0'..=b'9' | b':') => self.parse_index_or_slice(),
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn parse_index_or_slice(&mut self) -> Result<Selector, QueryErrorKind> {
+        let start = self.parse_optional_integer()?;
+        self.skip_whitespace();
+        if !self.consume(":") {
+            return start.map(Selector::Index).ok_or_else(|| self.unexpected());
+        }
+        self.skip_whitespace();
+        let end = self.parse_optional_integer()?;
+        self.skip_whitespace();
+        let step = if self.consume(":") {
+            self.skip_whitespace();
+            self.parse_optional_integer()?
+        } else {
+            None
+        };
+        Ok(Selector::Slice { start, end, step })
+    }
+
+    // Integers are written without leading zeros, and -0 is not allowed.
+    fn parse_optional_integer(&mut self) -> Result<Option<i64>, QueryErrorKind> {
+        let start = self.position;
+        if self.peek() == Some(b'-') {
+            self.position += 1;
+        }
+        let digits_start = self.position;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.position += 1;
+        }
+        let digits = &self.input[digits_start..self.position];
+        if digits.is_empty() {
+            if self.position != start {
+                return Err(self.unexpected());
+            }
+            return Ok(None);
+        }
+        let negative = digits_start != start;
+        if (digits.len() > 1 && digits.starts_with('0')) || (negative && digits == "0") {
+            self.position = start;
+            return Err(QueryErrorKind::InvalidLiteral);
+        }
+        match self.input[start..self.position].parse::<i64>() {
+            Ok(value) if (-MAX_INDEX..=MAX_INDEX).contains(&value) => Ok(Some(value)),
+            _ => {
+                self.position = start;
+                Err(QueryErrorKind::IndexOutOfRange)
+            }
+        }
+    }
+
+    // String literals use JSON escapes, in either single or double quotes.
+    fn parse_string(&mut self) -> Result<String, QueryErrorKind> {
+        let start = self.position;
+        let quote = self.bytes[start];
+        self.position += 1;
+        loop {
+            match self.peek() {
+                // A trailing backslash still ends up at the end of the input, not past it.
+                Some(b'\\') => self.position = (self.position + 2).min(self.bytes.len()),
+                Some(byte) if byte == quote => break,
+                Some(_) => self.position += 1,
+                None => return Err(QueryErrorKind::UnexpectedEnd),
+            }
+        }
+        self.position += 1;
+        let literal = self.input.get(start..self.position);
+        let options = ParseOptions::default().with_single_quotes();
+        literal
+            .and_then(
+                |literal| match json::parse_with_options(literal, &options) {
+                    Ok(Value::String(string)) => Some(string),
+                    _ => None,
+                },
+            )
+            .ok_or_else(|| {
+                self.position = start;
+                QueryErrorKind::InvalidLiteral
+            })
+    }
+
+    fn enter(&mut self) -> Result<(), QueryErrorKind> {
+        self.nesting += 1;
+        if self.nesting > MAX_NESTING {
+            return Err(QueryErrorKind::TooDeeplyNested);
+        }
+        Ok(())
+    }
+
+    fn parse_filter(&mut self) -> Result<Filter, QueryErrorKind> {
+        self.enter()?;
+        let mut filter = self.parse_and()?;
+        loop {
+            self.skip_whitespace();
+            if !self.consume("||") {
+                break;
+            }
+            self.skip_whitespace();
+            filter = Filter::Or(Box::new(filter), Box::new(self.parse_and()?));
+        }
+        self.nesting -= 1;
+        Ok(filter)
+    }
+
+    fn parse_and(&mut self) -> Result<Filter, QueryErrorKind> {
+        let mut filter = self.parse_basic()?;
+        loop {
+            self.skip_whitespace();
+            if !self.consume("&&") {
+                break;
+            }
+            self.skip_whitespace();
+            filter = Filter::And(Box::new(filter), Box::new(self.parse_basic()?));
+        }
+        Ok(filter)
+    }
+
+    fn parse_basic(&mut self) -> Result<Filter, QueryErrorKind> {
+        if self.consume("!") {
+            self.skip_whitespace();
+            self.enter()?;
+            let filter = self.parse_basic()?;
+            self.nesting -= 1;
+            return Ok(Filter::Not(Box::new(filter)));
+        }
+        if self.consume("(") {
+            self.skip_whitespace();
+            let filter = self.parse_filter()?;
+            self.skip_whitespace();
+            self.must_consume(b')')?;
+            return Ok(filter);
+        }
+
+        let left_start = self.position;
+        let left = self.parse_comparable()?;
+        self.skip_whitespace();
+        let operator = match self.parse_operator() {
+            Some(operator) => operator,
+            None => {
+                return match left {
+                    Comparable::Query(query) => Ok(Filter::Exists(query)),
+                    // A literal on its own is not a test.
+                    Comparable::Literal(_) => Err(self.unexpected()),
+                };
+            }
+        };
+        self.skip_whitespace();
+        let right_start = self.position;
+        let right = self.parse_comparable()?;
+        for (comparable, start) in [(&left, left_start), (&right, right_start)] {
+            if let Comparable::Query(query) = comparable {
+                if !query.is_singular() {
+                    self.position = start;
+                    return Err(QueryErrorKind::NotSingular);
+                }
+            }
+        }
+        Ok(Filter::Compare(left, operator, right))
+    }
+
+    fn parse_operator(&mut self) -> Option<Operator> {
+        let operators = [
+            ("==", Operator::Equal),
+            ("!=", Operator::NotEqual),
+            ("<=", Operator::LessOrEqual),
+            (">=", Operator::GreaterOrEqual),
+            ("<", Operator::Less),
+            (">", Operator::Greater),
+        ];
+        operators
+            .into_iter()
+            .find(|(token, _)| self.consume(token))
+            .map(|(_, operator)| operator)
+    }
+
+    fn parse_comparable(&mut self) -> Result<Comparable, QueryErrorKind> {
+        match self.peek() {
+            Some(byte @ (b'@' | b'$')) => {
+                self.position += 1;
+                self.enter()?;
+                let segments = self.parse_segments()?;
+                self.nesting -= 1;
+                Ok(Comparable::Query(FilterQuery {
+                    relative: byte == b'@',
+                    segments,
+                }))
+            }
+            Some(b'\'' | b'"') => Ok(Comparable::Literal(Value::String(self.parse_string()?))),
+            Some(b'-' | b'0'..=b'9') => self.parse_number().map(Comparable::Literal),
+            Some(b't' | b'f' | b'n') => {
+                for (keyword, value) in [
+                    ("true", Value::Bool(true)),
+                    ("false", Value::Bool(false)),
+                    ("null", Value::Null),
+                ] {
+                    if self.consume(keyword) {
+                        return Ok(Comparable::Literal(value));
+                    }
+                }
+                Err(self.unexpected())
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Value, QueryErrorKind> {
+        let start = self.position;
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.peek() {
+            self.position += 1;
+        }
+        json::parse(&self.input[start..self.position]).map_err(|_| {
+            self.position = start;
+            QueryErrorKind::InvalidLiteral
+        })
+    }
+}
@@ -0,0 +1,580 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// A pull-based JSON reader that produces one event at a time from any std::io::Read, so large
+// documents like /proc/all can be processed without holding all of them in memory.
+
+use std::io::{BufRead, BufReader, Read};
+
+use super::parser::{identifier_prefix, insert_member, parse_number_literal};
+use super::{Map, Number, ParseError, ParseErrorKind, ParseOptions, Value};
+
+#[derive(Debug)]
+pub enum Event {
+    StartObject,
+    EndObject,
+    StartArray,
+    EndArray,
+    Key(String),
+    String(String),
+    Number(Number),
+    Bool(bool),
+    Null,
+}
+
+#[derive(Debug)]
+pub enum ReaderError {
+    Io(std::io::Error),
+    Parse(ParseError),
+}
+
+impl std::fmt::Display for ReaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReaderError::Io(error) => write!(f, "{}", error),
+            ReaderError::Parse(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ReaderError {}
+
+impl From<std::io::Error> for ReaderError {
+    fn from(error: std::io::Error) -> ReaderError { ReaderError::Io(error) }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Container {
+    Array,
+    Object,
+}
+
+// What the reader will accept next.
+#[derive(Clone, Copy, PartialEq)]
+enum Expect {
+    Value,
+    ValueOrEndArray,
+    Key,
+    KeyOrEndObject,
+    CommaOrEnd,
+    EndOfInput,
+}
+
+pub struct Reader<R: Read> {
+    input: BufReader<R>,
+    containers: Vec<Container>,
+    expect: Expect,
+    offset: usize,
+    line: usize,
+    column: usize,
+    // Offset, line and column where the most recent key started.
+    key_position: (usize, usize, usize),
+    options: ParseOptions,
+    element_count: usize,
+    failed: bool,
+}
+
+impl<R: Read> Reader<R> {
+    pub fn new(input: R) -> Reader<R> { Reader::with_options(input, ParseOptions::default()) }
+
+    // The syntax options apply to all events, the duplicate key policy to the values built by
+    // read_value().
+    pub fn with_options(input: R, options: ParseOptions) -> Reader<R> {
+        Reader {
+            input: BufReader::new(input),
+            containers: Vec::new(),
+            expect: Expect::Value,
+            offset: 0,
+            line: 1,
+            column: 1,
+            key_position: (0, 1, 1),
+            options,
+            element_count: 0,
+            failed: false,
+        }
+    }
+
+    // Number of objects and arrays that are currently open.
+    pub fn depth(&self) -> usize { self.containers.len() }
+
+    // Returns the next event, or None once the document has been read completely.
+    pub fn next_event(&mut self) -> Result<Option<Event>, ReaderError> {
+        loop {
+            self.skip_whitespace()?;
+            let next = self.peek()?;
+            match (self.expect, next) {
+                (Expect::EndOfInput, None) => return Ok(None),
+                (Expect::EndOfInput, Some(byte)) => return Err(self.error(unexpected_byte(byte))),
+                (Expect::CommaOrEnd, Some(b',')) => {
+                    self.consume();
+                    let allow_trailing_commas = self.options.allow_trailing_commas;
+                    self.expect = match (self.containers.last(), allow_trailing_commas) {
+                        (Some(Container::Object), false) => Expect::Key,
+                        (Some(Container::Object), true) => Expect::KeyOrEndObject,
+                        (_, false) => Expect::Value,
+                        (_, true) => Expect::ValueOrEndArray,
+                    };
+                }
+                (Expect::CommaOrEnd, Some(b']'))
+                    if self.containers.last() == Some(&Container::Array) =>
+                {
+                    return self.end_container(Event::EndArray);
+                }
+                (Expect::CommaOrEnd, Some(b'}'))
+                    if self.containers.last() == Some(&Container::Object) =>
+                {
+                    return self.end_container(Event::EndObject);
+                }
+                (Expect::CommaOrEnd, Some(byte)) => return Err(self.error(unexpected_byte(byte))),
+                (Expect::CommaOrEnd, None) => return Err(self.error(ParseErrorKind::UnexpectedEof)),
+                (Expect::KeyOrEndObject, Some(b'}')) => {
+                    return self.end_container(Event::EndObject)
+                }
+                (Expect::Key | Expect::KeyOrEndObject, _) => {
+                    self.key_position = (self.offset, self.line, self.column);
+                    let key = self.read_key()?;
+                    self.skip_whitespace()?;
+                    self.must_consume(b':')?;
+                    self.expect = Expect::Value;
+                    return Ok(Some(Event::Key(key)));
+                }
+                (Expect::ValueOrEndArray, Some(b']')) => {
+                    return self.end_container(Event::EndArray)
+                }
+                (Expect::Value | Expect::ValueOrEndArray, _) => {
+                    return self.read_value_event().map(Some)
+                }
+            }
+        }
+    }
+
+    // Reads the next value as a whole, along with everything nested inside it.
+    // Returns None without consuming anything if the next event does not start a value,
+    // i.e. at the end of an array or document, or where an object key is expected.
+    pub fn read_value(&mut self) -> Result<Option<Value>, ReaderError> {
+        if !self.next_is_value()? {
+            return Ok(None);
+        }
+        let event = self.read_value_event()?;
+        self.build_value(event).map(Some)
+    }
+
+    // Like read_value(), but discards the value without building it.
+    pub fn skip_value(&mut self) -> Result<bool, ReaderError> {
+        if !self.next_is_value()? {
+            return Ok(false);
+        }
+        let depth = self.depth();
+        self.read_value_event()?;
+        while self.depth() > depth {
+            self.next_event()?;
+        }
+        Ok(true)
+    }
+
+    fn next_is_value(&mut self) -> Result<bool, ReaderError> {
+        self.skip_whitespace()?;
+        match self.expect {
+            Expect::Value => Ok(true),
+            Expect::ValueOrEndArray => Ok(self.peek()? != Some(b']')),
+            Expect::CommaOrEnd if self.containers.last() == Some(&Container::Array) => {
+                if self.peek()? != Some(b',') {
+                    return Ok(false);
+                }
+                self.consume();
+                self.expect = Expect::Value;
+                self.skip_whitespace()?;
+                if self.options.allow_trailing_commas && self.peek()? == Some(b']') {
+                    self.expect = Expect::ValueOrEndArray;
+                    return Ok(false);
+                }
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn build_value(&mut self, event: Event) -> Result<Value, ReaderError> {
+        match event {
+            Event::StartObject => {
+                let mut object = Map::new();
+                loop {
+                    match self.next_event()? {
+                        Some(Event::Key(key)) => {
+                            let key_position = self.key_position;
+                            let event = self.read_value_event()?;
+                            let value = self.build_value(event)?;
+                            let duplicate_keys = self.options.duplicate_keys;
+                            insert_member(&mut object, key, value, duplicate_keys).map_err(
+                                |kind| {
+                                    let (offset, line, column) = key_position;
+                                    ReaderError::Parse(ParseError::without_excerpt(
+                                        kind, offset, line, column,
+                                    ))
+                                },
+                            )?;
+                        }
+                        _ => return Ok(Value::Object(object)),
+                    }
+                }
+            }
+            Event::StartArray => {
+                let mut array = Vec::new();
+                loop {
+                    match self.next_event()? {
+                        Some(Event::EndArray) | None => return Ok(Value::Array(array)),
+                        Some(event) => array.push(self.build_value(event)?),
+                    }
+                }
+            }
+            Event::String(string) => Ok(Value::String(string)),
+            Event::Number(number) => Ok(Value::Number(number)),
+            Event::Bool(value) => Ok(Value::Bool(value)),
+            Event::Null => Ok(Value::Null),
+            Event::EndObject | Event::EndArray | Event::Key(_) => {
+                unreachable!("build_value() called with {:?}", event)
+            }
+        }
+    }
+
+    fn read_value_event(&mut self) -> Result<Event, ReaderError> {
+        self.skip_whitespace()?;
+        if !self.containers.is_empty() {
+            self.element_count += 1;
+            let result = self.options.check_element_count(self.element_count);
+            result.map_err(|kind| self.error(kind))?;
+        }
+        let event = match self.peek()? {
+            Some(b'{') => {
+                self.enter_container(Container::Object)?;
+                self.expect = Expect::KeyOrEndObject;
+                return Ok(Event::StartObject);
+            }
+            Some(b'[') => {
+                self.enter_container(Container::Array)?;
+                self.expect = Expect::ValueOrEndArray;
+                return Ok(Event::StartArray);
+            }
+            Some(b'"') => Event::String(self.read_string(b'"')?),
+            Some(b'\'') if self.options.allow_single_quotes => {
+                Event::String(self.read_string(b'\'')?)
+            }
+            Some(b'0'..=b'9' | b'-') => Event::Number(self.read_number()?),
+            Some(b't') => {
+                self.read_literal(b"true", ParseErrorKind::ExpectedTrue)?;
+                Event::Bool(true)
+            }
+            Some(b'f') => {
+                self.read_literal(b"false", ParseErrorKind::ExpectedFalse)?;
+                Event::Bool(false)
+            }
+            Some(b'n') => {
+                self.read_literal(b"null", ParseErrorKind::ExpectedNull)?;
+                Event::Null
+            }
+            Some(byte) => return Err(self.error(unexpected_byte(byte))),
+            None => return Err(self.error(ParseErrorKind::UnexpectedEof)),
+        };
+        self.end_value();
+        Ok(event)
+    }
+
+    fn enter_container(&mut self, container: Container) -> Result<(), ReaderError> {
+        let result = self.options.check_depth(self.containers.len() + 1);
+        result.map_err(|kind| self.error(kind))?;
+        self.consume();
+        self.containers.push(container);
+        Ok(())
+    }
+
+    fn end_container(&mut self, event: Event) -> Result<Option<Event>, ReaderError> {
+        self.consume();
+        self.containers.pop();
+        self.end_value();
+        Ok(Some(event))
+    }
+
+    fn end_value(&mut self) {
+        self.expect = if self.containers.is_empty() {
+            Expect::EndOfInput
+        } else {
+            Expect::CommaOrEnd
+        };
+    }
+
+    fn read_key(&mut self) -> Result<String, ReaderError> {
+        match self.peek()? {
+            Some(b'\'') if self.options.allow_single_quotes => self.read_string(b'\''),
+            Some(b'"') | None => self.read_string(b'"'),
+            Some(_) if self.options.allow_unquoted_keys => self.read_identifier(),
+            Some(_) => Err(self.error(ParseErrorKind::ExpectedCharacter('"'))),
+        }
+    }
+
+    fn read_identifier(&mut self) -> Result<String, ReaderError> {
+        let mut bytes = Vec::new();
+        while let Some(byte) = self.peek()? {
+            if !(byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'$' || byte >= 0x80) {
+                break;
+            }
+            bytes.push(byte);
+            self.consume();
+            self.check_string_length(bytes.len())?;
+        }
+        let (offset, line, column) = self.key_position;
+        let error =
+            |kind| ReaderError::Parse(ParseError::without_excerpt(kind, offset, line, column));
+        let identifier =
+            String::from_utf8(bytes).map_err(|_| error(ParseErrorKind::InvalidUtf8))?;
+        if identifier.is_empty() || identifier_prefix(&identifier) != identifier {
+            let ch = identifier
+                .chars()
+                .next()
+                .unwrap_or(char::REPLACEMENT_CHARACTER);
+            return Err(error(ParseErrorKind::UnexpectedCharacter(ch)));
+        }
+        Ok(identifier)
+    }
+
+    fn read_string(&mut self, quote: u8) -> Result<String, ReaderError> {
+        self.must_consume(quote)?;
+        let mut bytes = Vec::new();
+        loop {
+            match self.peek()? {
+                Some(byte) if byte == quote => break,
+                Some(b'\\') => {
+                    self.consume();
+                    let mut buffer = [0u8; 4];
+                    let ch = self.read_escape()?;
+                    bytes.extend_from_slice(ch.encode_utf8(&mut buffer).as_bytes());
+                    self.check_string_length(bytes.len())?;
+                }
+                Some(byte) if byte < 0x20 => {
+                    return Err(self.error(ParseErrorKind::UnexpectedControlCharacter))
+                }
+                Some(byte) => {
+                    self.consume();
+                    bytes.push(byte);
+                    self.check_string_length(bytes.len())?;
+                }
+                None => return Err(self.error(ParseErrorKind::UnexpectedEof)),
+            }
+        }
+        let string =
+            String::from_utf8(bytes).map_err(|_| self.error(ParseErrorKind::InvalidUtf8))?;
+        self.consume();
+        Ok(string)
+    }
+
+    fn read_escape(&mut self) -> Result<char, ReaderError> {
+        let unescaped = match self.peek()? {
+            Some(b'"') => '"',
+            Some(b'\'') if self.options.allow_single_quotes => '\'',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => 0x8 as char,
+            Some(b'f') => 0xc as char,
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                self.consume();
+                return self.read_unicode_escape();
+            }
+            Some(byte) => return Err(self.error(unexpected_byte(byte))),
+            None => return Err(self.error(ParseErrorKind::UnexpectedEof)),
+        };
+        self.consume();
+        Ok(unescaped)
+    }
+
+    fn read_unicode_escape(&mut self) -> Result<char, ReaderError> {
+        let code_unit = self.read_hex_code_unit()?;
+        match code_unit {
+            0xd800..=0xdbff => {
+                // A high surrogate has to be followed by an escaped low surrogate.
+                if self.peek()? != Some(b'\\') {
+                    return Err(self.error(ParseErrorKind::LoneSurrogate(code_unit)));
+                }
+                self.consume();
+                if self.peek()? != Some(b'u') {
+                    return Err(self.error(ParseErrorKind::LoneSurrogate(code_unit)));
+                }
+                self.consume();
+                let low_surrogate = self.read_hex_code_unit()?;
+                if !(0xdc00..=0xdfff).contains(&low_surrogate) {
+                    return Err(self.error(ParseErrorKind::LoneSurrogate(code_unit)));
+                }
+                let code_point = 0x10000
+                    + (((code_unit as u32) - 0xd800) << 10)
+                    + ((low_surrogate as u32) - 0xdc00);
+                Ok(char::from_u32(code_point).unwrap())
+            }
+            0xdc00..=0xdfff => Err(self.error(ParseErrorKind::LoneSurrogate(code_unit))),
+            _ => Ok(char::from_u32(code_unit as u32).unwrap()),
+        }
+    }
+
+    fn read_hex_code_unit(&mut self) -> Result<u16, ReaderError> {
+        let mut code_unit = 0u16;
+        for _ in 0..4 {
+            let digit = match self.peek()? {
+                Some(byte) => (byte as char)
+                    .to_digit(16)
+                    .ok_or_else(|| self.error(unexpected_byte(byte)))?,
+                None => return Err(self.error(ParseErrorKind::UnexpectedEof)),
+            };
+            self.consume();
+            code_unit = (code_unit << 4) | digit as u16;
+        }
+        Ok(code_unit)
+    }
+
+    fn read_number(&mut self) -> Result<Number, ReaderError> {
+        let mut string = String::new();
+        let allow_hex_numbers = self.options.allow_hex_numbers;
+        while let Some(byte) = self.peek()? {
+            let is_number_character = matches!(byte, b'-' | b'+' | b'.' | b'0'..=b'9')
+                || if allow_hex_numbers {
+                    byte.is_ascii_hexdigit() || byte == b'x' || byte == b'X'
+                } else {
+                    byte == b'e' || byte == b'E'
+                };
+            if !is_number_character {
+                break;
+            }
+            string.push(byte as char);
+            self.consume();
+            self.check_string_length(string.len())?;
+        }
+        parse_number_literal(&string, &self.options).map_err(|kind| self.error(kind))
+    }
+
+    fn read_literal(&mut self, literal: &[u8], error: ParseErrorKind) -> Result<(), ReaderError> {
+        for expected in literal {
+            if self.peek()? != Some(*expected) {
+                return Err(self.error(error));
+            }
+            self.consume();
+        }
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) -> Result<(), ReaderError> {
+        loop {
+            match self.peek()? {
+                Some(b' ' | b'\n' | b'\r' | b'\t') => self.consume(),
+                Some(b'/') if self.options.allow_comments => self.skip_comment()?,
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn skip_comment(&mut self) -> Result<(), ReaderError> {
+        let start = (self.offset, self.line, self.column);
+        self.consume();
+        match self.peek()? {
+            Some(b'/') => {
+                while !matches!(self.peek()?, Some(b'\n') | None) {
+                    self.consume();
+                }
+            }
+            Some(b'*') => {
+                self.consume();
+                let mut previous = 0;
+                loop {
+                    match self.peek()? {
+                        Some(b'/') if previous == b'*' => break,
+                        Some(byte) => previous = byte,
+                        None => return Err(self.error(ParseErrorKind::UnexpectedEof)),
+                    }
+                    self.consume();
+                }
+                self.consume();
+            }
+            _ => {
+                // A lone '/' can only be an error, so report it where it was.
+                let (offset, line, column) = start;
+                return Err(ReaderError::Parse(ParseError::without_excerpt(
+                    ParseErrorKind::UnexpectedCharacter('/'),
+                    offset,
+                    line,
+                    column,
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn must_consume(&mut self, expected: u8) -> Result<(), ReaderError> {
+        if self.peek()? != Some(expected) {
+            return Err(self.error(ParseErrorKind::ExpectedCharacter(expected as char)));
+        }
+        self.consume();
+        Ok(())
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, ReaderError> {
+        loop {
+            match self.input.fill_buf() {
+                Ok(buffer) => return Ok(buffer.first().copied()),
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(ReaderError::Io(error)),
+            }
+        }
+    }
+
+    // Consumes the byte that was last returned by peek().
+    fn consume(&mut self) {
+        let byte = self.input.buffer()[0];
+        self.input.consume(1);
+        self.offset += 1;
+        if byte == b'\n' {
+            self.line += 1;
+            self.column = 1;
+        } else if byte & 0xc0 != 0x80 {
+            // Continuation bytes of a UTF-8 sequence do not start a new column.
+            self.column += 1;
+        }
+    }
+
+    // Checked as the input is read, so an overlong string is rejected before it is buffered.
+    fn check_string_length(&self, length: usize) -> Result<(), ReaderError> {
+        let result = self.options.check_string_length(length);
+        result.map_err(|kind| self.error(kind))
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ReaderError {
+        ReaderError::Parse(ParseError::without_excerpt(
+            kind,
+            self.offset,
+            self.line,
+            self.column,
+        ))
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<Event, ReaderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Stop after the first error instead of reporting it over and over.
+        if self.failed {
+            return None;
+        }
+        let result = self.next_event().transpose();
+        self.failed = matches!(result, Some(Err(_)));
+        result
+    }
+}
+
+fn unexpected_byte(byte: u8) -> ParseErrorKind {
+    if byte.is_ascii() {
+        ParseErrorKind::UnexpectedCharacter(byte as char)
+    } else {
+        ParseErrorKind::UnexpectedCharacter(char::REPLACEMENT_CHARACTER)
+    }
+}
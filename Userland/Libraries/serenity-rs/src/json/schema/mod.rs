@@ -0,0 +1,593 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// JSON Schema validation, covering the parts of draft 2020-12 that are useful for checking
+// config files and ProcFS documents: boolean schemas, type, enum, minimum, maximum,
+// exclusiveMinimum, exclusiveMaximum, pattern, properties, required, items, and $ref to
+// locations within the same document (usually "#/$defs/..."). Other keywords are ignored.
+//
+//     let schema = json::schema::compile(&json::parse(SCHEMA)?)?;
+//     if let Err(errors) = schema.validate(&config) {
+//         for error in errors {
+//             eprintln!("{}", error);
+//         }
+//     }
+
+mod regex;
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use regex::Regex;
+pub use regex::RegexError;
+
+use super::{pointer, Map, Number, Value};
+
+// How many $ref hops may be followed without moving on to a nested value, so that a schema which
+// refers to itself in a loop cannot recurse forever.
+const MAX_REFERENCE_DEPTH: usize = 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Type {
+    Null,
+    Boolean,
+    Object,
+    Array,
+    Number,
+    String,
+    Integer,
+}
+
+impl Type {
+    fn from_name(name: &str) -> Option<Type> {
+        Some(match name {
+            "null" => Type::Null,
+            "boolean" => Type::Boolean,
+            "object" => Type::Object,
+            "array" => Type::Array,
+            "number" => Type::Number,
+            "string" => Type::String,
+            "integer" => Type::Integer,
+            _ => return None,
+        })
+    }
+
+    // Integral numbers are reported as "integer", as that is the narrower of the two types.
+    fn of(value: &Value) -> Type {
+        match value {
+            Value::Null => Type::Null,
+            Value::Bool(_) => Type::Boolean,
+            Value::Number(number) if number.is_integral() => Type::Integer,
+            Value::Number(_) => Type::Number,
+            Value::String(_) => Type::String,
+            Value::Array(_) => Type::Array,
+            Value::Object(_) => Type::Object,
+        }
+    }
+
+    fn matches(&self, value: &Value) -> bool {
+        match (self, Type::of(value)) {
+            (Type::Number, Type::Integer) => true,
+            (expected, actual) => *expected == actual,
+        }
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Type::Null => "null",
+            Type::Boolean => "boolean",
+            Type::Object => "object",
+            Type::Array => "array",
+            Type::Number => "number",
+            Type::String => "string",
+            Type::Integer => "integer",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug)]
+pub enum SchemaErrorKind {
+    // A keyword has a value of the wrong kind, e.g. "required": "name".
+    InvalidKeyword {
+        keyword: &'static str,
+        expected: &'static str,
+    },
+    InvalidPattern(RegexError),
+    // A schema that is neither an object nor a boolean.
+    NotASchema,
+    UnresolvedReference(String),
+    UnsupportedReference(String),
+}
+
+impl Display for SchemaErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaErrorKind::InvalidKeyword { keyword, expected } => {
+                write!(f, "\"{}\" must be {}", keyword, expected)
+            }
+            SchemaErrorKind::InvalidPattern(error) => write!(f, "invalid pattern: {}", error),
+            SchemaErrorKind::NotASchema => write!(f, "a schema must be an object or a boolean"),
+            SchemaErrorKind::UnresolvedReference(reference) => {
+                write!(f, "cannot resolve reference \"{}\"", reference)
+            }
+            SchemaErrorKind::UnsupportedReference(reference) => write!(
+                f,
+                "reference \"{}\" does not point into this document",
+                reference
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SchemaError {
+    kind: SchemaErrorKind,
+    path: String,
+}
+
+impl SchemaError {
+    pub fn kind(&self) -> &SchemaErrorKind { &self.kind }
+
+    // JSON Pointer to the offending part of the schema.
+    pub fn path(&self) -> &str { &self.path }
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid schema: {}", self.kind)?;
+        if !self.path.is_empty() {
+            write!(f, " at {}", self.path)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+#[derive(Debug)]
+pub enum ValidationErrorKind {
+    // The schema is `false`, which no value satisfies.
+    False,
+    Type { expected: Vec<Type>, actual: Type },
+    Enum,
+    Minimum(Number),
+    Maximum(Number),
+    ExclusiveMinimum(Number),
+    ExclusiveMaximum(Number),
+    Pattern(String),
+    Required(String),
+    ReferenceLoop,
+}
+
+impl Display for ValidationErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let bound = |number: &Number| Value::Number(number.clone());
+        match self {
+            ValidationErrorKind::False => write!(f, "no value is allowed here"),
+            ValidationErrorKind::Type { expected, actual } => {
+                let expected = expected
+                    .iter()
+                    .map(|expected| expected.to_string())
+                    .collect::<Vec<_>>();
+                write!(f, "expected {}, found {}", expected.join(" or "), actual)
+            }
+            ValidationErrorKind::Enum => write!(f, "value is not one of the allowed values"),
+            ValidationErrorKind::Minimum(minimum) => {
+                write!(f, "value is less than the minimum of {}", bound(minimum))
+            }
+            ValidationErrorKind::Maximum(maximum) => {
+                write!(f, "value is greater than the maximum of {}", bound(maximum))
+            }
+            ValidationErrorKind::ExclusiveMinimum(minimum) => {
+                write!(f, "value must be greater than {}", bound(minimum))
+            }
+            ValidationErrorKind::ExclusiveMaximum(maximum) => {
+                write!(f, "value must be less than {}", bound(maximum))
+            }
+            ValidationErrorKind::Pattern(pattern) => {
+                write!(f, "string does not match the pattern \"{}\"", pattern)
+            }
+            ValidationErrorKind::Required(property) => {
+                write!(f, "missing required property \"{}\"", property)
+            }
+            ValidationErrorKind::ReferenceLoop => write!(f, "$ref loops back on itself"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ValidationError {
+    kind: ValidationErrorKind,
+    path: String,
+}
+
+impl ValidationError {
+    pub fn kind(&self) -> &ValidationErrorKind { &self.kind }
+
+    // JSON Pointer to the offending value, e.g. "/processors/0/brand".
+    pub fn path(&self) -> &str { &self.path }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.kind)
+        } else {
+            write!(f, "{}: {}", self.path, self.kind)
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+enum Node {
+    Boolean(bool),
+    Keywords(Box<Keywords>),
+}
+
+#[derive(Default)]
+struct Keywords {
+    types: Option<Vec<Type>>,
+    enumeration: Option<Vec<Value>>,
+    minimum: Option<Number>,
+    maximum: Option<Number>,
+    exclusive_minimum: Option<Number>,
+    exclusive_maximum: Option<Number>,
+    pattern: Option<(String, Regex)>,
+    properties: Vec<(String, usize)>,
+    required: Vec<String>,
+    items: Option<usize>,
+    reference: Option<usize>,
+}
+
+// A compiled schema. Subschemas are stored in a flat list and refer to each other by index, which
+// allows recursive schemas.
+pub struct Schema {
+    nodes: Vec<Node>,
+}
+
+pub fn compile(schema: &Value) -> Result<Schema, SchemaError> {
+    let mut compiler = Compiler {
+        root: schema,
+        nodes: Vec::new(),
+        compiled: HashMap::new(),
+    };
+    compiler.compile(schema, String::new())?;
+    Ok(Schema {
+        nodes: compiler.nodes,
+    })
+}
+
+struct Compiler<'a> {
+    root: &'a Value,
+    nodes: Vec<Node>,
+    // Index of the node compiled for each location in the schema document.
+    compiled: HashMap<String, usize>,
+}
+
+impl<'a> Compiler<'a> {
+    fn compile(&mut self, schema: &'a Value, path: String) -> Result<usize, SchemaError> {
+        if let Some(index) = self.compiled.get(&path) {
+            return Ok(*index);
+        }
+        // Reserve the slot first, so a $ref back to this schema finds it.
+        let index = self.nodes.len();
+        self.nodes.push(Node::Boolean(true));
+        self.compiled.insert(path.clone(), index);
+
+        self.nodes[index] = match schema {
+            Value::Bool(value) => Node::Boolean(*value),
+            Value::Object(keywords) => {
+                Node::Keywords(Box::new(self.compile_keywords(keywords, &path)?))
+            }
+            _ => {
+                return Err(SchemaError {
+                    kind: SchemaErrorKind::NotASchema,
+                    path,
+                })
+            }
+        };
+        Ok(index)
+    }
+
+    fn compile_keywords(&mut self, schema: &'a Map, path: &str) -> Result<Keywords, SchemaError> {
+        let mut keywords = Keywords::default();
+        let invalid = |keyword: &'static str, expected: &'static str| SchemaError {
+            kind: SchemaErrorKind::InvalidKeyword { keyword, expected },
+            path: format!("{}/{}", path, pointer::escape_token(keyword)),
+        };
+
+        if let Some(value) = schema.get("type") {
+            const EXPECTED: &str = "a type name or an array of type names";
+            let names = match value {
+                Value::String(name) => vec![name.as_str()],
+                Value::Array(names) => names
+                    .iter()
+                    .map(|name| name.as_str().ok_or_else(|| invalid("type", EXPECTED)))
+                    .collect::<Result<_, _>>()?,
+                _ => return Err(invalid("type", EXPECTED)),
+            };
+            let types = names
+                .into_iter()
+                .map(|name| Type::from_name(name).ok_or_else(|| invalid("type", EXPECTED)))
+                .collect::<Result<_, _>>()?;
+            keywords.types = Some(types);
+        }
+
+        if let Some(value) = schema.get("enum") {
+            let values = value
+                .as_array()
+                .ok_or_else(|| invalid("enum", "an array"))?;
+            keywords.enumeration = Some(values.clone());
+        }
+
+        let number = |keyword: &'static str| -> Result<Option<Number>, SchemaError> {
+            match schema.get(keyword) {
+                None => Ok(None),
+                Some(Value::Number(number)) => Ok(Some(number.clone())),
+                Some(_) => Err(invalid(keyword, "a number")),
+            }
+        };
+        keywords.minimum = number("minimum")?;
+        keywords.maximum = number("maximum")?;
+        keywords.exclusive_minimum = number("exclusiveMinimum")?;
+        keywords.exclusive_maximum = number("exclusiveMaximum")?;
+
+        if let Some(value) = schema.get("pattern") {
+            let pattern = value
+                .as_str()
+                .ok_or_else(|| invalid("pattern", "a string"))?;
+            let regex = Regex::new(pattern).map_err(|error| SchemaError {
+                kind: SchemaErrorKind::InvalidPattern(error),
+                path: format!("{}/pattern", path),
+            })?;
+            keywords.pattern = Some((pattern.to_string(), regex));
+        }
+
+        if let Some(value) = schema.get("properties") {
+            let properties = value
+                .as_object()
+                .ok_or_else(|| invalid("properties", "an object"))?;
+            for (name, subschema) in properties {
+                let subschema_path = format!("{}/properties/{}", path, pointer::escape_token(name));
+                let index = self.compile(subschema, subschema_path)?;
+                keywords.properties.push((name.clone(), index));
+            }
+        }
+
+        if let Some(value) = schema.get("required") {
+            const EXPECTED: &str = "an array of strings";
+            let names = value
+                .as_array()
+                .ok_or_else(|| invalid("required", EXPECTED))?;
+            keywords.required = names
+                .iter()
+                .map(|name| {
+                    name.as_str()
+                        .map(str::to_string)
+                        .ok_or_else(|| invalid("required", EXPECTED))
+                })
+                .collect::<Result<_, _>>()?;
+        }
+
+        if let Some(subschema) = schema.get("items") {
+            // Before 2020-12, an array here described each position of a tuple.
+            if subschema.is_array() {
+                return Err(invalid("items", "a schema"));
+            }
+            keywords.items = Some(self.compile(subschema, format!("{}/items", path))?);
+        }
+
+        if let Some(value) = schema.get("$ref") {
+            let reference = value.as_str().ok_or_else(|| invalid("$ref", "a string"))?;
+            keywords.reference = Some(self.compile_reference(reference, path)?);
+        }
+
+        Ok(keywords)
+    }
+
+    // Only fragments that are JSON Pointers into this document are supported, like "#" or
+    // "#/$defs/address".
+    fn compile_reference(&mut self, reference: &str, path: &str) -> Result<usize, SchemaError> {
+        let error = |kind| SchemaError {
+            kind,
+            path: format!("{}/$ref", path),
+        };
+        let target = reference
+            .strip_prefix('#')
+            .and_then(percent_decode)
+            .filter(|target| target.is_empty() || target.starts_with('/'))
+            .ok_or_else(|| error(SchemaErrorKind::UnsupportedReference(reference.to_string())))?;
+        let schema = pointer::resolve(self.root, &target)
+            .ok_or_else(|| error(SchemaErrorKind::UnresolvedReference(reference.to_string())))?;
+        // Normalize the path, so a location that is reached both directly and through a $ref
+        // (or through differently escaped references) is only compiled once.
+        let tokens = pointer::parse_pointer(&target).unwrap_or_default();
+        let normalized = tokens
+            .iter()
+            .map(|token| format!("/{}", pointer::escape_token(token)))
+            .collect::<String>();
+        self.compile(schema, normalized)
+    }
+}
+
+// URI fragments may percent-encode characters, e.g. "#/$defs/a%20b".
+fn percent_decode(fragment: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(fragment.len());
+    let mut input = fragment.bytes();
+    while let Some(byte) = input.next() {
+        if byte != b'%' {
+            bytes.push(byte);
+            continue;
+        }
+        let high = (input.next()? as char).to_digit(16)?;
+        let low = (input.next()? as char).to_digit(16)?;
+        bytes.push((high * 16 + low) as u8);
+    }
+    String::from_utf8(bytes).ok()
+}
+
+fn report(errors: &mut Vec<ValidationError>, location: &Location, kind: ValidationErrorKind) {
+    errors.push(ValidationError {
+        kind,
+        path: location.to_string(),
+    });
+}
+
+// Location of a value inside the instance being validated.
+#[derive(Clone, Copy)]
+enum Location<'a> {
+    Root,
+    Key(&'a Location<'a>, &'a str),
+    Index(&'a Location<'a>, usize),
+}
+
+impl<'a> Display for Location<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Location::Root => Ok(()),
+            Location::Key(parent, key) => write!(f, "{}/{}", parent, pointer::escape_token(key)),
+            Location::Index(parent, index) => write!(f, "{}/{}", parent, index),
+        }
+    }
+}
+
+impl Schema {
+    // Checks the value against the schema and returns every violation that was found.
+    pub fn validate(&self, value: &Value) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        self.validate_node(0, value, &Location::Root, 0, &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn is_valid(&self, value: &Value) -> bool { self.validate(value).is_ok() }
+
+    fn validate_node(
+        &self,
+        index: usize,
+        value: &Value,
+        location: &Location,
+        reference_depth: usize,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        let keywords = match &self.nodes[index] {
+            Node::Boolean(true) => return,
+            Node::Boolean(false) => return report(errors, location, ValidationErrorKind::False),
+            Node::Keywords(keywords) => keywords,
+        };
+
+        if let Some(types) = &keywords.types {
+            if !types.iter().any(|expected| expected.matches(value)) {
+                report(errors, location, ValidationErrorKind::Type {
+                    expected: types.clone(),
+                    actual: Type::of(value),
+                });
+            }
+        }
+
+        if let Some(values) = &keywords.enumeration {
+            if !values.contains(value) {
+                report(errors, location, ValidationErrorKind::Enum);
+            }
+        }
+
+        if let Value::Number(number) = value {
+            if let Some(minimum) = &keywords.minimum {
+                if number < minimum {
+                    report(
+                        errors,
+                        location,
+                        ValidationErrorKind::Minimum(minimum.clone()),
+                    );
+                }
+            }
+            if let Some(maximum) = &keywords.maximum {
+                if number > maximum {
+                    report(
+                        errors,
+                        location,
+                        ValidationErrorKind::Maximum(maximum.clone()),
+                    );
+                }
+            }
+            if let Some(minimum) = &keywords.exclusive_minimum {
+                if number <= minimum {
+                    report(
+                        errors,
+                        location,
+                        ValidationErrorKind::ExclusiveMinimum(minimum.clone()),
+                    );
+                }
+            }
+            if let Some(maximum) = &keywords.exclusive_maximum {
+                if number >= maximum {
+                    report(
+                        errors,
+                        location,
+                        ValidationErrorKind::ExclusiveMaximum(maximum.clone()),
+                    );
+                }
+            }
+        }
+
+        if let (Value::String(string), Some((pattern, regex))) = (value, &keywords.pattern) {
+            if !regex.is_match(string) {
+                report(
+                    errors,
+                    location,
+                    ValidationErrorKind::Pattern(pattern.clone()),
+                );
+            }
+        }
+
+        if let Value::Object(object) = value {
+            for name in &keywords.required {
+                if !object.contains_key(name) {
+                    report(
+                        errors,
+                        location,
+                        ValidationErrorKind::Required(name.clone()),
+                    );
+                }
+            }
+        }
+
+        if let Some(reference) = keywords.reference {
+            if reference_depth >= MAX_REFERENCE_DEPTH {
+                report(errors, location, ValidationErrorKind::ReferenceLoop);
+            } else {
+                self.validate_node(reference, value, location, reference_depth + 1, errors);
+            }
+        }
+
+        match value {
+            Value::Object(object) => {
+                for (name, subschema) in &keywords.properties {
+                    if let Some(member) = object.get(name) {
+                        let location = Location::Key(location, name);
+                        self.validate_node(*subschema, member, &location, 0, errors);
+                    }
+                }
+            }
+            Value::Array(array) => {
+                if let Some(subschema) = keywords.items {
+                    for (i, element) in array.iter().enumerate() {
+                        let location = Location::Index(location, i);
+                        self.validate_node(subschema, element, &location, 0, errors);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,607 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Regular expressions for the "pattern" keyword. This covers most of the subset of ECMA-262
+// syntax that JSON Schema recommends for interoperability: literals and escapes, '.', character
+// classes and the \d \w \s shorthands, '^' and '$', groups, alternation and all quantifiers.
+// Lookahead is in that subset too, but can't be run as an NFA, so it is rejected along with
+// lookbehind, named groups and backreferences.
+//
+// Patterns are compiled into a small program that is run as a Thompson NFA, so matching takes
+// time linear in the length of the input and no pattern can make validation hang.
+
+use std::fmt::{Display, Formatter};
+
+// Upper bound on the size of a compiled pattern, which counted repetition could otherwise blow up.
+const MAX_PROGRAM_SIZE: usize = 10_000;
+
+#[derive(Debug)]
+pub enum RegexError {
+    InvalidEscape,
+    InvalidRange,
+    NothingToRepeat,
+    TooLarge,
+    UnexpectedEnd,
+    UnmatchedParenthesis,
+    Unsupported(&'static str),
+}
+
+impl Display for RegexError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegexError::InvalidEscape => write!(f, "invalid escape sequence"),
+            RegexError::InvalidRange => write!(f, "invalid range"),
+            RegexError::NothingToRepeat => write!(f, "quantifier without anything to repeat"),
+            RegexError::TooLarge => write!(f, "pattern is too large"),
+            RegexError::UnexpectedEnd => write!(f, "unexpected end of pattern"),
+            RegexError::UnmatchedParenthesis => write!(f, "unmatched parenthesis"),
+            RegexError::Unsupported(feature) => write!(f, "{} are not supported", feature),
+        }
+    }
+}
+
+impl std::error::Error for RegexError {}
+
+#[derive(Clone, Debug)]
+struct Class {
+    negated: bool,
+    ranges: Vec<(u32, u32)>,
+}
+
+impl Class {
+    fn matches(&self, ch: char) -> bool {
+        let ch = ch as u32;
+        self.ranges
+            .iter()
+            .any(|(first, last)| (*first..=*last).contains(&ch))
+            != self.negated
+    }
+}
+
+fn digit_ranges() -> Vec<(u32, u32)> { vec![('0' as u32, '9' as u32)] }
+
+fn word_ranges() -> Vec<(u32, u32)> {
+    vec![
+        ('0' as u32, '9' as u32),
+        ('A' as u32, 'Z' as u32),
+        ('_' as u32, '_' as u32),
+        ('a' as u32, 'z' as u32),
+    ]
+}
+
+// WhiteSpace and LineTerminator as defined by ECMA-262.
+fn space_ranges() -> Vec<(u32, u32)> {
+    vec![
+        (0x09, 0x0d),
+        (0x20, 0x20),
+        (0xa0, 0xa0),
+        (0x1680, 0x1680),
+        (0x2000, 0x200a),
+        (0x2028, 0x2029),
+        (0x202f, 0x202f),
+        (0x205f, 0x205f),
+        (0x3000, 0x3000),
+        (0xfeff, 0xfeff),
+    ]
+}
+
+// Expects sorted, non-overlapping ranges, as produced by the functions above.
+fn complement(ranges: Vec<(u32, u32)>) -> Vec<(u32, u32)> {
+    let mut complement = Vec::new();
+    let mut next = 0;
+    for (first, last) in ranges {
+        if first > next {
+            complement.push((next, first - 1));
+        }
+        next = last + 1;
+    }
+    if next <= char::MAX as u32 {
+        complement.push((next, char::MAX as u32));
+    }
+    complement
+}
+
+fn is_word_character(ch: char) -> bool { ch.is_ascii_alphanumeric() || ch == '_' }
+
+fn is_line_terminator(ch: char) -> bool { matches!(ch, '\n' | '\r' | '\u{2028}' | '\u{2029}') }
+
+enum Node {
+    Empty,
+    Char(char),
+    Any,
+    Class(Class),
+    Start,
+    End,
+    WordBoundary(bool),
+    Concat(Vec<Node>),
+    Alternate(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: u32,
+        max: Option<u32>,
+    },
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> { self.chars.get(self.position).copied() }
+
+    fn next(&mut self) -> Result<char, RegexError> {
+        let ch = self.peek().ok_or(RegexError::UnexpectedEnd)?;
+        self.position += 1;
+        Ok(ch)
+    }
+
+    fn consume(&mut self, expected: &str) -> bool {
+        let expected = expected.chars().collect::<Vec<_>>();
+        if !self.chars[self.position..].starts_with(&expected) {
+            return false;
+        }
+        self.position += expected.len();
+        true
+    }
+
+    fn parse_alternation(&mut self) -> Result<Node, RegexError> {
+        let mut alternatives = vec![self.parse_sequence()?];
+        while self.consume("|") {
+            alternatives.push(self.parse_sequence()?);
+        }
+        Ok(if alternatives.len() == 1 {
+            alternatives.pop().unwrap()
+        } else {
+            Node::Alternate(alternatives)
+        })
+    }
+
+    fn parse_sequence(&mut self) -> Result<Node, RegexError> {
+        let mut sequence = Vec::new();
+        while let Some(ch) = self.peek() {
+            if ch == '|' || ch == ')' {
+                break;
+            }
+            let atom = self.parse_atom()?;
+            sequence.push(self.parse_quantifier(atom)?);
+        }
+        Ok(match sequence.len() {
+            0 => Node::Empty,
+            1 => sequence.pop().unwrap(),
+            _ => Node::Concat(sequence),
+        })
+    }
+
+    fn parse_atom(&mut self) -> Result<Node, RegexError> {
+        match self.next()? {
+            '(' => {
+                if self.consume("?=") || self.consume("?!") {
+                    return Err(RegexError::Unsupported("lookahead assertions"));
+                }
+                if self.consume("?<=") || self.consume("?<!") {
+                    return Err(RegexError::Unsupported("lookbehind assertions"));
+                }
+                if self.consume("?<") {
+                    return Err(RegexError::Unsupported("named groups"));
+                }
+                self.consume("?:");
+                let node = self.parse_alternation()?;
+                if !self.consume(")") {
+                    return Err(RegexError::UnmatchedParenthesis);
+                }
+                Ok(node)
+            }
+            ')' => Err(RegexError::UnmatchedParenthesis),
+            '[' => self.parse_class(),
+            '.' => Ok(Node::Any),
+            '^' => Ok(Node::Start),
+            '$' => Ok(Node::End),
+            '\\' => self.parse_escape(),
+            '*' | '+' | '?' => Err(RegexError::NothingToRepeat),
+            '{' if self.counted_repetition_follows() => Err(RegexError::NothingToRepeat),
+            ch => Ok(Node::Char(ch)),
+        }
+    }
+
+    fn parse_escape(&mut self) -> Result<Node, RegexError> {
+        match self.peek() {
+            Some('d') => Ok(Node::Class(self.shorthand_class(digit_ranges, false))),
+            Some('D') => Ok(Node::Class(self.shorthand_class(digit_ranges, true))),
+            Some('w') => Ok(Node::Class(self.shorthand_class(word_ranges, false))),
+            Some('W') => Ok(Node::Class(self.shorthand_class(word_ranges, true))),
+            Some('s') => Ok(Node::Class(self.shorthand_class(space_ranges, false))),
+            Some('S') => Ok(Node::Class(self.shorthand_class(space_ranges, true))),
+            Some('b') => {
+                self.position += 1;
+                Ok(Node::WordBoundary(true))
+            }
+            Some('B') => {
+                self.position += 1;
+                Ok(Node::WordBoundary(false))
+            }
+            Some('1'..='9') => Err(RegexError::Unsupported("backreferences")),
+            _ => Ok(Node::Char(self.parse_character_escape()?)),
+        }
+    }
+
+    fn shorthand_class(&mut self, ranges: fn() -> Vec<(u32, u32)>, negated: bool) -> Class {
+        self.position += 1;
+        Class {
+            negated,
+            ranges: ranges(),
+        }
+    }
+
+    // Escapes that stand for a single character, after the backslash.
+    fn parse_character_escape(&mut self) -> Result<char, RegexError> {
+        let ch = match self.next()? {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'f' => '\u{c}',
+            'v' => '\u{b}',
+            '0' if !matches!(self.peek(), Some('0'..='9')) => '\0',
+            'c' => {
+                let letter = self.next()?;
+                if !letter.is_ascii_alphabetic() {
+                    return Err(RegexError::InvalidEscape);
+                }
+                char::from(letter as u8 % 32)
+            }
+            'x' => self.parse_hex_digits(2)?,
+            'u' => self.parse_hex_digits(4)?,
+            ch if ch.is_ascii_alphanumeric() => return Err(RegexError::InvalidEscape),
+            ch => ch,
+        };
+        Ok(ch)
+    }
+
+    fn parse_hex_digits(&mut self, count: usize) -> Result<char, RegexError> {
+        let mut code_point = 0;
+        for _ in 0..count {
+            let digit = self.next()?.to_digit(16).ok_or(RegexError::InvalidEscape)?;
+            code_point = code_point * 16 + digit;
+        }
+        char::from_u32(code_point).ok_or(RegexError::Unsupported("surrogate escapes"))
+    }
+
+    fn parse_class(&mut self) -> Result<Node, RegexError> {
+        let negated = self.consume("^");
+        let mut ranges = Vec::new();
+        loop {
+            let first = match self.next()? {
+                ']' => break,
+                '\\' => match self.parse_class_escape()? {
+                    ClassAtom::Char(ch) => ch,
+                    ClassAtom::Ranges(shorthand) => {
+                        ranges.extend(shorthand);
+                        continue;
+                    }
+                },
+                ch => ch,
+            };
+            // A '-' at the end of the class, or next to a shorthand, is a literal.
+            if self.peek() != Some('-') || self.chars.get(self.position + 1) == Some(&']') {
+                ranges.push((first as u32, first as u32));
+                continue;
+            }
+            self.position += 1;
+            let last = match self.next()? {
+                '\\' => match self.parse_class_escape()? {
+                    ClassAtom::Char(ch) => ch,
+                    ClassAtom::Ranges(shorthand) => {
+                        ranges.push((first as u32, first as u32));
+                        ranges.push(('-' as u32, '-' as u32));
+                        ranges.extend(shorthand);
+                        continue;
+                    }
+                },
+                ch => ch,
+            };
+            if first > last {
+                return Err(RegexError::InvalidRange);
+            }
+            ranges.push((first as u32, last as u32));
+        }
+        Ok(Node::Class(Class { negated, ranges }))
+    }
+
+    fn parse_class_escape(&mut self) -> Result<ClassAtom, RegexError> {
+        let ranges = match self.peek() {
+            Some('d') => digit_ranges(),
+            Some('D') => complement(digit_ranges()),
+            Some('w') => word_ranges(),
+            Some('W') => complement(word_ranges()),
+            Some('s') => space_ranges(),
+            Some('S') => complement(space_ranges()),
+            Some('b') => {
+                self.position += 1;
+                return Ok(ClassAtom::Char('\u{8}'));
+            }
+            Some('-') => {
+                self.position += 1;
+                return Ok(ClassAtom::Char('-'));
+            }
+            _ => return Ok(ClassAtom::Char(self.parse_character_escape()?)),
+        };
+        self.position += 1;
+        Ok(ClassAtom::Ranges(ranges))
+    }
+
+    fn counted_repetition_follows(&self) -> bool {
+        let mut position = self.position;
+        let mut saw_digit = false;
+        while let Some(ch) = self.chars.get(position) {
+            match ch {
+                '0'..='9' => saw_digit = true,
+                ',' if saw_digit => {}
+                '}' => return saw_digit,
+                _ => return false,
+            }
+            position += 1;
+        }
+        false
+    }
+
+    fn parse_quantifier(&mut self, atom: Node) -> Result<Node, RegexError> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                self.position += 1;
+                if !self.counted_repetition_follows() {
+                    // Annex B allows a '{' that does not start a quantifier as a literal.
+                    self.position -= 1;
+                    return Ok(atom);
+                }
+                let min = self.parse_count()?;
+                let max = if self.consume(",") {
+                    match self.peek() {
+                        Some('}') => None,
+                        _ => Some(self.parse_count()?),
+                    }
+                } else {
+                    Some(min)
+                };
+                if matches!(max, Some(max) if max < min) {
+                    return Err(RegexError::InvalidRange);
+                }
+                // The closing brace is consumed below, like the single-character quantifiers.
+                (min, max)
+            }
+            _ => return Ok(atom),
+        };
+        self.position += 1;
+        if matches!(atom, Node::Start | Node::End | Node::WordBoundary(_)) {
+            return Err(RegexError::NothingToRepeat);
+        }
+        // Lazy quantifiers only differ in which match they find, not in whether there is one.
+        self.consume("?");
+        if matches!(self.peek(), Some('*' | '+' | '?')) {
+            return Err(RegexError::NothingToRepeat);
+        }
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+        })
+    }
+
+    fn parse_count(&mut self) -> Result<u32, RegexError> {
+        let start = self.position;
+        while let Some('0'..='9') = self.peek() {
+            self.position += 1;
+        }
+        let digits = self.chars[start..self.position].iter().collect::<String>();
+        digits.parse().map_err(|_| RegexError::TooLarge)
+    }
+}
+
+enum ClassAtom {
+    Char(char),
+    Ranges(Vec<(u32, u32)>),
+}
+
+enum Instruction {
+    Char(char),
+    Any,
+    Class(Class),
+    Start,
+    End,
+    WordBoundary(bool),
+    Split(usize, usize),
+    Jump(usize),
+    Match,
+}
+
+struct Compiler {
+    program: Vec<Instruction>,
+}
+
+impl Compiler {
+    fn emit(&mut self, instruction: Instruction) -> Result<usize, RegexError> {
+        if self.program.len() >= MAX_PROGRAM_SIZE {
+            return Err(RegexError::TooLarge);
+        }
+        self.program.push(instruction);
+        Ok(self.program.len() - 1)
+    }
+
+    fn compile(&mut self, node: &Node) -> Result<(), RegexError> {
+        match node {
+            Node::Empty => {}
+            Node::Char(ch) => {
+                self.emit(Instruction::Char(*ch))?;
+            }
+            Node::Any => {
+                self.emit(Instruction::Any)?;
+            }
+            Node::Class(class) => {
+                self.emit(Instruction::Class(class.clone()))?;
+            }
+            Node::Start => {
+                self.emit(Instruction::Start)?;
+            }
+            Node::End => {
+                self.emit(Instruction::End)?;
+            }
+            Node::WordBoundary(expected) => {
+                self.emit(Instruction::WordBoundary(*expected))?;
+            }
+            Node::Concat(nodes) => {
+                for node in nodes {
+                    self.compile(node)?;
+                }
+            }
+            Node::Alternate(alternatives) => {
+                let mut jumps = Vec::new();
+                for (i, alternative) in alternatives.iter().enumerate() {
+                    if i == alternatives.len() - 1 {
+                        self.compile(alternative)?;
+                        break;
+                    }
+                    let split = self.emit(Instruction::Split(0, 0))?;
+                    self.compile(alternative)?;
+                    jumps.push(self.emit(Instruction::Jump(0))?);
+                    self.program[split] = Instruction::Split(split + 1, self.program.len());
+                }
+                let end = self.program.len();
+                for jump in jumps {
+                    self.program[jump] = Instruction::Jump(end);
+                }
+            }
+            Node::Repeat { node, min, max } => {
+                for _ in 0..*min {
+                    self.compile(node)?;
+                }
+                match max {
+                    None => {
+                        let split = self.emit(Instruction::Split(0, 0))?;
+                        self.compile(node)?;
+                        self.emit(Instruction::Jump(split))?;
+                        self.program[split] = Instruction::Split(split + 1, self.program.len());
+                    }
+                    Some(max) => {
+                        let mut splits = Vec::new();
+                        for _ in *min..*max {
+                            splits.push(self.emit(Instruction::Split(0, 0))?);
+                            self.compile(node)?;
+                        }
+                        let end = self.program.len();
+                        for split in splits {
+                            self.program[split] = Instruction::Split(split + 1, end);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct Regex {
+    program: Vec<Instruction>,
+}
+
+impl Regex {
+    pub fn new(pattern: &str) -> Result<Regex, RegexError> {
+        let mut parser = Parser {
+            chars: pattern.chars().collect(),
+            position: 0,
+        };
+        let node = parser.parse_alternation()?;
+        if parser.peek().is_some() {
+            return Err(RegexError::UnmatchedParenthesis);
+        }
+
+        let mut compiler = Compiler {
+            program: Vec::new(),
+        };
+        compiler.compile(&node)?;
+        compiler.emit(Instruction::Match)?;
+        Ok(Regex {
+            program: compiler.program,
+        })
+    }
+
+    // Like RegExp.prototype.test(), this looks for a match anywhere in the text. Patterns have
+    // to use '^' and '$' to match the whole string.
+    pub fn is_match(&self, text: &str) -> bool {
+        let chars = text.chars().collect::<Vec<_>>();
+        let mut current = Vec::new();
+        let mut next = Vec::new();
+        // The position for which each instruction was last added, so it is only added once.
+        let mut added = vec![usize::MAX; self.program.len()];
+
+        for position in 0..=chars.len() {
+            self.add_thread(&mut current, &mut added, 0, &chars, position);
+            for pc in current.drain(..) {
+                let matches = match &self.program[pc] {
+                    Instruction::Match => return true,
+                    Instruction::Char(ch) => chars.get(position) == Some(ch),
+                    Instruction::Any => {
+                        matches!(chars.get(position), Some(ch) if !is_line_terminator(*ch))
+                    }
+                    Instruction::Class(class) => {
+                        matches!(chars.get(position), Some(ch) if class.matches(*ch))
+                    }
+                    _ => unreachable!(),
+                };
+                if matches {
+                    self.add_thread(&mut next, &mut added, pc + 1, &chars, position + 1);
+                }
+            }
+            std::mem::swap(&mut current, &mut next);
+        }
+        false
+    }
+
+    // Follows jumps, splits and assertions from pc, and adds every instruction that consumes a
+    // character (or Match) to the thread list.
+    fn add_thread(
+        &self,
+        threads: &mut Vec<usize>,
+        added: &mut [usize],
+        pc: usize,
+        chars: &[char],
+        position: usize,
+    ) {
+        let mut stack = vec![pc];
+        while let Some(pc) = stack.pop() {
+            if added[pc] == position {
+                continue;
+            }
+            added[pc] = position;
+            match &self.program[pc] {
+                Instruction::Jump(target) => stack.push(*target),
+                Instruction::Split(first, second) => {
+                    stack.push(*second);
+                    stack.push(*first);
+                }
+                Instruction::Start => {
+                    if position == 0 {
+                        stack.push(pc + 1);
+                    }
+                }
+                Instruction::End => {
+                    if position == chars.len() {
+                        stack.push(pc + 1);
+                    }
+                }
+                Instruction::WordBoundary(expected) => {
+                    let before = position > 0 && is_word_character(chars[position - 1]);
+                    let after = matches!(chars.get(position), Some(ch) if is_word_character(*ch));
+                    if (before != after) == *expected {
+                        stack.push(pc + 1);
+                    }
+                }
+                _ => threads.push(pc),
+            }
+        }
+    }
+}
@@ -0,0 +1,393 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{
+    self,
+    DeserializeOwned,
+    DeserializeSeed,
+    EnumAccess,
+    IntoDeserializer,
+    MapAccess,
+    SeqAccess,
+    VariantAccess,
+    Visitor,
+};
+use serde::{forward_to_deserialize_any, Deserialize};
+
+use super::{number_from_i128, number_from_u128, Error, Path};
+use crate::json::map::{self, Map};
+use crate::json::{self, Number, Value};
+
+pub fn from_str<T: DeserializeOwned>(input: &str) -> Result<T, Error> {
+    let value = json::parse(input)?;
+    from_value(&value)
+}
+
+pub fn from_value<'de, T: Deserialize<'de>>(value: &'de Value) -> Result<T, Error> {
+    T::deserialize(ValueDeserializer::new(value))
+}
+
+pub struct ValueDeserializer<'de, 'p> {
+    value: &'de Value,
+    path: Path<'p>,
+}
+
+impl<'de> ValueDeserializer<'de, 'static> {
+    pub fn new(value: &'de Value) -> ValueDeserializer<'de, 'static> {
+        ValueDeserializer {
+            value,
+            path: Path::Root,
+        }
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for &'de Value {
+    type Deserializer = ValueDeserializer<'de, 'static>;
+
+    fn into_deserializer(self) -> Self::Deserializer { ValueDeserializer::new(self) }
+}
+
+impl<'de, 'p> ValueDeserializer<'de, 'p> {
+    fn invalid_type(&self, expected: &dyn de::Expected) -> Error {
+        let unexpected = match self.value {
+            Value::Null => de::Unexpected::Unit,
+            Value::Bool(value) => de::Unexpected::Bool(*value),
+            Value::Number(Number::Integer64(value)) => de::Unexpected::Signed(*value),
+            Value::Number(Number::UInt64(value)) => de::Unexpected::Unsigned(*value),
+            Value::Number(Number::Float64(value)) => de::Unexpected::Float(*value),
+            Value::Number(Number::Raw(_)) => de::Unexpected::Other("number"),
+            Value::String(value) => de::Unexpected::Str(value),
+            Value::Array(_) => de::Unexpected::Seq,
+            Value::Object(_) => de::Unexpected::Map,
+        };
+        <Error as de::Error>::invalid_type(unexpected, expected).with_path(&self.path)
+    }
+}
+
+// Mirrors the serialization of raw numbers: the widest integer type that holds them, or a float.
+fn visit_raw_number<'de, V: Visitor<'de>>(string: &str, visitor: V) -> Result<V::Value, Error> {
+    if let Ok(value) = string.parse() {
+        visitor.visit_i128(value)
+    } else if let Ok(value) = string.parse() {
+        visitor.visit_u128(value)
+    } else {
+        match string.parse() {
+            Ok(value) => visitor.visit_f64(value),
+            Err(_) => Err(de::Error::custom(format!("invalid raw number {}", string))),
+        }
+    }
+}
+
+impl<'de, 'p> de::Deserializer<'de> for ValueDeserializer<'de, 'p> {
+    type Error = Error;
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let result = match self.value {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(value) => visitor.visit_bool(*value),
+            Value::Number(Number::Integer64(value)) => visitor.visit_i64(*value),
+            Value::Number(Number::UInt64(value)) => visitor.visit_u64(*value),
+            Value::Number(Number::Float64(value)) => visitor.visit_f64(*value),
+            Value::Number(Number::Raw(string)) => visit_raw_number(string, visitor),
+            Value::String(value) => visitor.visit_borrowed_str(value),
+            Value::Array(array) => visitor.visit_seq(ArrayAccess {
+                elements: array.iter().enumerate(),
+                path: &self.path,
+            }),
+            Value::Object(object) => visitor.visit_map(ObjectAccess {
+                members: object.iter(),
+                value: None,
+                path: &self.path,
+            }),
+        };
+        result.map_err(|error| error.with_path(&self.path))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let result = match self.value {
+            // Unit variants are plain strings, all others are objects with a single member.
+            Value::String(variant) => visitor.visit_enum(BorrowedStrDeserializer::new(variant)),
+            Value::Object(object) if object.len() == 1 => {
+                let (variant, value) = object.iter().next().unwrap();
+                visitor.visit_enum(VariantDeserializer {
+                    variant,
+                    value,
+                    path: &self.path,
+                })
+            }
+            _ => return Err(self.invalid_type(&"a string or an object with a single member")),
+        };
+        result.map_err(|error| error.with_path(&self.path))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+}
+
+struct ArrayAccess<'de, 'p> {
+    elements: std::iter::Enumerate<std::slice::Iter<'de, Value>>,
+    path: &'p Path<'p>,
+}
+
+impl<'de, 'p> SeqAccess<'de> for ArrayAccess<'de, 'p> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        match self.elements.next() {
+            Some((index, value)) => seed
+                .deserialize(ValueDeserializer {
+                    value,
+                    path: Path::Index(self.path, index),
+                })
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> { Some(self.elements.len()) }
+}
+
+struct ObjectAccess<'de, 'p> {
+    members: map::Iter<'de>,
+    value: Option<(&'de String, &'de Value)>,
+    path: &'p Path<'p>,
+}
+
+impl<'de, 'p> MapAccess<'de> for ObjectAccess<'de, 'p> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.members.next() {
+            Some((name, value)) => {
+                self.value = Some((name, value));
+                seed.deserialize(KeyDeserializer { key: name }).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let (name, value) = self
+            .value
+            .take()
+            .expect("next_value_seed() called before next_key_seed()");
+        seed.deserialize(ValueDeserializer {
+            value,
+            path: Path::Key(self.path, name),
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> { Some(self.members.len()) }
+}
+
+// Object keys are always strings, but maps with integer keys should still work.
+struct KeyDeserializer<'de> {
+    key: &'de str,
+}
+
+macro_rules! deserialize_integer_key {
+    ($method:ident => $visit:ident) => {
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self.key.parse() {
+                Ok(value) => visitor.$visit(value),
+                Err(_) => Err(de::Error::invalid_value(
+                    de::Unexpected::Str(self.key),
+                    &"an integer",
+                )),
+            }
+        }
+    };
+}
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer<'de> {
+    type Error = Error;
+
+    deserialize_integer_key!(deserialize_i8 => visit_i8);
+
+    deserialize_integer_key!(deserialize_i16 => visit_i16);
+
+    deserialize_integer_key!(deserialize_i32 => visit_i32);
+
+    deserialize_integer_key!(deserialize_i64 => visit_i64);
+
+    deserialize_integer_key!(deserialize_u8 => visit_u8);
+
+    deserialize_integer_key!(deserialize_u16 => visit_u16);
+
+    deserialize_integer_key!(deserialize_u32 => visit_u32);
+
+    deserialize_integer_key!(deserialize_u64 => visit_u64);
+
+    forward_to_deserialize_any! {
+        bool i128 u128 f32 f64 char str string bytes byte_buf option unit unit_struct
+        newtype_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_str(self.key)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(BorrowedStrDeserializer::new(self.key))
+    }
+}
+
+struct VariantDeserializer<'de, 'p> {
+    variant: &'de str,
+    value: &'de Value,
+    path: &'p Path<'p>,
+}
+
+impl<'de: 'p, 'p> EnumAccess<'de> for VariantDeserializer<'de, 'p> {
+    type Error = Error;
+    type Variant = ValueDeserializer<'de, 'p>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), Error> {
+        let variant = seed.deserialize(BorrowedStrDeserializer::<Error>::new(self.variant))?;
+        Ok((variant, ValueDeserializer {
+            value: self.value,
+            path: Path::Key(self.path, self.variant),
+        }))
+    }
+}
+
+impl<'de, 'p> VariantAccess<'de> for ValueDeserializer<'de, 'p> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        match self.value {
+            Value::Null => Ok(()),
+            _ => Err(self.invalid_type(&"a unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_map(self, visitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("any JSON value")
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Value, E> { Ok(Value::Bool(value)) }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Value, E> {
+        Ok(Value::Number(Number::Integer64(value)))
+    }
+
+    fn visit_i128<E: de::Error>(self, value: i128) -> Result<Value, E> {
+        Ok(Value::Number(number_from_i128(value)))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Value, E> {
+        Ok(Value::Number(number_from_u128(value as u128)))
+    }
+
+    fn visit_u128<E: de::Error>(self, value: u128) -> Result<Value, E> {
+        Ok(Value::Number(number_from_u128(value)))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Value, E> {
+        Ok(Value::Number(Number::Float64(value)))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Value, E> {
+        Ok(Value::String(value.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<Value, E> {
+        Ok(Value::String(value))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Value, E> { Ok(Value::Null) }
+
+    fn visit_none<E: de::Error>(self) -> Result<Value, E> { Ok(Value::Null) }
+
+    fn visit_some<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut array = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(element) = seq.next_element()? {
+            array.push(element);
+        }
+        Ok(Value::Array(array))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut object = Map::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some((name, value)) = map.next_entry()? {
+            object.insert(name, value);
+        }
+        Ok(Value::Object(object))
+    }
+}
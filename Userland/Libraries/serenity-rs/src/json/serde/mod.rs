@@ -0,0 +1,115 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+mod de;
+mod ser;
+
+use std::fmt::Display;
+
+pub use de::{from_str, from_value, ValueDeserializer};
+pub use ser::{to_string, to_string_pretty, to_value, Serializer};
+
+use super::{Number, ParseError};
+
+#[derive(Debug)]
+pub enum Error {
+    Parse(ParseError),
+    // The path is empty until the error has propagated out of the value it was raised for.
+    Data { message: String, path: String },
+}
+
+impl Error {
+    fn with_path(self, path: &Path) -> Error {
+        match self {
+            Error::Data {
+                message,
+                path: old_path,
+            } if old_path.is_empty() => Error::Data {
+                message,
+                path: path.to_string(),
+            },
+            error => error,
+        }
+    }
+
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            Error::Data { path, .. } if !path.is_empty() => Some(path),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parse(error) => write!(f, "{}", error),
+            Error::Data { message, path } if path.is_empty() => write!(f, "{}", message),
+            Error::Data { message, path } => write!(f, "{} at {}", message, path),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Error { Error::Parse(error) }
+}
+
+impl ::serde::de::Error for Error {
+    fn custom<T: Display>(message: T) -> Error {
+        Error::Data {
+            message: message.to_string(),
+            path: String::new(),
+        }
+    }
+}
+
+impl ::serde::ser::Error for Error {
+    fn custom<T: Display>(message: T) -> Error {
+        Error::Data {
+            message: message.to_string(),
+            path: String::new(),
+        }
+    }
+}
+
+// Location of a value inside the document being deserialized, e.g. `[3].interrupt_line`.
+#[derive(Clone, Copy)]
+enum Path<'a> {
+    Root,
+    Key(&'a Path<'a>, &'a str),
+    Index(&'a Path<'a>, usize),
+}
+
+impl<'a> std::fmt::Display for Path<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Path::Root => Ok(()),
+            Path::Key(Path::Root, key) => write!(f, "{}", key),
+            Path::Key(parent, key) => write!(f, "{}.{}", parent, key),
+            Path::Index(parent, index) => write!(f, "{}[{}]", parent, index),
+        }
+    }
+}
+
+// 128-bit integers are stored in the narrowest variant that holds them, or as a raw number.
+fn number_from_i128(value: i128) -> Number {
+    if let Ok(value) = i64::try_from(value) {
+        Number::Integer64(value)
+    } else if let Ok(value) = u64::try_from(value) {
+        Number::UInt64(value)
+    } else {
+        Number::Raw(value.to_string())
+    }
+}
+
+fn number_from_u128(value: u128) -> Number {
+    match i128::try_from(value) {
+        Ok(value) => number_from_i128(value),
+        Err(_) => Number::Raw(value.to_string()),
+    }
+}
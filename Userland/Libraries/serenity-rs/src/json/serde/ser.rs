@@ -0,0 +1,480 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+use serde::ser::{self, Impossible, Serialize};
+
+use super::{number_from_i128, number_from_u128, Error};
+use crate::json::{self, Map, Number, Value};
+
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value, Error> {
+    value.serialize(Serializer)
+}
+
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, Error> {
+    Ok(json::serialize(&to_value(value)?))
+}
+
+pub fn to_string_pretty<T: Serialize + ?Sized>(value: &T) -> Result<String, Error> {
+    Ok(json::serialize_pretty(&to_value(value)?))
+}
+
+impl Serialize for Value {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Null => serializer.serialize_unit(),
+            Value::Bool(value) => serializer.serialize_bool(*value),
+            Value::Number(Number::Integer64(value)) => serializer.serialize_i64(*value),
+            Value::Number(Number::UInt64(value)) => serializer.serialize_u64(*value),
+            Value::Number(Number::Float64(value)) => serializer.serialize_f64(*value),
+            Value::Number(Number::Raw(string)) => serialize_raw_number(string, serializer),
+            Value::String(value) => serializer.serialize_str(value),
+            Value::Array(array) => serializer.collect_seq(array),
+            Value::Object(object) => serializer.collect_map(object),
+        }
+    }
+}
+
+// serde has no arbitrary-precision numbers, so raw numbers are passed on as the widest integer
+// type that holds them, or as a float.
+fn serialize_raw_number<S: ser::Serializer>(
+    string: &str,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if let Ok(value) = string.parse() {
+        serializer.serialize_i128(value)
+    } else if let Ok(value) = string.parse() {
+        serializer.serialize_u128(value)
+    } else {
+        match string.parse() {
+            Ok(value) => serializer.serialize_f64(value),
+            Err(_) => Err(ser::Error::custom(format!("invalid raw number {}", string))),
+        }
+    }
+}
+
+// Serializes any serde data structure into a json::Value tree.
+pub struct Serializer;
+
+fn integer(value: i64) -> Value { Value::Number(Number::Integer64(value)) }
+
+impl ser::Serializer for Serializer {
+    type Error = Error;
+    type Ok = Value;
+    type SerializeMap = SerializeObject;
+    type SerializeSeq = SerializeArray;
+    type SerializeStruct = SerializeObject;
+    type SerializeStructVariant = SerializeVariant<SerializeObject>;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeVariant<SerializeArray>;
+
+    fn serialize_bool(self, value: bool) -> Result<Value, Error> { Ok(Value::Bool(value)) }
+
+    fn serialize_i8(self, value: i8) -> Result<Value, Error> { Ok(integer(value as i64)) }
+
+    fn serialize_i16(self, value: i16) -> Result<Value, Error> { Ok(integer(value as i64)) }
+
+    fn serialize_i32(self, value: i32) -> Result<Value, Error> { Ok(integer(value as i64)) }
+
+    fn serialize_i64(self, value: i64) -> Result<Value, Error> { Ok(integer(value)) }
+
+    fn serialize_u8(self, value: u8) -> Result<Value, Error> { Ok(integer(value as i64)) }
+
+    fn serialize_u16(self, value: u16) -> Result<Value, Error> { Ok(integer(value as i64)) }
+
+    fn serialize_u32(self, value: u32) -> Result<Value, Error> { Ok(integer(value as i64)) }
+
+    fn serialize_i128(self, value: i128) -> Result<Value, Error> {
+        Ok(Value::Number(number_from_i128(value)))
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<Value, Error> {
+        Ok(Value::Number(number_from_u128(value as u128)))
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<Value, Error> {
+        Ok(Value::Number(number_from_u128(value)))
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<Value, Error> {
+        Ok(Value::Number(Number::Float64(value as f64)))
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<Value, Error> {
+        Ok(Value::Number(Number::Float64(value)))
+    }
+
+    fn serialize_char(self, value: char) -> Result<Value, Error> {
+        Ok(Value::String(value.to_string()))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<Value, Error> {
+        Ok(Value::String(value.to_string()))
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Value, Error> {
+        Ok(Value::Array(
+            value.iter().map(|byte| integer(*byte as i64)).collect(),
+        ))
+    }
+
+    fn serialize_none(self) -> Result<Value, Error> { Ok(Value::Null) }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value, Error> { Ok(Value::Null) }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Error> { Ok(Value::Null) }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Value, Error> {
+        Ok(Value::String(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Value, Error> {
+        let mut object = Map::new();
+        object.insert(variant.to_string(), value.serialize(self)?);
+        Ok(Value::Object(object))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray, Error> {
+        Ok(SerializeArray {
+            array: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVariant<SerializeArray>, Error> {
+        Ok(SerializeVariant {
+            variant,
+            inner: self.serialize_seq(Some(len))?,
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeObject, Error> {
+        Ok(SerializeObject {
+            object: Map::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SerializeObject, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVariant<SerializeObject>, Error> {
+        Ok(SerializeVariant {
+            variant,
+            inner: self.serialize_map(Some(len))?,
+        })
+    }
+}
+
+pub struct SerializeArray {
+    array: Vec<Value>,
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Error = Error;
+    type Ok = Value;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.array.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> { Ok(Value::Array(self.array)) }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Error = Error;
+    type Ok = Value;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, Error> { ser::SerializeSeq::end(self) }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Error = Error;
+    type Ok = Value;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, Error> { ser::SerializeSeq::end(self) }
+}
+
+pub struct SerializeObject {
+    object: Map,
+    key: Option<String>,
+}
+
+impl ser::SerializeMap for SerializeObject {
+    type Error = Error;
+    type Ok = Value;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .expect("serialize_value() called before serialize_key()");
+        self.object.insert(key, value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> { Ok(Value::Object(self.object)) }
+}
+
+impl ser::SerializeStruct for SerializeObject {
+    type Error = Error;
+    type Ok = Value;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.object
+            .insert(key.to_string(), value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> { ser::SerializeMap::end(self) }
+}
+
+// Wraps the array or object of a tuple or struct variant in an object keyed by the variant name.
+pub struct SerializeVariant<T> {
+    variant: &'static str,
+    inner: T,
+}
+
+impl<T> SerializeVariant<T> {
+    fn wrap(variant: &'static str, value: Value) -> Value {
+        let mut object = Map::new();
+        object.insert(variant.to_string(), value);
+        Value::Object(object)
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeVariant<SerializeArray> {
+    type Error = Error;
+    type Ok = Value;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(&mut self.inner, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Self::wrap(
+            self.variant,
+            ser::SerializeSeq::end(self.inner)?,
+        ))
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeVariant<SerializeObject> {
+    type Error = Error;
+    type Ok = Value;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        ser::SerializeStruct::serialize_field(&mut self.inner, key, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Self::wrap(
+            self.variant,
+            ser::SerializeMap::end(self.inner)?,
+        ))
+    }
+}
+
+// Object keys have to be strings, so only types with an obvious string form are accepted.
+struct KeySerializer;
+
+fn key_must_be_a_string() -> Error { ser::Error::custom("object key must be a string") }
+
+impl ser::Serializer for KeySerializer {
+    type Error = Error;
+    type Ok = String;
+    type SerializeMap = Impossible<String, Error>;
+    type SerializeSeq = Impossible<String, Error>;
+    type SerializeStruct = Impossible<String, Error>;
+    type SerializeStructVariant = Impossible<String, Error>;
+    type SerializeTuple = Impossible<String, Error>;
+    type SerializeTupleStruct = Impossible<String, Error>;
+    type SerializeTupleVariant = Impossible<String, Error>;
+
+    fn serialize_bool(self, value: bool) -> Result<String, Error> { Ok(value.to_string()) }
+
+    fn serialize_i8(self, value: i8) -> Result<String, Error> { Ok(value.to_string()) }
+
+    fn serialize_i16(self, value: i16) -> Result<String, Error> { Ok(value.to_string()) }
+
+    fn serialize_i32(self, value: i32) -> Result<String, Error> { Ok(value.to_string()) }
+
+    fn serialize_i64(self, value: i64) -> Result<String, Error> { Ok(value.to_string()) }
+
+    fn serialize_u8(self, value: u8) -> Result<String, Error> { Ok(value.to_string()) }
+
+    fn serialize_u16(self, value: u16) -> Result<String, Error> { Ok(value.to_string()) }
+
+    fn serialize_u32(self, value: u32) -> Result<String, Error> { Ok(value.to_string()) }
+
+    fn serialize_u64(self, value: u64) -> Result<String, Error> { Ok(value.to_string()) }
+
+    fn serialize_f32(self, _value: f32) -> Result<String, Error> { Err(key_must_be_a_string()) }
+
+    fn serialize_f64(self, _value: f64) -> Result<String, Error> { Err(key_must_be_a_string()) }
+
+    fn serialize_char(self, value: char) -> Result<String, Error> { Ok(value.to_string()) }
+
+    fn serialize_str(self, value: &str) -> Result<String, Error> { Ok(value.to_string()) }
+
+    fn serialize_bytes(self, _value: &[u8]) -> Result<String, Error> { Err(key_must_be_a_string()) }
+
+    fn serialize_none(self) -> Result<String, Error> { Err(key_must_be_a_string()) }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<String, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit(self) -> Result<String, Error> { Err(key_must_be_a_string()) }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, Error> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Error> {
+        Err(key_must_be_a_string())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        Err(key_must_be_a_string())
+    }
+}
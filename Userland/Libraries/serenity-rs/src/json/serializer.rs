@@ -0,0 +1,313 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+use std::fmt::Write;
+
+use super::{Number, Value};
+
+#[derive(Clone, Debug, Default)]
+pub struct SerializeOptions {
+    // Number of spaces per nesting level, or None for compact output.
+    pub indent: Option<usize>,
+    pub sort_keys: bool,
+    // RFC 8785 (JCS) output: no whitespace, keys sorted by UTF-16 code units, and every number
+    // written as ECMAScript would write the nearest f64. Overrides indent and sort_keys.
+    pub canonical: bool,
+}
+
+impl SerializeOptions {
+    pub fn compact() -> SerializeOptions { SerializeOptions::default() }
+
+    pub fn pretty() -> SerializeOptions {
+        SerializeOptions {
+            indent: Some(4),
+            sort_keys: false,
+            canonical: false,
+        }
+    }
+
+    pub fn canonical() -> SerializeOptions {
+        SerializeOptions {
+            indent: None,
+            sort_keys: false,
+            canonical: true,
+        }
+    }
+
+    pub fn with_sorted_keys(mut self) -> SerializeOptions {
+        self.sort_keys = true;
+        self
+    }
+}
+
+pub fn serialize(value: &Value) -> String {
+    serialize_with_options(value, &SerializeOptions::compact())
+}
+
+pub fn serialize_pretty(value: &Value) -> String {
+    serialize_with_options(value, &SerializeOptions::pretty())
+}
+
+// Canonical JSON (RFC 8785), for hashing and signing. Returns None if the value contains NaN, an
+// infinity, or a raw number too large for an f64, as JCS has no representation for those. The
+// other functions write them as null instead.
+pub fn serialize_canonical(value: &Value) -> Option<String> {
+    if contains_non_finite_number(value) {
+        return None;
+    }
+    Some(serialize_with_options(
+        value,
+        &SerializeOptions::canonical(),
+    ))
+}
+
+fn contains_non_finite_number(value: &Value) -> bool {
+    match value {
+        Value::Number(number) => !matches!(number.as_f64(), Some(value) if value.is_finite()),
+        Value::Array(array) => array.iter().any(contains_non_finite_number),
+        Value::Object(object) => object.values().any(contains_non_finite_number),
+        _ => false,
+    }
+}
+
+pub fn serialize_with_options(value: &Value, options: &SerializeOptions) -> String {
+    let mut output = String::new();
+    // Writing into a String cannot fail.
+    serialize_into(&mut output, value, options).unwrap();
+    output
+}
+
+pub fn serialize_into<W: Write>(
+    output: &mut W,
+    value: &Value,
+    options: &SerializeOptions,
+) -> std::fmt::Result {
+    let mut serializer = Serializer {
+        output,
+        options,
+        depth: 0,
+    };
+    serializer.serialize_value(value)
+}
+
+struct Serializer<'a, W: Write> {
+    output: &'a mut W,
+    options: &'a SerializeOptions,
+    depth: usize,
+}
+
+impl<'a, W: Write> Serializer<'a, W> {
+    fn serialize_value(&mut self, value: &Value) -> std::fmt::Result {
+        match value {
+            Value::Null => self.output.write_str("null"),
+            Value::Bool(value) => self.output.write_str(if *value { "true" } else { "false" }),
+            Value::Number(number) => self.serialize_number(number),
+            Value::String(string) => self.serialize_string(string),
+            Value::Array(array) => self.serialize_array(array),
+            Value::Object(object) => {
+                let mut members = object.iter().collect::<Vec<_>>();
+                if self.options.canonical {
+                    members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+                } else if self.options.sort_keys {
+                    members.sort_by_key(|(key, _)| *key);
+                }
+                self.serialize_object(&members)
+            }
+        }
+    }
+
+    fn serialize_number(&mut self, number: &Number) -> std::fmt::Result {
+        if self.options.canonical {
+            return serialize_ecmascript_number(self.output, number.as_f64().unwrap_or(f64::NAN));
+        }
+        match number {
+            Number::Integer64(value) => write!(self.output, "{}", value),
+            Number::UInt64(value) => write!(self.output, "{}", value),
+            Number::Float64(value) => serialize_float(self.output, *value),
+            Number::Raw(string) => self.output.write_str(string),
+        }
+    }
+
+    fn serialize_string(&mut self, string: &str) -> std::fmt::Result {
+        serialize_string(self.output, string)
+    }
+
+    fn serialize_array(&mut self, array: &[Value]) -> std::fmt::Result {
+        if array.is_empty() {
+            return self.output.write_str("[]");
+        }
+        self.output.write_char('[')?;
+        self.depth += 1;
+        for (i, element) in array.iter().enumerate() {
+            if i != 0 {
+                self.output.write_char(',')?;
+            }
+            self.newline_and_indent()?;
+            self.serialize_value(element)?;
+        }
+        self.depth -= 1;
+        self.newline_and_indent()?;
+        self.output.write_char(']')
+    }
+
+    fn serialize_object(&mut self, members: &[(&String, &Value)]) -> std::fmt::Result {
+        if members.is_empty() {
+            return self.output.write_str("{}");
+        }
+        self.output.write_char('{')?;
+        self.depth += 1;
+        for (i, (name, value)) in members.iter().enumerate() {
+            if i != 0 {
+                self.output.write_char(',')?;
+            }
+            self.newline_and_indent()?;
+            self.serialize_string(name)?;
+            self.output.write_char(':')?;
+            if self.indent().is_some() {
+                self.output.write_char(' ')?;
+            }
+            self.serialize_value(value)?;
+        }
+        self.depth -= 1;
+        self.newline_and_indent()?;
+        self.output.write_char('}')
+    }
+
+    fn indent(&self) -> Option<usize> {
+        match self.options.canonical {
+            true => None,
+            false => self.options.indent,
+        }
+    }
+
+    fn newline_and_indent(&mut self) -> std::fmt::Result {
+        if let Some(indent) = self.indent() {
+            self.output.write_char('\n')?;
+            for _ in 0..(indent * self.depth) {
+                self.output.write_char(' ')?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn serialize_float<W: Write>(output: &mut W, value: f64) -> std::fmt::Result {
+    // JSON has no representation for NaN or the infinities.
+    if !value.is_finite() {
+        return output.write_str("null");
+    }
+
+    // Display and LowerExp print the shortest digit string that round-trips. Display never uses
+    // an exponent, so 1e300 would come out as 301 digits. Like ECMAScript, switch to exponent
+    // notation outside of [1e-6, 1e21); the exponent keeps the value a float when parsed back.
+    if value != 0.0 && !(1e-6..1e21).contains(&value.abs()) {
+        return write!(output, "{:e}", value);
+    }
+    // Display drops the fractional part of integral values. Keep it so the value parses back as
+    // a float.
+    let string = format!("{}", value);
+    output.write_str(&string)?;
+    if !string.contains('.') {
+        output.write_str(".0")?;
+    }
+    Ok(())
+}
+
+// Number::prototype.toString() from ECMA-262, which RFC 8785 requires for all numbers.
+fn serialize_ecmascript_number<W: Write>(output: &mut W, value: f64) -> std::fmt::Result {
+    if !value.is_finite() {
+        return output.write_str("null");
+    }
+    if value == 0.0 {
+        // Including -0.
+        return output.write_char('0');
+    }
+    if value < 0.0 {
+        output.write_char('-')?;
+    }
+
+    // LowerExp prints the shortest digit string that round-trips, as d.ddde[-]x.
+    let scientific = format!("{:e}", value.abs());
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let digits = round_tie_to_even(mantissa.replace('.', ""), value.abs());
+    let k = digits.len() as i32;
+    // The position of the decimal point relative to the start of the digits.
+    let n = exponent.parse::<i32>().unwrap() + 1;
+
+    if k <= n && n <= 21 {
+        output.write_str(&digits)?;
+        for _ in 0..(n - k) {
+            output.write_char('0')?;
+        }
+    } else if 0 < n && n <= 21 {
+        let (integral, fractional) = digits.split_at(n as usize);
+        write!(output, "{}.{}", integral, fractional)?;
+    } else if -6 < n && n <= 0 {
+        output.write_str("0.")?;
+        for _ in 0..-n {
+            output.write_char('0')?;
+        }
+        output.write_str(&digits)?;
+    } else {
+        let (first, rest) = digits.split_at(1);
+        output.write_str(first)?;
+        if !rest.is_empty() {
+            write!(output, ".{}", rest)?;
+        }
+        let sign = if n > 0 { '+' } else { '-' };
+        write!(output, "e{}{}", sign, (n - 1).abs())?;
+    }
+    Ok(())
+}
+
+// When the value lies exactly halfway between two shortest digit strings, ECMAScript picks the one
+// ending in an even digit, while LowerExp rounds up. 1424953923781206.25 has to print as
+// 1424953923781206.2, not 1424953923781206.3.
+fn round_tie_to_even(mut digits: String, value: f64) -> String {
+    let last = digits.as_bytes()[digits.len() - 1];
+    if matches!(last, b'0' | b'2' | b'4' | b'6' | b'8') {
+        return digits;
+    }
+    // An f64 has at most 767 significant decimal digits, so this is exact.
+    let exact = format!("{:.767e}", value);
+    let (mantissa, _) = exact.split_once('e').unwrap();
+    let exact_digits = mantissa.replace('.', "");
+    let exact_digits = exact_digits.trim_end_matches('0');
+    let k = digits.len();
+    let is_tie = exact_digits.len() == k + 1
+        && exact_digits.ends_with('5')
+        && exact_digits[..k - 1] == digits[..k - 1]
+        && exact_digits.as_bytes()[k - 1] + 1 == last;
+    if is_tie {
+        digits.pop();
+        digits.push((last - 1) as char);
+    }
+    digits
+}
+
+fn serialize_string<W: Write>(output: &mut W, string: &str) -> std::fmt::Result {
+    output.write_char('"')?;
+    let mut start = 0;
+    for (i, ch) in string.char_indices() {
+        if ch != '"' && ch != '\\' && ch >= '\u{20}' {
+            continue;
+        }
+        output.write_str(&string[start..i])?;
+        match ch {
+            '"' => output.write_str("\\\"")?,
+            '\\' => output.write_str("\\\\")?,
+            '\u{8}' => output.write_str("\\b")?,
+            '\u{c}' => output.write_str("\\f")?,
+            '\n' => output.write_str("\\n")?,
+            '\r' => output.write_str("\\r")?,
+            '\t' => output.write_str("\\t")?,
+            _ => write!(output, "\\u{:04x}", ch as u32)?,
+        }
+        start = i + ch.len_utf8();
+    }
+    output.write_str(&string[start..])?;
+    output.write_char('"')
+}
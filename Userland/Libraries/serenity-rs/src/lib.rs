@@ -2,11 +2,23 @@
 #![feature(core_ffi_c)]
 #![allow(dead_code)]
 #![allow(unused_imports)]
+// target_os = "serenity" is only known to the Serenity toolchain, which in turn predates the lint.
+#![allow(unknown_lints)]
+#![allow(unexpected_cfgs)]
 
+// The OS bindings only build for Serenity itself. The rest of the crate is portable, so it can be
+// benchmarked and fuzzed on the host.
+#[cfg(target_os = "serenity")]
 pub mod core;
 pub mod gfx;
+#[cfg(target_os = "serenity")]
 pub mod ipc;
 pub mod json;
+pub mod pcidb;
+// The ProcFS readers deserialize the nodes through the JSON module's serde support.
+#[cfg(feature = "serde")]
+pub mod procfs;
+#[cfg(target_os = "serenity")]
 pub mod sys;
 
 extern "C" {
@@ -0,0 +1,56 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug)]
+pub enum PciDatabaseErrorKind {
+    Io(std::io::Error),
+    // A vendor, device, subsystem, class, subclass or programming interface line without a valid
+    // hexadecimal ID.
+    InvalidId,
+    // An indented line with nothing to belong to, like a device before any vendor.
+    Orphan,
+}
+
+impl Display for PciDatabaseErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PciDatabaseErrorKind::Io(error) => write!(f, "{}", error),
+            PciDatabaseErrorKind::InvalidId => write!(f, "invalid ID"),
+            PciDatabaseErrorKind::Orphan => write!(f, "entry outside of any vendor or class"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PciDatabaseError {
+    kind: PciDatabaseErrorKind,
+    line: usize,
+}
+
+impl PciDatabaseError {
+    pub(super) fn new(kind: PciDatabaseErrorKind, line: usize) -> PciDatabaseError {
+        PciDatabaseError { kind, line }
+    }
+
+    pub fn kind(&self) -> &PciDatabaseErrorKind { &self.kind }
+
+    // 1-based. 0 when the file could not be read at all.
+    pub fn line(&self) -> usize { self.line }
+}
+
+impl Display for PciDatabaseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.kind)
+        } else {
+            write!(f, "{} on line {}", self.kind, self.line)
+        }
+    }
+}
+
+impl std::error::Error for PciDatabaseError {}
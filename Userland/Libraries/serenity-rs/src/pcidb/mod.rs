@@ -0,0 +1,102 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Names for PCI IDs, from the pci.ids database LibPCIDB also reads:
+//
+//     let database = pcidb::Database::open(pcidb::DEFAULT_PATH)?;
+//     for device in procfs.pci()? {
+//         println!("{:?}", database.device(device.vendor_id, device.device_id));
+//     }
+//
+// All names live in one string, and each kind of entry is a sorted array of IDs and ranges into
+// it, so a lookup is a binary search and the whole database takes little more than its names.
+
+mod error;
+mod parser;
+
+use std::ops::Range;
+use std::path::Path;
+
+pub use error::{PciDatabaseError, PciDatabaseErrorKind};
+
+pub const DEFAULT_PATH: &str = "/res/pci.ids";
+
+#[derive(Clone, Debug)]
+struct Entry<K> {
+    id: K,
+    name: Range<u32>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Database {
+    names: String,
+    vendors: Vec<Entry<u16>>,
+    // Keyed by vendor and device.
+    devices: Vec<Entry<(u16, u16)>>,
+    // Keyed by vendor, device, subsystem vendor and subsystem device.
+    subsystems: Vec<Entry<(u16, u16, u16, u16)>>,
+    classes: Vec<Entry<u8>>,
+    subclasses: Vec<Entry<(u8, u8)>>,
+    programming_interfaces: Vec<Entry<(u8, u8, u8)>>,
+}
+
+impl Database {
+    pub fn open(path: impl AsRef<Path>) -> Result<Database, PciDatabaseError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|error| PciDatabaseError::new(PciDatabaseErrorKind::Io(error), 0))?;
+        Database::parse(&contents)
+    }
+
+    // Lines the database does not know, like the later sections of newer files, are skipped.
+    pub fn parse(input: &str) -> Result<Database, PciDatabaseError> { parser::parse(input) }
+
+    pub fn vendor(&self, vendor_id: u16) -> Option<&str> { self.find(&self.vendors, vendor_id) }
+
+    pub fn device(&self, vendor_id: u16, device_id: u16) -> Option<&str> {
+        self.find(&self.devices, (vendor_id, device_id))
+    }
+
+    pub fn subsystem(
+        &self,
+        vendor_id: u16,
+        device_id: u16,
+        subsystem_vendor_id: u16,
+        subsystem_id: u16,
+    ) -> Option<&str> {
+        let id = (vendor_id, device_id, subsystem_vendor_id, subsystem_id);
+        self.find(&self.subsystems, id)
+    }
+
+    pub fn class(&self, class: u8) -> Option<&str> { self.find(&self.classes, class) }
+
+    pub fn subclass(&self, class: u8, subclass: u8) -> Option<&str> {
+        self.find(&self.subclasses, (class, subclass))
+    }
+
+    pub fn programming_interface(
+        &self,
+        class: u8,
+        subclass: u8,
+        programming_interface: u8,
+    ) -> Option<&str> {
+        self.find(
+            &self.programming_interfaces,
+            (class, subclass, programming_interface),
+        )
+    }
+
+    pub fn vendor_count(&self) -> usize { self.vendors.len() }
+
+    pub fn device_count(&self) -> usize { self.devices.len() }
+
+    pub fn class_count(&self) -> usize { self.classes.len() }
+
+    fn find<K: Ord + Copy>(&self, entries: &[Entry<K>], id: K) -> Option<&str> {
+        let index = entries.binary_search_by_key(&id, |entry| entry.id).ok()?;
+        let name = &entries[index].name;
+        Some(&self.names[name.start as usize..name.end as usize])
+    }
+}
@@ -0,0 +1,198 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// The pci.ids format. Vendors start at the beginning of a line, their devices are indented by one
+// tab and the devices' subsystems by two:
+//
+//     8086  Intel Corporation
+//     	7010  82371SB PIIX3 IDE [Natoma/Triton II]
+//     		1af4 1100  Qemu virtual machine
+//
+// Classes follow the same layout with subclasses and programming interfaces, but are prefixed
+// with "C":
+//
+//     C 01  Mass storage controller
+//     	01  IDE interface
+//     		80  ISA Compatibility mode-only controller, supports bus mastering
+//
+// Other sections, like the "V" and "L" ones of newer files, are skipped along with everything
+// indented under them.
+
+use super::{Database, Entry, PciDatabaseError, PciDatabaseErrorKind};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    None,
+    Vendors,
+    Classes,
+    Other,
+}
+
+struct Parser<'a> {
+    database: Database,
+    section: Section,
+    // What the indented lines belong to: the last vendor and device, or class and subclass. A
+    // device is forgotten by the next vendor, so that its subsystems can't end up under the
+    // previous vendor's last device.
+    vendor: Option<u16>,
+    device: Option<u16>,
+    class: Option<u8>,
+    subclass: Option<u8>,
+    line: usize,
+    input: &'a str,
+}
+
+// Splits "8086  Intel Corporation" into the ID and the name. IDs are fixed-width lowercase hex.
+fn split_id<T: TryFrom<u32>>(text: &str, digits: usize) -> Option<(T, &str)> {
+    let id = text.get(..digits)?;
+    if !id.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    let rest = &text[digits..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+    let id = u32::from_str_radix(id, 16).ok()?;
+    Some((T::try_from(id).ok()?, rest.trim()))
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, kind: PciDatabaseErrorKind) -> PciDatabaseError {
+        PciDatabaseError::new(kind, self.line)
+    }
+
+    fn name(&mut self, name: &str) -> std::ops::Range<u32> {
+        let start = self.database.names.len() as u32;
+        self.database.names.push_str(name);
+        start..self.database.names.len() as u32
+    }
+
+    fn parse_vendor_line(&mut self, depth: usize, text: &str) -> Result<(), PciDatabaseError> {
+        let invalid = || self.error(PciDatabaseErrorKind::InvalidId);
+        let orphan = || self.error(PciDatabaseErrorKind::Orphan);
+        match depth {
+            0 => {
+                let (id, name) = split_id::<u16>(text, 4).ok_or_else(invalid)?;
+                let name = self.name(name);
+                self.database.vendors.push(Entry { id, name });
+                (self.vendor, self.device) = (Some(id), None);
+            }
+            1 => {
+                let vendor = self.vendor.ok_or_else(orphan)?;
+                let (id, name) = split_id::<u16>(text, 4).ok_or_else(invalid)?;
+                let name = self.name(name);
+                self.database.devices.push(Entry {
+                    id: (vendor, id),
+                    name,
+                });
+                self.device = Some(id);
+            }
+            _ => {
+                let vendor = self.vendor.ok_or_else(orphan)?;
+                let device = self.device.ok_or_else(orphan)?;
+                let (subsystem_vendor, rest) = split_id::<u16>(text, 4).ok_or_else(invalid)?;
+                let (subsystem, name) = split_id::<u16>(rest, 4).ok_or_else(invalid)?;
+                let name = self.name(name);
+                self.database.subsystems.push(Entry {
+                    id: (vendor, device, subsystem_vendor, subsystem),
+                    name,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_class_line(&mut self, depth: usize, text: &str) -> Result<(), PciDatabaseError> {
+        let invalid = || self.error(PciDatabaseErrorKind::InvalidId);
+        let orphan = || self.error(PciDatabaseErrorKind::Orphan);
+        match depth {
+            0 => {
+                let text = text.strip_prefix("C ").ok_or_else(invalid)?;
+                let (id, name) = split_id::<u8>(text, 2).ok_or_else(invalid)?;
+                let name = self.name(name);
+                self.database.classes.push(Entry { id, name });
+                (self.class, self.subclass) = (Some(id), None);
+            }
+            1 => {
+                let class = self.class.ok_or_else(orphan)?;
+                let (id, name) = split_id::<u8>(text, 2).ok_or_else(invalid)?;
+                let name = self.name(name);
+                self.database.subclasses.push(Entry {
+                    id: (class, id),
+                    name,
+                });
+                self.subclass = Some(id);
+            }
+            _ => {
+                let class = self.class.ok_or_else(orphan)?;
+                let subclass = self.subclass.ok_or_else(orphan)?;
+                let (id, name) = split_id::<u8>(text, 2).ok_or_else(invalid)?;
+                let name = self.name(name);
+                self.database.programming_interfaces.push(Entry {
+                    id: (class, subclass, id),
+                    name,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn parse(mut self) -> Result<Database, PciDatabaseError> {
+        for (index, line) in self.input.lines().enumerate() {
+            self.line = index + 1;
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let text = line.trim_start_matches('\t');
+            let depth = line.len() - text.len();
+            if depth == 0 {
+                self.section = if text.starts_with("C ") {
+                    Section::Classes
+                } else if text.starts_with(|ch: char| ch.is_ascii_hexdigit()) {
+                    Section::Vendors
+                } else {
+                    Section::Other
+                };
+            }
+            match self.section {
+                Section::Vendors => self.parse_vendor_line(depth, text)?,
+                Section::Classes => self.parse_class_line(depth, text)?,
+                Section::None => return Err(self.error(PciDatabaseErrorKind::Orphan)),
+                Section::Other => {}
+            }
+        }
+
+        // The file is sorted already, but lookups must not depend on that. Of any duplicates, the
+        // first one in the file is kept.
+        let database = &mut self.database;
+        fn sort<K: Ord + Copy>(entries: &mut Vec<Entry<K>>) {
+            entries.sort_by_key(|entry| entry.id);
+            entries.dedup_by_key(|entry| entry.id);
+        }
+        sort(&mut database.vendors);
+        sort(&mut database.devices);
+        sort(&mut database.subsystems);
+        sort(&mut database.classes);
+        sort(&mut database.subclasses);
+        sort(&mut database.programming_interfaces);
+        database.names.shrink_to_fit();
+        Ok(self.database)
+    }
+}
+
+pub(super) fn parse(input: &str) -> Result<Database, PciDatabaseError> {
+    Parser {
+        database: Database::default(),
+        section: Section::None,
+        vendor: None,
+        device: None,
+        class: None,
+        subclass: None,
+        line: 0,
+        input,
+    }
+    .parse()
+}
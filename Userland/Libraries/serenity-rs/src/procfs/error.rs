@@ -0,0 +1,60 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use crate::json::{self, ParseError};
+
+#[derive(Debug)]
+pub enum ProcFSErrorKind {
+    Io(std::io::Error),
+    Parse(ParseError),
+    // The node parsed, but a member is missing or has the wrong type. The error carries the
+    // location of the value, e.g. `[3].interrupt_line`.
+    Invalid(json::serde::Error),
+    // The contents of a node that is not JSON, like uptime, could not be read.
+    UnexpectedShape,
+}
+
+impl Display for ProcFSErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProcFSErrorKind::Io(error) => write!(f, "{}", error),
+            ProcFSErrorKind::Parse(error) => write!(f, "{}", error),
+            ProcFSErrorKind::Invalid(error) => write!(f, "{}", error),
+            ProcFSErrorKind::UnexpectedShape => write!(f, "unexpected contents"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ProcFSError {
+    kind: ProcFSErrorKind,
+    path: PathBuf,
+}
+
+impl ProcFSError {
+    pub(super) fn new(kind: ProcFSErrorKind, path: &Path) -> ProcFSError {
+        ProcFSError {
+            kind,
+            path: path.to_path_buf(),
+        }
+    }
+
+    pub fn kind(&self) -> &ProcFSErrorKind { &self.kind }
+
+    // The node that could not be read.
+    pub fn path(&self) -> &Path { &self.path }
+}
+
+impl Display for ProcFSError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.kind)
+    }
+}
+
+impl std::error::Error for ProcFSError {}
@@ -0,0 +1,117 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Typed readers for the global ProcFS nodes:
+//
+//     let procfs = procfs::ProcFS::new();
+//     sys::unveil(&procfs.path("interrupts").to_string_lossy(), "r")?;
+//     for handler in procfs.interrupts()? {
+//         println!("{}: {}", handler.interrupt_line, handler.call_count);
+//     }
+//
+// The readers only use std::fs, so pointing ProcFS::with_root() at a directory of recorded nodes
+// works on any host. Unveiling is left to the caller, which knows which nodes it needs.
+
+mod error;
+mod net;
+mod process;
+mod system;
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub use error::{ProcFSError, ProcFSErrorKind};
+pub use net::{ArpEntry, LocalSocket, NetworkAdapter, SocketOrigin, TcpSocket, UdpSocket};
+pub use process::{AllProcesses, Process, Thread};
+use serde::de::DeserializeOwned;
+pub use system::{
+    Device,
+    DeviceType,
+    InterruptHandler,
+    MemoryStatus,
+    MountedFileSystem,
+    PciDevice,
+    Processor,
+    PAGE_SIZE,
+};
+
+use crate::json;
+
+#[derive(Clone, Debug)]
+pub struct ProcFS {
+    root: PathBuf,
+}
+
+impl Default for ProcFS {
+    fn default() -> ProcFS { ProcFS::new() }
+}
+
+impl ProcFS {
+    pub fn new() -> ProcFS { ProcFS::with_root("/proc") }
+
+    pub fn with_root(root: impl Into<PathBuf>) -> ProcFS { ProcFS { root: root.into() } }
+
+    pub fn root(&self) -> &Path { &self.root }
+
+    // The path of a node, like "net/tcp", for unveiling it.
+    pub fn path(&self, node: &str) -> PathBuf { self.root.join(node) }
+
+    pub fn cpuinfo(&self) -> Result<Vec<Processor>, ProcFSError> { self.read_json("cpuinfo") }
+
+    pub fn interrupts(&self) -> Result<Vec<InterruptHandler>, ProcFSError> {
+        self.read_json("interrupts")
+    }
+
+    pub fn memstat(&self) -> Result<MemoryStatus, ProcFSError> { self.read_json("memstat") }
+
+    pub fn df(&self) -> Result<Vec<MountedFileSystem>, ProcFSError> { self.read_json("df") }
+
+    // Whole seconds since boot.
+    pub fn uptime(&self) -> Result<Duration, ProcFSError> {
+        let path = self.path("uptime");
+        let contents = self.read(&path)?;
+        contents
+            .trim()
+            .parse()
+            .map(Duration::from_secs)
+            .map_err(|_| ProcFSError::new(ProcFSErrorKind::UnexpectedShape, &path))
+    }
+
+    pub fn pci(&self) -> Result<Vec<PciDevice>, ProcFSError> { self.read_json("pci") }
+
+    pub fn devices(&self) -> Result<Vec<Device>, ProcFSError> { self.read_json("devices") }
+
+    pub fn net_adapters(&self) -> Result<Vec<NetworkAdapter>, ProcFSError> {
+        self.read_json("net/adapters")
+    }
+
+    pub fn net_arp(&self) -> Result<Vec<ArpEntry>, ProcFSError> { self.read_json("net/arp") }
+
+    pub fn net_tcp(&self) -> Result<Vec<TcpSocket>, ProcFSError> { self.read_json("net/tcp") }
+
+    pub fn net_udp(&self) -> Result<Vec<UdpSocket>, ProcFSError> { self.read_json("net/udp") }
+
+    pub fn net_local(&self) -> Result<Vec<LocalSocket>, ProcFSError> { self.read_json("net/local") }
+
+    pub fn all(&self) -> Result<AllProcesses, ProcFSError> { self.read_json("all") }
+
+    fn read(&self, path: &Path) -> Result<String, ProcFSError> {
+        std::fs::read_to_string(path)
+            .map_err(|error| ProcFSError::new(ProcFSErrorKind::Io(error), path))
+    }
+
+    fn read_json<T: DeserializeOwned>(&self, node: &str) -> Result<T, ProcFSError> {
+        let path = self.path(node);
+        let contents = self.read(&path)?;
+        json::serde::from_str(&contents).map_err(|error| {
+            let kind = match error {
+                json::serde::Error::Parse(error) => ProcFSErrorKind::Parse(error),
+                error => ProcFSErrorKind::Invalid(error),
+            };
+            ProcFSError::new(kind, &path)
+        })
+    }
+}
@@ -0,0 +1,117 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// The nodes under /proc/net.
+
+use std::net::Ipv4Addr;
+
+use serde::{de, Deserialize, Deserializer};
+
+// One entry of /proc/net/adapters.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct NetworkAdapter {
+    pub name: String,
+    pub class_name: String,
+    pub mac_address: String,
+    // Left out by the kernel while they are unset.
+    pub ipv4_address: Option<Ipv4Addr>,
+    pub ipv4_netmask: Option<Ipv4Addr>,
+    pub ipv4_gateway: Option<Ipv4Addr>,
+    pub packets_in: u64,
+    pub bytes_in: u64,
+    pub packets_out: u64,
+    pub bytes_out: u64,
+    pub link_up: bool,
+    // In Mbit/s, or -1 if unknown.
+    pub link_speed: i32,
+    pub link_full_duplex: bool,
+    pub mtu: u32,
+}
+
+// One entry of /proc/net/arp.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct ArpEntry {
+    pub ip_address: Ipv4Addr,
+    pub mac_address: String,
+}
+
+// The process that opened a socket. The kernel only shows it to root and to the socket's owner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SocketOrigin {
+    pub pid: i32,
+    pub uid: u32,
+    pub gid: u32,
+}
+
+// The origin_* members of a socket, which the kernel adds or leaves out together.
+#[derive(Deserialize)]
+struct OriginMembers {
+    origin_pid: Option<i32>,
+    origin_uid: Option<u32>,
+    origin_gid: Option<u32>,
+}
+
+fn deserialize_origin<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<SocketOrigin>, D::Error> {
+    let members = OriginMembers::deserialize(deserializer)?;
+    let pid = match members.origin_pid {
+        Some(pid) => pid,
+        None => return Ok(None),
+    };
+    Ok(Some(SocketOrigin {
+        pid,
+        uid: members
+            .origin_uid
+            .ok_or_else(|| de::Error::missing_field("origin_uid"))?,
+        gid: members
+            .origin_gid
+            .ok_or_else(|| de::Error::missing_field("origin_gid"))?,
+    }))
+}
+
+// One entry of /proc/net/tcp.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct TcpSocket {
+    pub local_address: Ipv4Addr,
+    pub local_port: u16,
+    pub peer_address: Ipv4Addr,
+    pub peer_port: u16,
+    // As the kernel names it, e.g. "Listen" or "Established".
+    pub state: String,
+    pub ack_number: u32,
+    pub sequence_number: u32,
+    pub packets_in: u64,
+    pub bytes_in: u64,
+    pub packets_out: u64,
+    pub bytes_out: u64,
+    #[serde(flatten, deserialize_with = "deserialize_origin")]
+    pub origin: Option<SocketOrigin>,
+}
+
+// One entry of /proc/net/udp.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct UdpSocket {
+    pub local_address: Ipv4Addr,
+    pub local_port: u16,
+    pub peer_address: Ipv4Addr,
+    pub peer_port: u16,
+    #[serde(flatten, deserialize_with = "deserialize_origin")]
+    pub origin: Option<SocketOrigin>,
+}
+
+// One entry of /proc/net/local, a Unix domain socket.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct LocalSocket {
+    pub path: String,
+    pub origin_pid: i32,
+    pub origin_uid: u32,
+    pub origin_gid: u32,
+    // All zero while no connection has been accepted.
+    pub acceptor_pid: i32,
+    pub acceptor_uid: u32,
+    pub acceptor_gid: u32,
+}
@@ -0,0 +1,74 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// /proc/all, every process and thread in the system.
+
+use serde::Deserialize;
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct AllProcesses {
+    // The kernel's own process (the "colonel") comes first.
+    pub processes: Vec<Process>,
+    // Scheduler time across all threads, in the units of Thread::time_user and time_kernel.
+    pub total_time: u64,
+    pub total_time_kernel: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Process {
+    pub pid: i32,
+    pub ppid: i32,
+    // The foreground process group of the controlling terminal, or 0 without one.
+    pub pgid: i32,
+    // The process group this process is in.
+    pub pgp: i32,
+    pub sid: i32,
+    pub uid: u32,
+    pub gid: u32,
+    pub name: String,
+    pub executable: String,
+    // The name of the controlling terminal, or empty.
+    pub tty: String,
+    // Space-separated promises. Empty for kernel processes.
+    pub pledge: String,
+    // "None", "Dropped" or "Locked". Empty for kernel processes.
+    pub veil: String,
+    pub nfds: u32,
+    // Memory amounts are in bytes.
+    pub amount_virtual: u64,
+    pub amount_resident: u64,
+    pub amount_dirty_private: u64,
+    pub amount_clean_inode: u64,
+    pub amount_shared: u64,
+    pub amount_purgeable_volatile: u64,
+    pub amount_purgeable_nonvolatile: u64,
+    pub dumpable: bool,
+    pub kernel: bool,
+    pub threads: Vec<Thread>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Thread {
+    pub tid: i32,
+    pub name: String,
+    // "Running", "Runnable", "Stopped" and so on, or what a blocked thread is waiting for.
+    pub state: String,
+    pub cpu: u32,
+    pub priority: u32,
+    pub times_scheduled: u64,
+    pub time_user: u64,
+    pub time_kernel: u64,
+    pub syscall_count: u64,
+    pub inode_faults: u64,
+    pub zero_faults: u64,
+    pub cow_faults: u64,
+    pub file_read_bytes: u64,
+    pub file_write_bytes: u64,
+    pub unix_socket_read_bytes: u64,
+    pub unix_socket_write_bytes: u64,
+    pub ipv4_socket_read_bytes: u64,
+    pub ipv4_socket_write_bytes: u64,
+}
@@ -0,0 +1,107 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// The nodes describing the machine: /proc/cpuinfo, interrupts, memstat, df, pci and devices.
+
+use serde::Deserialize;
+
+// The size of the pages memstat counts in.
+pub const PAGE_SIZE: u64 = 4096;
+
+// One entry of /proc/cpuinfo.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Processor {
+    pub processor: u32,
+    pub vendor_id: String,
+    // Only present when running under a hypervisor.
+    pub hypervisor_vendor_id: Option<String>,
+    pub brand: String,
+    pub family: u32,
+    pub model: u32,
+    pub stepping: u32,
+    #[serde(rename = "type")]
+    pub processor_type: u32,
+    pub features: Vec<String>,
+}
+
+// One entry of /proc/interrupts.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct InterruptHandler {
+    pub purpose: String,
+    pub interrupt_line: u32,
+    pub controller: String,
+    pub cpu_handler: u32,
+    pub device_sharing: u32,
+    // The total across all CPUs.
+    pub call_count: u64,
+    // Indexed by processor. ProcFSInterrupts only writes call_count, so this is always None for
+    // now; it is read from a "per_cpu_call_counts" array for when the kernel counts per CPU.
+    pub per_cpu_call_counts: Option<Vec<u64>>,
+}
+
+// /proc/memstat. The kmalloc counters are in bytes, the physical ones in pages of PAGE_SIZE.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct MemoryStatus {
+    pub kmalloc_allocated: u64,
+    pub kmalloc_available: u64,
+    pub user_physical_allocated: u64,
+    pub user_physical_available: u64,
+    pub user_physical_committed: u64,
+    pub user_physical_uncommitted: u64,
+    pub super_physical_allocated: u64,
+    pub super_physical_available: u64,
+    pub kmalloc_call_count: u64,
+    pub kfree_call_count: u64,
+}
+
+// One mounted file system in /proc/df.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct MountedFileSystem {
+    pub class_name: String,
+    pub mount_point: String,
+    // The backing file, or "none".
+    pub source: String,
+    pub total_block_count: u64,
+    pub free_block_count: u64,
+    pub total_inode_count: u64,
+    pub free_inode_count: u64,
+    pub block_size: u64,
+    pub readonly: bool,
+    pub mount_flags: i32,
+}
+
+// One entry of /proc/pci.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct PciDevice {
+    pub domain: u32,
+    pub bus: u8,
+    pub device: u8,
+    pub function: u8,
+    pub vendor_id: u16,
+    pub device_id: u16,
+    pub revision_id: u8,
+    pub class: u8,
+    pub subclass: u8,
+    pub subsystem_id: u16,
+    pub subsystem_vendor_id: u16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeviceType {
+    Block,
+    Character,
+}
+
+// One entry of /proc/devices.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct Device {
+    pub major: u32,
+    pub minor: u32,
+    pub class_name: String,
+    #[serde(rename = "type")]
+    pub device_type: DeviceType,
+}
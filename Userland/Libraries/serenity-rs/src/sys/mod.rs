@@ -1,6 +1,9 @@
 extern crate libc;
 
-use std::ffi::{c_char, CString};
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::net::Ipv4Addr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 pub fn pledge(promises: &str) -> std::io::Result<()> {
     let promises_c_string = CString::new(promises).unwrap();
@@ -43,3 +46,151 @@ pub fn lock_veil() -> std::io::Result<()> {
         Ok(())
     }
 }
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn set_interrupted(_: libc::c_int) { INTERRUPTED.store(true, Ordering::Relaxed); }
+
+// Replaces the default SIGINT action, which kills the process, with setting a flag that
+// interrupted() returns. Lets long-running loops stop at a point where they can clean up.
+pub fn catch_interrupt() -> std::io::Result<()> {
+    let handler = set_interrupted as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    if unsafe { libc::signal(libc::SIGINT, handler) } == libc::SIG_ERR {
+        Err(std::io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn interrupted() -> bool { INTERRUPTED.load(Ordering::Relaxed) }
+
+// struct termios from Kernel/API/POSIX/termios.h.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Termios {
+    input_flags: u32,
+    output_flags: u32,
+    control_flags: u32,
+    local_flags: u32,
+    control_characters: [u8; 32],
+    input_speed: u32,
+    output_speed: u32,
+}
+
+const ICANON: u32 = 0o2;
+const ECHO: u32 = 0o10;
+const VTIME: usize = 5;
+const VMIN: usize = 6;
+const TCSANOW: c_int = 0;
+
+// struct pollfd from Kernel/API/POSIX/poll.h.
+#[repr(C)]
+struct PollDescriptor {
+    fd: c_int,
+    events: i16,
+    returned_events: i16,
+}
+
+const POLLIN: i16 = 1;
+
+extern "C" {
+    fn tcgetattr(fd: c_int, termios: *mut Termios) -> c_int;
+    fn tcsetattr(fd: c_int, action: c_int, termios: *const Termios) -> c_int;
+    fn poll(descriptors: *mut PollDescriptor, count: u32, timeout: c_int) -> c_int;
+    fn read(fd: c_int, buffer: *mut c_void, count: usize) -> isize;
+}
+
+// Lets keys on standard input be read as they are pressed, without echoing them, until dropped.
+// ^C still raises SIGINT. Needs the "tty" promise, also for the drop.
+pub struct RawTerminal {
+    original: Termios,
+}
+
+impl RawTerminal {
+    pub fn enable() -> std::io::Result<RawTerminal> {
+        let mut termios = std::mem::MaybeUninit::<Termios>::uninit();
+        if unsafe { tcgetattr(0, termios.as_mut_ptr()) } < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        let original = unsafe { termios.assume_init() };
+        let mut raw = original;
+        raw.local_flags &= !(ICANON | ECHO);
+        raw.control_characters[VMIN] = 1;
+        raw.control_characters[VTIME] = 0;
+        if unsafe { tcsetattr(0, TCSANOW, &raw) } < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(RawTerminal { original })
+    }
+
+    // The next byte typed, or None if nothing was typed within the timeout or a signal came
+    // first. Keys that send escape sequences come out one byte at a time. Fails with
+    // UnexpectedEof once the terminal is gone.
+    pub fn read_key(&self, timeout: Duration) -> std::io::Result<Option<u8>> {
+        let mut descriptor = PollDescriptor {
+            fd: 0,
+            events: POLLIN,
+            returned_events: 0,
+        };
+        let milliseconds = timeout.as_millis().min(c_int::MAX as u128) as c_int;
+        match unsafe { poll(&mut descriptor, 1, milliseconds) } {
+            0 => return Ok(None),
+            result if result < 0 => {
+                let error = std::io::Error::last_os_error();
+                return match error.kind() {
+                    std::io::ErrorKind::Interrupted => Ok(None),
+                    _ => Err(error),
+                };
+            }
+            _ => {}
+        }
+        let mut key = 0u8;
+        match unsafe { read(0, &mut key as *mut u8 as *mut c_void, 1) } {
+            1 => Ok(Some(key)),
+            0 => Err(std::io::ErrorKind::UnexpectedEof.into()),
+            _ => Err(std::io::Error::last_os_error()),
+        }
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) { unsafe { tcsetattr(0, TCSANOW, &self.original) }; }
+}
+
+// struct hostent from LibC's netdb.h, field for field: h_name, h_aliases, h_addrtype, h_length
+// and h_addr_list. The two ints fill the space between the pointers exactly, so the size below
+// holds for both 32-bit and 64-bit pointers.
+#[repr(C)]
+struct HostEntry {
+    name: *const c_char,
+    aliases: *const *const c_char,
+    address_type: c_int,
+    length: c_int,
+    addresses: *const *const c_char,
+}
+
+const _: () =
+    assert!(std::mem::size_of::<HostEntry>() == 3 * std::mem::size_of::<*const c_char>() + 8);
+
+extern "C" {
+    // The length is a socklen_t, which is a uint32_t in Kernel/API/POSIX/sys/types.h. The entry
+    // is LibC's own static buffer, so the name is copied out before anything else calls it.
+    fn gethostbyaddr(address: *const c_void, length: u32, address_type: c_int) -> *const HostEntry;
+}
+
+// The name LookupServer knows an address by, if any. Needs the "unix" promise and
+// /tmp/portal/lookup unveiled "rw".
+pub fn host_name(address: Ipv4Addr) -> Option<String> {
+    // An in_addr, which is in network byte order.
+    let octets = address.octets();
+    let entry = unsafe { gethostbyaddr(octets.as_ptr() as *const c_void, 4, libc::AF_INET) };
+    if entry.is_null() || unsafe { (*entry).name.is_null() } {
+        return None;
+    }
+    let name = unsafe { CStr::from_ptr((*entry).name) }.to_string_lossy();
+    if name.is_empty() {
+        None
+    } else {
+        Some(name.into_owned())
+    }
+}
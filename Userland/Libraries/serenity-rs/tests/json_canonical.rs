@@ -0,0 +1,127 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// The examples of RFC 8785 (JSON Canonicalization Scheme).
+
+use serenity::json::{self, Number, ParseOptions, Value};
+
+fn canonicalize(string: &str) -> Option<String> {
+    json::serialize_canonical(&json::parse(string).unwrap())
+}
+
+// Appendix B: IEEE 754 bit patterns and the text they serialize to.
+#[test]
+fn writes_numbers_as_ecmascript_does() {
+    let cases = [
+        (0x0000000000000000, "0"),
+        (0x8000000000000000, "0"),
+        (0x0000000000000001, "5e-324"),
+        (0x8000000000000001, "-5e-324"),
+        (0x7fefffffffffffff, "1.7976931348623157e+308"),
+        (0xffefffffffffffff, "-1.7976931348623157e+308"),
+        (0x4340000000000000, "9007199254740992"),
+        (0xc340000000000000, "-9007199254740992"),
+        (0x4430000000000000, "295147905179352830000"),
+        (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+        (0x44b52d02c7e14af6, "1e+23"),
+        (0x44b52d02c7e14af7, "1.0000000000000001e+23"),
+        (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+        (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+        (0x444b1ae4d6e2ef50, "1e+21"),
+        (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+        (0x3eb0c6f7a0b5ed8d, "0.000001"),
+        (0x41b3de4355555553, "333333333.3333332"),
+        (0x41b3de4355555554, "333333333.33333325"),
+        (0x41b3de4355555555, "333333333.3333333"),
+        (0x41b3de4355555556, "333333333.3333334"),
+        (0x41b3de4355555557, "333333333.33333343"),
+        (0xbecbf647612f3696, "-0.0000033333333333333333"),
+        // Halfway between 1424953923781206.2 and 1424953923781206.3; the even digit wins.
+        (0x43143ff3c1cb0959, "1424953923781206.2"),
+    ];
+    for (bits, expected) in cases {
+        let value = Value::from(f64::from_bits(bits));
+        assert_eq!(
+            json::serialize_canonical(&value).as_deref(),
+            Some(expected),
+            "{:016x}",
+            bits
+        );
+    }
+    // Integers and raw numbers go through the nearest f64 too.
+    let raw = |string: &str| Value::Number(Number::Raw(string.to_string()));
+    assert_eq!(
+        json::serialize_canonical(&Value::from(u64::MAX)).unwrap(),
+        "18446744073709552000"
+    );
+    assert_eq!(
+        json::serialize_canonical(&raw("1424953923781206.25")).unwrap(),
+        "1424953923781206.2"
+    );
+    assert_eq!(json::serialize_canonical(&raw("4.50")).unwrap(), "4.5");
+}
+
+// NaN and the infinities (the last two rows of appendix B) have no canonical form.
+#[test]
+fn refuses_numbers_without_a_canonical_form() {
+    for bits in [0x7fffffffffffffff, 0x7ff0000000000000, 0xfff0000000000000] {
+        let value = Value::from(f64::from_bits(bits));
+        assert_eq!(json::serialize_canonical(&value), None, "{:016x}", bits);
+        assert_eq!(json::serialize(&value), "null");
+    }
+    let options = ParseOptions::default().with_raw_numbers();
+    let nested = json::parse_with_options(r#"{"a": [1, {"b": 1e400}]}"#, &options).unwrap();
+    assert_eq!(json::serialize_canonical(&nested), None);
+}
+
+// Section 3.2.2, the sample input and its canonical form.
+#[test]
+fn canonicalizes_the_sample_object() {
+    let input = r#"{
+        "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+        "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+        "literals": [null, true, false]
+    }"#;
+    let expected = concat!(
+        r#"{"literals":[null,true,false],"#,
+        r#""numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"#,
+        r#""string":"€$\u000f\nA'B\"\\\\\"/"}"#
+    );
+    assert_eq!(canonicalize(input).unwrap(), expected);
+    // Canonicalizing is idempotent.
+    assert_eq!(canonicalize(expected).unwrap(), expected);
+}
+
+// Section 3.2.3: keys are sorted by their UTF-16 code units, so the emoji (a surrogate pair
+// starting with 0xd83d) sorts before U+FB33, unlike when sorting by code point or UTF-8.
+#[test]
+fn sorts_keys_by_utf16_code_units() {
+    let input = r#"{
+        "€": "Euro Sign",
+        "\r": "Carriage Return",
+        "דּ": "Hebrew Letter Dalet With Dagesh",
+        "1": "One",
+        "😀": "Emoji: Grinning Face",
+        "\u0080": "Control",
+        "ö": "Latin Small Letter O With Diaeresis"
+    }"#;
+    let canonical = json::parse(&canonicalize(input).unwrap()).unwrap();
+    let keys: Vec<_> = canonical.as_object().unwrap().keys().cloned().collect();
+    assert_eq!(keys, [
+        "\r",
+        "1",
+        "\u{80}",
+        "\u{f6}",
+        "\u{20ac}",
+        "\u{1f600}",
+        "\u{fb33}"
+    ]);
+    // Nested objects are sorted too, and arrays keep their order.
+    assert_eq!(
+        canonicalize(r#"{"b": [{"z": 1, "y": 2}, 0], "a": {"d": 1, "c": 2}}"#).unwrap(),
+        r#"{"a":{"c":2,"d":1},"b":[{"y":2,"z":1},0]}"#
+    );
+}
@@ -0,0 +1,262 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use serenity::json::{Difference, Number, ParseOptions, Value};
+use serenity::{assert_json_eq, json};
+
+// The exact decimal values of the floats 1e300 and 5e-324.
+const EXACT_1E300: &str = "1000000000000000052504760255204420248704468581108159154915854115511802457988908195786371375080447864043704443832883878176942523235360430575644792184786706982848387200926575803737830233794788090059368953234970799945081119038967640880074652742780142494579258788820056842838115669472196386865459400540160";
+const EXACT_5E_324: &str = "4.940656458412465441765687928682213723650598026143247644255856825006755072702087518652998363616359923797965646954457177309266567103559397963987747960107818781263007131903114045278458171678489821036887186360569987307230500063874091535649843873124733972731696151400317153853980741262385655911710266585566867681870395603106249319452715914924553293054565444011274801297099995419319894090804165633245247571478690147267801593552386115501348035264934720193790268107107491703332226844753335720832431936092382893458368060106011506169809753078342277318329247904982524730776375927247874656084778203734469699533647017972677717585125660551199131504891101451037862738167250955837389733598993664809941164205702637090279242767544565229087538682506419718265533447265625e-324";
+
+fn raw(string: &str) -> Value { Value::Number(Number::Raw(string.to_string())) }
+
+fn parse_raw(string: &str) -> Value {
+    json::parse_with_options(string, &ParseOptions::default().with_raw_numbers()).unwrap()
+}
+
+fn hash(value: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+// Every pair has to compare as given both ways round, and equal values have to hash alike.
+fn assert_order(values: &[Value]) {
+    for (i, a) in values.iter().enumerate() {
+        for (j, b) in values.iter().enumerate() {
+            assert_eq!(a.cmp(b), i.cmp(&j), "{} vs {}", a, b);
+            assert_eq!(a == b, i == j, "{} == {}", a, b);
+        }
+    }
+}
+
+fn assert_equal(values: &[Value]) {
+    for a in values {
+        for b in values {
+            assert_eq!(a, b);
+            assert_eq!(a.cmp(b), Ordering::Equal, "{} vs {}", a, b);
+            assert_eq!(hash(a), hash(b), "{} and {} hash differently", a, b);
+        }
+    }
+}
+
+#[test]
+fn numbers_equal_across_representations() {
+    assert_equal(&[
+        Value::from(1),
+        Value::from(1u64),
+        Value::from(1.0),
+        raw("1"),
+        raw("1.000"),
+        raw("1e0"),
+        raw("0.1e1"),
+        raw("10E-1"),
+    ]);
+    assert_equal(&[
+        Value::from(0),
+        Value::from(0.0),
+        Value::from(-0.0),
+        raw("-0"),
+        raw("0e99"),
+    ]);
+    assert_equal(&[
+        Value::from(u64::MAX),
+        raw("18446744073709551615"),
+        raw("1.8446744073709551615e19"),
+    ]);
+    assert_equal(&[Value::from(i64::MIN), raw("-9223372036854775808.0")]);
+    // Floats are exact binary fractions, and raw numbers with the same value equal them.
+    assert_equal(&[
+        Value::from(0.1),
+        raw("0.1000000000000000055511151231257827021181583404541015625"),
+    ]);
+    assert_equal(&[Value::from(1e300), raw(EXACT_1E300)]);
+    assert_equal(&[Value::from(5e-324), raw(EXACT_5E_324)]);
+    // Integral floats beyond i128 hash alike too.
+    assert_equal(&[Value::from(1e300), raw(&format!("{}.000", EXACT_1E300))]);
+}
+
+#[test]
+fn raw_numbers_compare_exactly() {
+    // These all round to the same f64, or to infinity, but are different numbers.
+    assert_order(&[
+        raw("-2e400"),
+        raw("-1e400"),
+        Value::from(-1.0),
+        raw("0.1"),
+        Value::from(0.1),
+        raw("0.10000000000000001"),
+        raw("12345678901234567890123"),
+        raw("12345678901234567890124"),
+        raw("12345678901234567890124.5"),
+        raw("1e400"),
+        raw("2e400"),
+        raw("2.0000000000000000001e400"),
+        raw("1e9999999999999999999999"),
+    ]);
+    assert_ne!(parse_raw("1e400"), parse_raw("2e400"));
+    assert_ne!(
+        parse_raw("1234567890123456789012345"),
+        parse_raw("1234567890123456789012346")
+    );
+    assert_eq!(parse_raw("1e400"), parse_raw("10e399"));
+    assert_eq!(hash(&parse_raw("1e400")), hash(&parse_raw("10e399")));
+    assert_eq!(
+        hash(&parse_raw("1234567890123456789012345.0")),
+        hash(&parse_raw("1234567890123456789012345"))
+    );
+}
+
+#[test]
+fn special_floats_are_ordered() {
+    // NaN and the infinities only get in through Number::from, but still have a place.
+    assert_order(&[
+        Value::from(f64::NEG_INFINITY),
+        raw("-1e400"),
+        Value::from(f64::MIN),
+        Value::from(i64::MIN),
+        Value::from(-0.5),
+        Value::from(0),
+        Value::from(f64::MIN_POSITIVE),
+        Value::from(0.5),
+        Value::from(u64::MAX),
+        raw("1e400"),
+        Value::from(f64::INFINITY),
+        Value::from(f64::NAN),
+    ]);
+    assert_equal(&[Value::from(f64::NAN), Value::from(-f64::NAN)]);
+}
+
+#[test]
+fn values_are_ordered_by_type_then_content() {
+    assert_order(&[
+        Value::Null,
+        Value::from(false),
+        Value::from(true),
+        Value::from(-1),
+        Value::from(2.5),
+        Value::from(""),
+        Value::from("a"),
+        Value::from("b"),
+        json!([]),
+        json!([1]),
+        json!([1, 2]),
+        json!([2]),
+        json!({}),
+        json!({"a": 1}),
+        json!({"a": 1, "b": 0}),
+        json!({"a": 2}),
+        json!({"b": 0}),
+    ]);
+}
+
+#[test]
+fn member_order_does_not_matter() {
+    let a = json::parse(r#"{"x": 1, "y": [true, {"p": null, "q": 1.0}]}"#).unwrap();
+    let b = json::parse(r#"{"y": [true, {"q": 1, "p": null}], "x": 1.0}"#).unwrap();
+    assert_equal(&[a.clone(), b.clone()]);
+    assert_ne!(
+        a,
+        json::parse(r#"{"x": 1, "y": [{"q": 1, "p": null}, true]}"#).unwrap()
+    );
+    assert_ne!(a, json::parse(r#"{"x": 1}"#).unwrap());
+}
+
+#[test]
+fn equal_values_collapse_in_sets() {
+    let values = [
+        Value::from(1),
+        Value::from(1.0),
+        raw("1e0"),
+        json!({"a": 1, "b": 2}),
+        json!({"b": 2.0, "a": 1}),
+        raw("1e400"),
+        raw("2e400"),
+    ];
+    let hashed: std::collections::HashSet<_> = values.iter().collect();
+    let sorted: std::collections::BTreeSet<_> = values.iter().collect();
+    assert_eq!(hashed.len(), 4);
+    assert_eq!(sorted.len(), 4);
+}
+
+#[test]
+fn lists_differences_by_pointer() {
+    let left = json!({
+        "pid": 1,
+        "name": "init",
+        "threads": [{"tid": 1, "state": "Running"}, {"tid": 2}],
+        "a/b": 0,
+    });
+    let right = json!({
+        "pid": 1.0,
+        "name": "SystemServer",
+        "threads": [{"tid": 1, "state": "Blocked"}],
+        "a/b": 0,
+        "kernel": false,
+    });
+    let differences = json::differences(&left, &right);
+    assert_eq!(differences, [
+        Difference {
+            pointer: "/name".to_string(),
+            left: Some(&left["name"]),
+            right: Some(&right["name"]),
+        },
+        Difference {
+            pointer: "/threads/0/state".to_string(),
+            left: Some(&left["threads"][0]["state"]),
+            right: Some(&right["threads"][0]["state"]),
+        },
+        Difference {
+            pointer: "/threads/1".to_string(),
+            left: Some(&left["threads"][1]),
+            right: None,
+        },
+        Difference {
+            pointer: "/kernel".to_string(),
+            left: None,
+            right: Some(&right["kernel"]),
+        },
+    ]);
+    let lines: Vec<_> = differences.iter().map(|d| d.to_string()).collect();
+    assert_eq!(lines, [
+        "/name: \"init\" != \"SystemServer\"",
+        "/threads/0/state: \"Running\" != \"Blocked\"",
+        "/threads/1: {\"tid\":2} != (missing)",
+        "/kernel: (missing) != false",
+    ]);
+
+    assert!(json::differences(&left, &left.clone()).is_empty());
+    assert_eq!(
+        json::differences(&json!(1), &json!("1"))[0].to_string(),
+        "(root): 1 != \"1\""
+    );
+    // differences() is empty exactly when the values are equal, raw numbers included.
+    assert_eq!(json::differences(&raw("1e400"), &raw("2e400")).len(), 1);
+    assert!(json::differences(&raw("1e400"), &raw("10e399")).is_empty());
+}
+
+#[test]
+fn assert_json_eq_accepts_equal_values() {
+    assert_json_eq!(
+        json::parse(r#"{"b": [1, 2.0], "a": null}"#).unwrap(),
+        json!({"a": null, "b": [1.0, 2]}),
+    );
+}
+
+#[test]
+#[should_panic(
+    expected = "JSON values are not equal:\n    /b/1: 2 != 3\n    /c: (missing) != true"
+)]
+fn assert_json_eq_lists_differences() {
+    assert_json_eq!(
+        json!({"a": 1, "b": [1, 2]}),
+        json!({"a": 1, "b": [1, 3], "c": true})
+    );
+}
@@ -0,0 +1,412 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Parser conformance, named after the cases of JSONTestSuite
+// (https://github.com/nst/JSONTestSuite): y_ documents must parse, n_ documents must not, and
+// i_ documents are up to the implementation, so the tests pin down what this one does.
+
+use serenity::json::{self, ParseErrorKind, Reader, Value};
+
+const ACCEPTED: &[(&str, &str)] = &[
+    ("y_array_arraysWithSpaces", "[[]   ]"),
+    ("y_array_empty-string", "[\"\"]"),
+    ("y_array_empty", "[]"),
+    ("y_array_ending_with_newline", "[\"a\"]"),
+    ("y_array_false", "[false]"),
+    ("y_array_heterogeneous", "[null, 1, \"1\", {}]"),
+    ("y_array_null", "[null]"),
+    ("y_array_with_1_and_newline", "[1\n]"),
+    ("y_array_with_leading_space", " [1]"),
+    ("y_array_with_several_null", "[1,null,null,null,2]"),
+    ("y_array_with_trailing_space", "[2] "),
+    ("y_number_0e+1", "[0e+1]"),
+    ("y_number_0e1", "[0e1]"),
+    ("y_number_after_space", "[ 4]"),
+    (
+        "y_number_double_close_to_zero",
+        "[-0.000000000000000000000000000000000000000000000000000000000000000000000000000001]",
+    ),
+    ("y_number_int_with_exp", "[20e1]"),
+    ("y_number_minus_zero", "[-0]"),
+    ("y_number_negative_int", "[-123]"),
+    ("y_number_negative_one", "[-1]"),
+    ("y_number_negative_zero", "[-0]"),
+    ("y_number_real_capital_e", "[1E22]"),
+    ("y_number_real_capital_e_neg_exp", "[1E-2]"),
+    ("y_number_real_capital_e_pos_exp", "[1E+2]"),
+    ("y_number_real_exponent", "[123e45]"),
+    ("y_number_real_fraction_exponent", "[123.456e78]"),
+    ("y_number_real_neg_exp", "[1e-2]"),
+    ("y_number_real_pos_exponent", "[1e+2]"),
+    ("y_number_simple_int", "[123]"),
+    ("y_number_simple_real", "[123.456789]"),
+    ("y_number", "[123e65]"),
+    ("y_object_basic", "{\"asd\":\"sdf\"}"),
+    (
+        "y_object_duplicated_key_and_value",
+        "{\"a\":\"b\",\"a\":\"b\"}",
+    ),
+    ("y_object_duplicated_key", "{\"a\":\"b\",\"a\":\"c\"}"),
+    ("y_object_empty", "{}"),
+    ("y_object_empty_key", "{\"\":0}"),
+    ("y_object_escaped_null_in_key", "{\"foo\\u0000bar\": 42}"),
+    (
+        "y_object_extreme_numbers",
+        "{ \"min\": -1.0e+28, \"max\": 1.0e+28 }",
+    ),
+    ("y_object_simple", "{\"a\":[]}"),
+    (
+        "y_object_string_unicode",
+        "{\"title\":\"\\u041f\\u043e\\u043b\\u0442\\u043e\"}",
+    ),
+    ("y_object_with_newlines", "{\n\"a\": \"b\"\n}"),
+    ("y_object", "{\"asd\":\"sdf\", \"dfg\":\"fgh\"}"),
+    (
+        "y_string_1_2_3_bytes_UTF-8_sequences",
+        "[\"\\u0060\\u012a\\u12AB\"]",
+    ),
+    ("y_string_accepted_surrogate_pair", "[\"\\uD801\\udc37\"]"),
+    (
+        "y_string_accepted_surrogate_pairs",
+        "[\"\\ud83d\\ude39\\ud83d\\udc8d\"]",
+    ),
+    (
+        "y_string_allowed_escapes",
+        "[\"\\\"\\\\\\/\\b\\f\\n\\r\\t\"]",
+    ),
+    ("y_string_backslash_and_u_escaped_zero", "[\"\\\\u0000\"]"),
+    ("y_string_backslash_doublequotes", "[\"\\\"\"]"),
+    ("y_string_comments", "[\"a/*b*/c/*d//e\"]"),
+    ("y_string_double_escape_a", "[\"\\\\a\"]"),
+    ("y_string_double_escape_n", "[\"\\\\n\"]"),
+    ("y_string_escaped_control_character", "[\"\\u0012\"]"),
+    ("y_string_escaped_noncharacter", "[\"\\uFFFF\"]"),
+    ("y_string_in_array", "[\"asd\"]"),
+    ("y_string_in_array_with_leading_space", "[ \"asd\"]"),
+    ("y_string_last_surrogates_1_and_2", "[\"\\uDBFF\\uDFFF\"]"),
+    ("y_string_nbsp_uescaped", "[\"new\\u00A0line\"]"),
+    ("y_string_nonCharacterInUTF-8_U+10FFFF", "[\"\u{10ffff}\"]"),
+    ("y_string_null_escape", "[\"\\u0000\"]"),
+    ("y_string_one-byte-utf-8", "[\"\\u002c\"]"),
+    ("y_string_pi", "[\"\u{3c0}\"]"),
+    ("y_string_simple_ascii", "[\"asd \"]"),
+    ("y_string_space", "\" \""),
+    (
+        "y_string_surrogates_U+1D11E_MUSICAL_SYMBOL_G_CLEF",
+        "[\"\\uD834\\uDd1e\"]",
+    ),
+    ("y_string_u+2028_line_sep", "[\"\u{2028}\"]"),
+    ("y_string_u+2029_par_sep", "[\"\u{2029}\"]"),
+    ("y_string_uEscape", "[\"\\u0061\\u30af\\u30EA\\u30b9\"]"),
+    ("y_string_uescaped_newline", "[\"new\\u000Aline\"]"),
+    ("y_string_unescaped_char_delete", "[\"\u{7f}\"]"),
+    ("y_string_unicode", "[\"\\uA66D\"]"),
+    ("y_string_unicode_U+FFFE_nonchar", "[\"\\uFFFE\"]"),
+    ("y_string_utf8", "[\"\u{20ac}\u{1d11e}\"]"),
+    ("y_string_with_del_character", "[\"a\u{7f}a\"]"),
+    ("y_structure_lonely_false", "false"),
+    ("y_structure_lonely_int", "42"),
+    ("y_structure_lonely_negative_real", "-0.1"),
+    ("y_structure_lonely_null", "null"),
+    ("y_structure_lonely_string", "\"asd\""),
+    ("y_structure_lonely_true", "true"),
+    ("y_structure_string_empty", "\"\""),
+    ("y_structure_trailing_newline", "[\"a\"]\n"),
+    ("y_structure_true_in_array", "[true]"),
+    ("y_structure_whitespace_array", " [] "),
+];
+
+const REJECTED: &[(&str, &str)] = &[
+    ("n_array_1_true_without_comma", "[1 true]"),
+    ("n_array_colon_instead_of_comma", "[\"\": 1]"),
+    ("n_array_comma_after_close", "[\"\"],"),
+    ("n_array_comma_and_number", "[,1]"),
+    ("n_array_double_comma", "[1,,2]"),
+    ("n_array_double_extra_comma", "[\"x\",,]"),
+    ("n_array_extra_close", "[\"x\"]]"),
+    ("n_array_extra_comma", "[\"\",]"),
+    ("n_array_incomplete", "[\"x\""),
+    ("n_array_incomplete_invalid_value", "[x"),
+    ("n_array_inner_array_no_comma", "[3[4]]"),
+    ("n_array_items_separated_by_semicolon", "[1:2]"),
+    ("n_array_just_comma", "[,]"),
+    ("n_array_just_minus", "[-]"),
+    ("n_array_missing_value", "[   , \"\"]"),
+    ("n_array_number_and_comma", "[1,]"),
+    ("n_array_star_inside", "[*]"),
+    ("n_array_unclosed", "[\"\""),
+    ("n_array_unclosed_trailing_comma", "[1,"),
+    ("n_incomplete_false", "[fals]"),
+    ("n_incomplete_null", "[nul]"),
+    ("n_incomplete_true", "[tru]"),
+    ("n_number_++", "[++1234]"),
+    ("n_number_+1", "[+1]"),
+    ("n_number_-01", "[-01]"),
+    ("n_number_-1.0.", "[-1.0.]"),
+    ("n_number_-2.", "[-2.]"),
+    ("n_number_-NaN", "[-NaN]"),
+    ("n_number_.-1", "[.-1]"),
+    ("n_number_.2e-3", "[.2e-3]"),
+    ("n_number_0.1.2", "[0.1.2]"),
+    ("n_number_0.3e+", "[0.3e+]"),
+    ("n_number_0.3e", "[0.3e]"),
+    ("n_number_0.e1", "[0.e1]"),
+    ("n_number_0_capital_E+", "[0E+]"),
+    ("n_number_0e", "[0e]"),
+    ("n_number_1.0e+", "[1.0e+]"),
+    ("n_number_1eE2", "[1eE2]"),
+    ("n_number_2.e3", "[2.e3]"),
+    ("n_number_9.e+", "[9.e+]"),
+    ("n_number_expression", "[1+2]"),
+    ("n_number_hex_1_digit", "[0x1]"),
+    ("n_number_Inf", "[Inf]"),
+    ("n_number_infinity", "[Infinity]"),
+    ("n_number_minus_infinity", "[-Infinity]"),
+    ("n_number_NaN", "[NaN]"),
+    ("n_number_neg_int_starting_with_zero", "[-012]"),
+    ("n_number_neg_real_without_int_part", "[-.123]"),
+    ("n_number_real_without_fractional_part", "[1.]"),
+    ("n_number_starting_with_dot", "[.123]"),
+    ("n_number_with_leading_zero", "[012]"),
+    ("n_object_bad_value", "[\"a\", truth]"),
+    ("n_object_comma_instead_of_colon", "{\"x\", null}"),
+    ("n_object_double_colon", "{\"x\"::\"b\"}"),
+    ("n_object_missing_colon", "{\"a\" b}"),
+    ("n_object_missing_key", "{:\"b\"}"),
+    ("n_object_missing_semicolon", "{\"a\" \"b\"}"),
+    ("n_object_missing_value", "{\"a\":"),
+    ("n_object_no-colon", "{\"a\""),
+    ("n_object_non_string_key", "{1:1}"),
+    ("n_object_single_quote", "{'a':0}"),
+    ("n_object_trailing_comma", "{\"id\":0,}"),
+    ("n_object_trailing_comment", "{\"a\":\"b\"}/**/"),
+    ("n_object_unquoted_key", "{a: \"b\"}"),
+    ("n_object_with_trailing_garbage", "{\"a\": true} \"x\""),
+    ("n_string_1_surrogate_then_escape", "[\"\\uD800\\\"]"),
+    ("n_string_1_surrogate_then_escape_u", "[\"\\uD800\\u\"]"),
+    ("n_string_1_surrogate_then_escape_u1", "[\"\\uD800\\u1\"]"),
+    ("n_string_1_surrogate_then_escape_u1x", "[\"\\uD800\\u1x\"]"),
+    ("n_string_escape_x", "[\"\\x00\"]"),
+    ("n_string_escaped_backslash_bad", "[\"\\\\\\\"]"),
+    ("n_string_escaped_ctrl_char_tab", "[\"\\\t\"]"),
+    ("n_string_escaped_emoji", "[\"\\\u{1f300}\"]"),
+    ("n_string_incomplete_escape", "[\"\\\"]"),
+    ("n_string_incomplete_escaped_character", "[\"\\u00A\"]"),
+    ("n_string_incomplete_surrogate", "[\"\\uD834\\uDd\"]"),
+    (
+        "n_string_incomplete_surrogate_escape_invalid",
+        "[\"\\uD800\\uD800\\x\"]",
+    ),
+    ("n_string_invalid_backslash_esc", "[\"\\a\"]"),
+    ("n_string_invalid_unicode_escape", "[\"\\uqqqq\"]"),
+    ("n_string_leading_uescaped_thinspace", "[\\u0020\"asd\"]"),
+    ("n_string_no_quotes_with_bad_escape", "[\\n]"),
+    ("n_string_single_doublequote", "\""),
+    ("n_string_single_quote", "['single quote']"),
+    ("n_string_start_escape_unclosed", "[\"\\"),
+    ("n_string_unescaped_ctrl_char", "[\"a\u{0}a\"]"),
+    ("n_string_unescaped_newline", "[\"new\nline\"]"),
+    ("n_string_unescaped_tab", "[\"\t\"]"),
+    ("n_string_unicode_CapitalU", "\"\\UA66D\""),
+    ("n_structure_array_trailing_garbage", "[1]x"),
+    ("n_structure_array_with_extra_array_close", "[1]]"),
+    ("n_structure_close_unopened_array", "1]"),
+    (
+        "n_structure_comma_instead_of_closing_brace",
+        "{\"x\": true,",
+    ),
+    ("n_structure_double_array", "[][]"),
+    ("n_structure_end_array", "]"),
+    ("n_structure_lone-open-bracket", "["),
+    ("n_structure_no_data", ""),
+    ("n_structure_null-byte-outside-string", "[\u{0}]"),
+    ("n_structure_object_unclosed_no_value", "{\"\":"),
+    (
+        "n_structure_object_with_comment",
+        "{\"a\":/*comment*/\"b\"}",
+    ),
+    ("n_structure_single_star", "*"),
+    ("n_structure_trailing_#", "{\"a\":\"b\"}#{}"),
+    ("n_structure_unclosed_array", "[1"),
+    ("n_structure_unclosed_object", "{\"asd\":\"asd\""),
+    ("n_structure_whitespace_formfeed", "[\u{c}]"),
+    ("n_structure_whitespace_U+2060_word_joiner", "[\u{2060}]"),
+];
+
+// Not valid UTF-8, so these can only be fed to the Reader.
+const REJECTED_BYTES: &[(&str, &[u8])] = &[
+    ("n_string_invalid_utf8_after_escape", b"[\"\\\xe5\"]"),
+    ("n_string_invalid-utf-8-in-escape", b"[\"\\u\xe5\"]"),
+    ("n_structure_lone-invalid-utf-8", b"\xe5"),
+    ("i_string_invalid_utf-8", b"[\"\xff\"]"),
+    ("i_string_lone_utf8_continuation_byte", b"[\"\x81\"]"),
+    ("i_string_overlong_sequence_2_bytes", b"[\"\xc0\xaf\"]"),
+    ("i_string_UTF8_surrogate_U+D800", b"[\"\xed\xa0\x80\"]"),
+];
+
+// Escapes of UTF-16 surrogates that do not form a pair. Strings hold Unicode scalar values, so
+// they are rejected.
+const LONE_SURROGATES: &[(&str, &str, u16)] = &[
+    (
+        "i_string_1st_surrogate_but_2nd_missing",
+        "[\"\\uDADA\"]",
+        0xdada,
+    ),
+    (
+        "i_string_1st_valid_surrogate_2nd_invalid",
+        "[\"\\uD888\\u1234\"]",
+        0xd888,
+    ),
+    (
+        "i_string_incomplete_surrogate_and_escape_valid",
+        "[\"\\uD800\\n\"]",
+        0xd800,
+    ),
+    (
+        "i_string_incomplete_surrogate_pair",
+        "[\"\\uDd1ea\"]",
+        0xdd1e,
+    ),
+    (
+        "i_string_incomplete_surrogates_escape_valid",
+        "[\"\\uD800\\uD800\\n\"]",
+        0xd800,
+    ),
+    ("i_string_invalid_lonely_surrogate", "[\"\\ud800\"]", 0xd800),
+    ("i_string_invalid_surrogate", "[\"\\ud800abc\"]", 0xd800),
+    (
+        "i_string_inverted_surrogates_U+1D11E",
+        "[\"\\uDd1e\\uD834\"]",
+        0xdd1e,
+    ),
+    ("i_string_lone_second_surrogate", "[\"\\uDFAA\"]", 0xdfaa),
+];
+
+fn read(input: &[u8]) -> Result<Option<Value>, String> {
+    let mut reader = Reader::new(input);
+    let value = reader.read_value().map_err(|error| error.to_string())?;
+    match reader.next_event() {
+        Ok(None) => Ok(value),
+        Ok(Some(_)) => Err("trailing data".to_string()),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+#[test]
+fn accepts_valid_documents() {
+    for (name, input) in ACCEPTED {
+        if let Err(error) = json::parse(input) {
+            panic!("{}: {}", name, error);
+        }
+        if let Err(error) = read(input.as_bytes()) {
+            panic!("{} (reader): {}", name, error);
+        }
+    }
+}
+
+#[test]
+fn rejects_invalid_documents() {
+    for (name, input) in REJECTED {
+        assert!(json::parse(input).is_err(), "{} was accepted", name);
+        assert!(
+            !matches!(read(input.as_bytes()), Ok(Some(_))),
+            "{} was accepted by the reader",
+            name
+        );
+    }
+}
+
+#[test]
+fn rejects_invalid_utf8() {
+    for (name, input) in REJECTED_BYTES {
+        assert!(read(input).is_err(), "{} was accepted", name);
+    }
+}
+
+#[test]
+fn rejects_lone_surrogates() {
+    for (name, input, code_unit) in LONE_SURROGATES {
+        let error = json::parse(input).expect_err(name);
+        match error.kind() {
+            ParseErrorKind::LoneSurrogate(actual) => assert_eq!(actual, code_unit, "{}", name),
+            kind => panic!("{}: expected a lone surrogate error, got {}", name, kind),
+        }
+        assert!(read(input.as_bytes()).is_err(), "{} (reader)", name);
+    }
+}
+
+#[test]
+fn unescapes_strings() {
+    let cases = [
+        ("\"\\\"\"", "\""),
+        ("\"\\\\\"", "\\"),
+        ("\"\\/\"", "/"),
+        ("\"\\b\"", "\u{8}"),
+        ("\"\\f\"", "\u{c}"),
+        ("\"\\n\"", "\n"),
+        ("\"\\r\"", "\r"),
+        ("\"\\t\"", "\t"),
+        ("\"\\u0000\"", "\u{0}"),
+        ("\"\\u00e9\"", "\u{e9}"),
+        ("\"\\u00E9\"", "\u{e9}"),
+        ("\"\\uFFFF\"", "\u{ffff}"),
+        ("\"\\uD834\\uDD1E\"", "\u{1d11e}"),
+        ("\"\\ud83d\\ude39\"", "\u{1f639}"),
+        ("\"\\uDBFF\\uDFFF\"", "\u{10ffff}"),
+        ("\"a\\/b\\\\c\\\"d\"", "a/b\\c\"d"),
+        ("\"\\\\u0000\"", "\\u0000"),
+    ];
+    for (input, expected) in cases {
+        let value = json::parse(input).unwrap_or_else(|error| panic!("{}: {}", input, error));
+        assert_eq!(value.as_str(), Some(expected), "{}", input);
+        let read = read(input.as_bytes()).unwrap().unwrap();
+        assert_eq!(read.as_str(), Some(expected), "{} (reader)", input);
+    }
+}
+
+#[test]
+fn rejects_excessive_nesting() {
+    let input = "[".repeat(100_000);
+    let error = json::parse(&input).unwrap_err();
+    assert!(matches!(
+        error.kind(),
+        ParseErrorKind::DepthLimitExceeded(json::DEFAULT_MAX_DEPTH)
+    ));
+    let nested = format!(
+        "{}{}",
+        "[".repeat(json::DEFAULT_MAX_DEPTH),
+        "]".repeat(json::DEFAULT_MAX_DEPTH)
+    );
+    assert!(json::parse(&nested).is_ok());
+}
+
+#[test]
+fn rejects_numbers_out_of_range() {
+    let cases = [
+        "[1.5e+9999]",
+        "[-1e+9999]",
+        "[-123123e100000]",
+        "[123.456e-789]",
+        "[123e-10000000]",
+        "[-1e-400]",
+    ];
+    for input in cases {
+        let error = json::parse(input).unwrap_err();
+        assert!(
+            matches!(error.kind(), ParseErrorKind::NumberOutOfRange),
+            "{}",
+            input
+        );
+        assert!(read(input.as_bytes()).is_err(), "{} (reader)", input);
+        let options = json::ParseOptions::default().with_raw_numbers();
+        let value = json::parse_with_options(input, &options).unwrap();
+        assert_eq!(value.to_string(), input, "{} (raw)", input);
+    }
+    // Zero is zero whatever its exponent, and subnormals are in range.
+    assert_eq!(json::parse("[0e-400]").unwrap().to_string(), "[0.0]");
+    assert_eq!(json::parse("[-0.0e-400]").unwrap().to_string(), "[-0.0]");
+    assert_eq!(json::parse("[5e-324]").unwrap().to_string(), "[5e-324]");
+}
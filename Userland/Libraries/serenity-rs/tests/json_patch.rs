@@ -0,0 +1,446 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+use serenity::json::patch::{self, Operation, Patch, PatchErrorKind};
+use serenity::json::{Number, ParseOptions, Value};
+use serenity::{assert_json_eq, json};
+
+fn parse(string: &str) -> Value {
+    json::parse_with_options(string, &ParseOptions::default().with_raw_numbers()).unwrap()
+}
+
+fn apply(document: &str, patch: &str) -> Result<Value, patch::PatchError> {
+    let mut document = parse(document);
+    patch::apply(&mut document, &Patch::from_value(&parse(patch)).unwrap())?;
+    Ok(document)
+}
+
+// The examples of RFC 6902, appendix A, that have a result.
+#[test]
+fn applies_the_rfc_examples() {
+    let cases = [
+        // A.1. Adding an Object Member
+        (
+            r#"{"foo": "bar"}"#,
+            r#"[{"op": "add", "path": "/baz", "value": "qux"}]"#,
+            r#"{"baz": "qux", "foo": "bar"}"#,
+        ),
+        // A.2. Adding an Array Element
+        (
+            r#"{"foo": ["bar", "baz"]}"#,
+            r#"[{"op": "add", "path": "/foo/1", "value": "qux"}]"#,
+            r#"{"foo": ["bar", "qux", "baz"]}"#,
+        ),
+        // A.3. Removing an Object Member
+        (
+            r#"{"baz": "qux", "foo": "bar"}"#,
+            r#"[{"op": "remove", "path": "/baz"}]"#,
+            r#"{"foo": "bar"}"#,
+        ),
+        // A.4. Removing an Array Element
+        (
+            r#"{"foo": ["bar", "qux", "baz"]}"#,
+            r#"[{"op": "remove", "path": "/foo/1"}]"#,
+            r#"{"foo": ["bar", "baz"]}"#,
+        ),
+        // A.5. Replacing a Value
+        (
+            r#"{"baz": "qux", "foo": "bar"}"#,
+            r#"[{"op": "replace", "path": "/baz", "value": "boo"}]"#,
+            r#"{"baz": "boo", "foo": "bar"}"#,
+        ),
+        // A.6. Moving a Value
+        (
+            r#"{"foo": {"bar": "baz", "waldo": "fred"}, "qux": {"corge": "grault"}}"#,
+            r#"[{"op": "move", "from": "/foo/waldo", "path": "/qux/thud"}]"#,
+            r#"{"foo": {"bar": "baz"}, "qux": {"corge": "grault", "thud": "fred"}}"#,
+        ),
+        // A.7. Moving an Array Element
+        (
+            r#"{"foo": ["all", "grass", "cows", "eat"]}"#,
+            r#"[{"op": "move", "from": "/foo/1", "path": "/foo/3"}]"#,
+            r#"{"foo": ["all", "cows", "eat", "grass"]}"#,
+        ),
+        // A.8. Testing a Value: Success
+        (
+            r#"{"baz": "qux", "foo": ["a", 2, "c"]}"#,
+            r#"[{"op": "test", "path": "/baz", "value": "qux"},
+                {"op": "test", "path": "/foo/1", "value": 2}]"#,
+            r#"{"baz": "qux", "foo": ["a", 2, "c"]}"#,
+        ),
+        // A.10. Adding a Nested Member Object
+        (
+            r#"{"foo": "bar"}"#,
+            r#"[{"op": "add", "path": "/child", "value": {"grandchild": {}}}]"#,
+            r#"{"foo": "bar", "child": {"grandchild": {}}}"#,
+        ),
+        // A.11. Ignoring Unrecognized Elements
+        (
+            r#"{"foo": "bar"}"#,
+            r#"[{"op": "add", "path": "/baz", "value": "qux", "xyz": 123}]"#,
+            r#"{"foo": "bar", "baz": "qux"}"#,
+        ),
+        // A.14. ~ Escape Ordering
+        (
+            r#"{"/": 9, "~1": 10}"#,
+            r#"[{"op": "test", "path": "/~01", "value": 10}]"#,
+            r#"{"/": 9, "~1": 10}"#,
+        ),
+        // A.16. Adding an Array Value
+        (
+            r#"{"foo": ["bar"]}"#,
+            r#"[{"op": "add", "path": "/foo/-", "value": ["abc", "def"]}]"#,
+            r#"{"foo": ["bar", ["abc", "def"]]}"#,
+        ),
+        // Copying, and the whole document as the target.
+        (
+            r#"{"a": {"b": [1]}}"#,
+            r#"[{"op": "copy", "from": "/a", "path": "/c"},
+                {"op": "add", "path": "/c/b/0", "value": 0}]"#,
+            r#"{"a": {"b": [1]}, "c": {"b": [0, 1]}}"#,
+        ),
+        (
+            r#"{"a": 1}"#,
+            r#"[{"op": "replace", "path": "", "value": [1]}, {"op": "add", "path": "/1", "value": 2}]"#,
+            "[1, 2]",
+        ),
+        (
+            r#"{"a": 1}"#,
+            r#"[{"op": "move", "from": "/a", "path": "/a"}]"#,
+            r#"{"a": 1}"#,
+        ),
+    ];
+    for (document, patch, expected) in cases {
+        let patched = apply(document, patch).unwrap_or_else(|error| panic!("{}: {}", patch, error));
+        assert_json_eq!(patched, parse(expected));
+    }
+}
+
+#[test]
+fn reports_failing_operations() {
+    let cases = [
+        // A.9. Testing a Value: Error
+        (
+            r#"{"baz": "qux"}"#,
+            r#"[{"op": "test", "path": "/baz", "value": "bar"}]"#,
+            "operation 0: test failed at \"/baz\"",
+        ),
+        // A.12. Adding to a Nonexistent Target
+        (
+            r#"{"foo": "bar"}"#,
+            r#"[{"op": "add", "path": "/baz/bat", "value": "qux"}]"#,
+            "operation 0: path \"/baz/bat\" does not exist",
+        ),
+        // A.15. Comparing Strings and Numbers
+        (
+            r#"{"/": 9, "~1": 10}"#,
+            r#"[{"op": "test", "path": "/~01", "value": "10"}]"#,
+            "operation 0: test failed at \"/~01\"",
+        ),
+        (
+            "[1, 2]",
+            r#"[{"op": "add", "path": "/3", "value": 0}]"#,
+            "operation 0: path \"/3\" does not exist",
+        ),
+        (
+            "[1, 2]",
+            r#"[{"op": "add", "path": "/01", "value": 0}]"#,
+            "operation 0: path \"/01\" does not exist",
+        ),
+        (
+            "[1, 2]",
+            r#"[{"op": "remove", "path": "/-"}]"#,
+            "operation 0: path \"/-\" does not exist",
+        ),
+        (
+            "{}",
+            r#"[{"op": "remove", "path": ""}]"#,
+            "operation 0: cannot remove the whole document",
+        ),
+        (
+            r#"{"a": {"b": 1}}"#,
+            r#"[{"op": "move", "from": "/a", "path": "/a/b/c"}]"#,
+            "operation 0: cannot move a value into itself at \"/a/b/c\"",
+        ),
+        (
+            "{}",
+            r#"[{"op": "replace", "path": "a", "value": 1}]"#,
+            "operation 0: \"a\" is not a valid JSON Pointer",
+        ),
+        (
+            r#"{"a": 1}"#,
+            r#"[{"op": "copy", "from": "/b", "path": "/c"}]"#,
+            "operation 0: path \"/b\" does not exist",
+        ),
+    ];
+    for (document, patch, message) in cases {
+        let error = apply(document, patch)
+            .err()
+            .unwrap_or_else(|| panic!("{} applied", patch));
+        assert_eq!(error.to_string(), message);
+    }
+}
+
+#[test]
+fn rejects_invalid_patch_documents() {
+    let cases = [
+        (
+            r#"{"op": "add"}"#,
+            None,
+            "a patch must be an array of operations",
+        ),
+        ("[1]", Some(0), "an operation must be an object"),
+        (
+            r#"[{"op": "remove", "path": "/a"}, {"op": "frob", "path": ""}]"#,
+            Some(1),
+            "unknown operation \"frob\"",
+        ),
+        (r#"[{"path": "/a"}]"#, Some(0), "missing \"op\""),
+        (r#"[{"op": "add"}]"#, Some(0), "missing \"path\""),
+        (
+            r#"[{"op": "add", "path": "/a"}]"#,
+            Some(0),
+            "missing \"value\"",
+        ),
+        (
+            r#"[{"op": "move", "path": "/a"}]"#,
+            Some(0),
+            "missing \"from\"",
+        ),
+        (
+            r#"[{"op": "copy", "path": "/a", "from": 1}]"#,
+            Some(0),
+            "\"from\" must be a string",
+        ),
+        (
+            r#"[{"op": 1, "path": "/a"}]"#,
+            Some(0),
+            "\"op\" must be a string",
+        ),
+    ];
+    for (patch, operation, message) in cases {
+        let error = Patch::from_value(&parse(patch)).unwrap_err();
+        assert_eq!(error.operation(), operation, "{}", patch);
+        assert_eq!(error.kind().to_string(), message, "{}", patch);
+    }
+    // A null value is still a value.
+    let patch =
+        Patch::from_value(&parse(r#"[{"op": "add", "path": "/a", "value": null}]"#)).unwrap();
+    assert_eq!(patch.operations(), [Operation::Add {
+        path: "/a".to_string(),
+        value: Value::Null,
+    }]);
+}
+
+#[test]
+fn operations_round_trip_through_json() {
+    let patch = Patch::from(vec![
+        Operation::Add {
+            path: "/a".to_string(),
+            value: json!([1, {"b": null}]),
+        },
+        Operation::Remove {
+            path: "/b~1c".to_string(),
+        },
+        Operation::Replace {
+            path: "".to_string(),
+            value: json!("x"),
+        },
+        Operation::Move {
+            from: "/d".to_string(),
+            path: "/e".to_string(),
+        },
+        Operation::Copy {
+            from: "/f".to_string(),
+            path: "/g/-".to_string(),
+        },
+        Operation::Test {
+            path: "/h".to_string(),
+            value: json!(1.5),
+        },
+    ]);
+    let value = patch.to_value();
+    assert_eq!(
+        value.to_string(),
+        concat!(
+            r#"[{"op":"add","path":"/a","value":[1,{"b":null}]},"#,
+            r#"{"op":"remove","path":"/b~1c"},"#,
+            r#"{"op":"replace","path":"","value":"x"},"#,
+            r#"{"op":"move","path":"/e","from":"/d"},"#,
+            r#"{"op":"copy","path":"/g/-","from":"/f"},"#,
+            r#"{"op":"test","path":"/h","value":1.5}]"#
+        )
+    );
+    assert_eq!(Patch::from_value(&value).unwrap(), patch);
+}
+
+#[test]
+fn failed_patches_leave_the_document_unchanged() {
+    let original = parse(r#"{"a": [1, 2, 3], "b": {"c": "d"}}"#);
+    // Every kind of operation succeeds before the last one fails.
+    let patch = Patch::from_value(&parse(
+        r#"[
+            {"op": "add", "path": "/a/-", "value": 4},
+            {"op": "remove", "path": "/a/0"},
+            {"op": "replace", "path": "/b/c", "value": "e"},
+            {"op": "move", "from": "/b", "path": "/f"},
+            {"op": "copy", "from": "/f", "path": "/g"},
+            {"op": "test", "path": "/a", "value": [2, 3, 4]},
+            {"op": "test", "path": "/g/c", "value": "d"}
+        ]"#,
+    ))
+    .unwrap();
+    let mut document = original.clone();
+    let error = patch::apply(&mut document, &patch).unwrap_err();
+    assert_eq!(error.operation(), Some(6));
+    assert!(matches!(error.kind(), PatchErrorKind::TestFailed(path) if path == "/g/c"));
+    assert_json_eq!(document, original);
+    assert_eq!(document.to_string(), original.to_string());
+
+    // Without the failing test, all of it applies.
+    let patch = Patch::from(patch.operations()[..6].to_vec());
+    patch::apply(&mut document, &patch).unwrap();
+    assert_json_eq!(
+        document,
+        parse(r#"{"a": [2, 3, 4], "f": {"c": "e"}, "g": {"c": "e"}}"#)
+    );
+}
+
+fn assert_diff_round_trips(from: &Value, to: &Value) -> Patch {
+    let patch = patch::diff(from, to);
+    let mut patched = from.clone();
+    patch::apply(&mut patched, &patch)
+        .unwrap_or_else(|error| panic!("{} from {} to {}: {}", patch.to_value(), from, to, error));
+    assert_json_eq!(patched, to.clone());
+    // The patch survives being sent as JSON.
+    assert_eq!(Patch::from_value(&patch.to_value()).unwrap(), patch);
+    patch
+}
+
+#[test]
+fn diffs_apply_to_give_the_target() {
+    let cases = [
+        ("{}", "{}", "[]"),
+        ("1", "1.0", "[]"),
+        (r#"{"a": 1, "b": 2}"#, r#"{"b": 2, "a": 1}"#, "[]"),
+        ("1", "2", r#"[{"op":"replace","path":"","value":2}]"#),
+        (
+            r#"{"a": 1}"#,
+            r#"{"a": 1, "b": 2}"#,
+            r#"[{"op":"add","path":"/b","value":2}]"#,
+        ),
+        (
+            r#"{"a": 1, "b~/": 2}"#,
+            r#"{"a": 1}"#,
+            r#"[{"op":"remove","path":"/b~0~1"}]"#,
+        ),
+        (
+            r#"{"a": {"b": [1, 2]}}"#,
+            r#"{"a": {"b": [1, 3]}}"#,
+            r#"[{"op":"replace","path":"/a/b/1","value":3}]"#,
+        ),
+        ("[1, 2, 3]", "[1, 3]", r#"[{"op":"remove","path":"/1"}]"#),
+        (
+            "[1, 3]",
+            "[1, 2, 3]",
+            r#"[{"op":"add","path":"/1","value":2}]"#,
+        ),
+        (
+            "[1, 2, 3, 4]",
+            "[0, 1, 2, 4, 5]",
+            r#"[{"op":"replace","path":"/3","value":5},{"op":"replace","path":"/2","value":4},{"op":"add","path":"/0","value":0}]"#,
+        ),
+        ("[]", "[[]]", r#"[{"op":"add","path":"/0","value":[]}]"#),
+        (
+            r#"{"a": []}"#,
+            r#"{"a": {}}"#,
+            r#"[{"op":"replace","path":"/a","value":{}}]"#,
+        ),
+        // Raw numbers that round to the same float are still different numbers.
+        (
+            "[1e400]",
+            "[2e400]",
+            r#"[{"op":"replace","path":"/0","value":2e400}]"#,
+        ),
+        (
+            "12345678901234567890123",
+            "12345678901234567890124",
+            r#"[{"op":"replace","path":"","value":12345678901234567890124}]"#,
+        ),
+        ("[1e400]", "[10e399]", "[]"),
+    ];
+    for (from, to, expected) in cases {
+        let patch = assert_diff_round_trips(&parse(from), &parse(to));
+        assert_eq!(patch.to_value().to_string(), expected, "{} to {}", from, to);
+    }
+}
+
+#[test]
+fn diffs_of_a_changing_process_list_round_trip() {
+    let all = parse(include_str!("fixtures/proc/all"));
+    let mut next = all.clone();
+    let processes = next.get_mut("processes").unwrap().as_array_mut().unwrap();
+    // A process exits, another starts, and the rest keep running.
+    let exited = processes.remove(5);
+    let mut started = exited.as_object().unwrap().clone();
+    started.insert("pid".to_string(), json!(99));
+    started.insert("name".to_string(), json!("Calculator"));
+    processes.push(Value::Object(started));
+    for process in processes.iter_mut() {
+        let threads = process.get_mut("threads").unwrap().as_array_mut().unwrap();
+        for thread in threads {
+            let scheduled = thread.get_mut("times_scheduled").unwrap();
+            *scheduled = json!(scheduled.as_u64().unwrap() + 3);
+        }
+    }
+    processes[2].as_object_mut().unwrap().remove("tty");
+    next.as_object_mut().unwrap().insert(
+        "total_time".to_string(),
+        Value::Number(Number::Raw("123456789012345678901234567890".to_string())),
+    );
+
+    assert_diff_round_trips(&all, &next);
+    assert_diff_round_trips(&next, &all);
+    assert_diff_round_trips(&all, &json!({"processes": []}));
+    assert_diff_round_trips(&Value::Null, &all);
+}
+
+// The examples of RFC 7386, appendix A.
+#[test]
+fn merges_the_rfc_examples() {
+    let cases = [
+        (r#"{"a":"b"}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+        (r#"{"a":"b"}"#, r#"{"b":"c"}"#, r#"{"a":"b","b":"c"}"#),
+        (r#"{"a":"b"}"#, r#"{"a":null}"#, "{}"),
+        (r#"{"a":"b","b":"c"}"#, r#"{"a":null}"#, r#"{"b":"c"}"#),
+        (r#"{"a":["b"]}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#),
+        (r#"{"a":"c"}"#, r#"{"a":["b"]}"#, r#"{"a":["b"]}"#),
+        (
+            r#"{"a":{"b":"c"}}"#,
+            r#"{"a":{"b":"d","c":null}}"#,
+            r#"{"a":{"b":"d"}}"#,
+        ),
+        (r#"{"a":[{"b":"c"}]}"#, r#"{"a":[1]}"#, r#"{"a":[1]}"#),
+        (r#"["a","b"]"#, r#"["c","d"]"#, r#"["c","d"]"#),
+        (r#"{"a":"b"}"#, r#"["c"]"#, r#"["c"]"#),
+        (r#"{"a":"foo"}"#, "null", "null"),
+        (r#"{"a":"foo"}"#, r#""bar""#, r#""bar""#),
+        (r#"{"e":null}"#, r#"{"a":1}"#, r#"{"e":null,"a":1}"#),
+        (r#"[1,2]"#, r#"{"a":"b","c":null}"#, r#"{"a":"b"}"#),
+        ("{}", r#"{"a":{"bb":{"ccc":null}}}"#, r#"{"a":{"bb":{}}}"#),
+    ];
+    for (document, patch, expected) in cases {
+        let mut merged = parse(document);
+        patch::merge(&mut merged, &parse(patch));
+        assert_eq!(
+            merged.to_string(),
+            expected,
+            "{} merged with {}",
+            document,
+            patch
+        );
+    }
+}
@@ -0,0 +1,335 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// The examples from RFC 9535, with the results it gives for them. Examples that call function
+// extensions like length() or match() are left out, as those are not supported.
+
+use serenity::json::query::{self, QueryErrorKind};
+use serenity::json::{self, Value};
+
+const BOOKSTORE: &str = r#"{
+    "store": {
+        "book": [
+            {"category": "reference", "author": "Nigel Rees", "title": "Sayings of the Century", "price": 8.95},
+            {"category": "fiction", "author": "Evelyn Waugh", "title": "Sword of Honour", "price": 12.99},
+            {"category": "fiction", "author": "Herman Melville", "title": "Moby Dick", "isbn": "0-553-21311-3", "price": 8.99},
+            {"category": "fiction", "author": "J. R. R. Tolkien", "title": "The Lord of the Rings", "isbn": "0-395-19395-8", "price": 22.99}
+        ],
+        "bicycle": {"color": "red", "price": 399}
+    }
+}"#;
+
+// Each query with the JSON array of the values it has to select, in order.
+fn assert_selects(document: &str, cases: &[(&str, &str)]) {
+    let document = json::parse(document).unwrap();
+    for (expression, expected) in cases {
+        let selected = query::select(&document, expression)
+            .unwrap_or_else(|error| panic!("{}: {}", expression, error));
+        let selected = Value::Array(selected.into_iter().cloned().collect());
+        assert_eq!(
+            selected.to_string(),
+            json::parse(expected).unwrap().to_string(),
+            "{}",
+            expression
+        );
+    }
+}
+
+#[test]
+fn bookstore_examples() {
+    // Section 1.5, table 3.
+    assert_selects(BOOKSTORE, &[
+        (
+            "$.store.book[*].author",
+            r#"["Nigel Rees", "Evelyn Waugh", "Herman Melville", "J. R. R. Tolkien"]"#,
+        ),
+        (
+            "$..author",
+            r#"["Nigel Rees", "Evelyn Waugh", "Herman Melville", "J. R. R. Tolkien"]"#,
+        ),
+        (
+            "$.store.*",
+            r#"[[{"category": "reference", "author": "Nigel Rees", "title": "Sayings of the Century", "price": 8.95},
+            {"category": "fiction", "author": "Evelyn Waugh", "title": "Sword of Honour", "price": 12.99},
+            {"category": "fiction", "author": "Herman Melville", "title": "Moby Dick", "isbn": "0-553-21311-3", "price": 8.99},
+            {"category": "fiction", "author": "J. R. R. Tolkien", "title": "The Lord of the Rings", "isbn": "0-395-19395-8", "price": 22.99}],
+            {"color": "red", "price": 399}]"#,
+        ),
+        // The RFC leaves the order of descendants open; here it is always document order.
+        ("$.store..price", "[8.95, 12.99, 8.99, 22.99, 399]"),
+        (
+            "$..book[2]",
+            r#"[{"category": "fiction", "author": "Herman Melville", "title": "Moby Dick", "isbn": "0-553-21311-3", "price": 8.99}]"#,
+        ),
+        ("$..book[2].author", r#"["Herman Melville"]"#),
+        ("$..book[2].publisher", "[]"),
+        (
+            "$..book[-1]",
+            r#"[{"category": "fiction", "author": "J. R. R. Tolkien", "title": "The Lord of the Rings", "isbn": "0-395-19395-8", "price": 22.99}]"#,
+        ),
+        (
+            "$..book[0,1].title",
+            r#"["Sayings of the Century", "Sword of Honour"]"#,
+        ),
+        (
+            "$..book[:2].title",
+            r#"["Sayings of the Century", "Sword of Honour"]"#,
+        ),
+        (
+            "$..book[?@.isbn].title",
+            r#"["Moby Dick", "The Lord of the Rings"]"#,
+        ),
+        (
+            "$..book[?@.price<10].title",
+            r#"["Sayings of the Century", "Moby Dick"]"#,
+        ),
+    ]);
+    let document = json::parse(BOOKSTORE).unwrap();
+    // 2 objects, 4 books with their 18 members, and the bicycle's 2.
+    assert_eq!(query::select(&document, "$..*").unwrap().len(), 27);
+}
+
+#[test]
+fn name_selector_examples() {
+    // Section 2.3.1.3.
+    assert_selects(r#"{"o": {"j j": {"k.k": 3}}, "'": {"@": 2}}"#, &[
+        ("$.o['j j']", r#"[{"k.k": 3}]"#),
+        ("$.o['j j']['k.k']", "[3]"),
+        (r#"$.o["j j"]["k.k"]"#, "[3]"),
+        (r#"$["'"]["@"]"#, "[2]"),
+    ]);
+}
+
+#[test]
+fn wildcard_selector_examples() {
+    // Section 2.3.2.3.
+    assert_selects(r#"{"o": {"j": 1, "k": 2}, "a": [5, 3]}"#, &[
+        ("$[*]", r#"[{"j": 1, "k": 2}, [5, 3]]"#),
+        ("$.o[*]", "[1, 2]"),
+        ("$.o[*, *]", "[1, 2, 1, 2]"),
+        ("$.a[*]", "[5, 3]"),
+    ]);
+}
+
+#[test]
+fn index_selector_examples() {
+    // Section 2.3.3.3.
+    assert_selects(r#"["a", "b"]"#, &[
+        ("$[1]", r#"["b"]"#),
+        ("$[-2]", r#"["a"]"#),
+        ("$[2]", "[]"),
+        ("$[-3]", "[]"),
+    ]);
+}
+
+#[test]
+fn array_slice_selector_examples() {
+    // Section 2.3.4.3.
+    assert_selects(r#"["a", "b", "c", "d", "e", "f", "g"]"#, &[
+        ("$[1:3]", r#"["b", "c"]"#),
+        ("$[5:]", r#"["f", "g"]"#),
+        ("$[1:5:2]", r#"["b", "d"]"#),
+        ("$[5:1:-2]", r#"["f", "d"]"#),
+        ("$[::-1]", r#"["g", "f", "e", "d", "c", "b", "a"]"#),
+        ("$[::0]", "[]"),
+        ("$[-100:100]", r#"["a", "b", "c", "d", "e", "f", "g"]"#),
+        ("$[-2:]", r#"["f", "g"]"#),
+    ]);
+}
+
+#[test]
+fn filter_selector_examples() {
+    // Section 2.3.5.3, table 12.
+    let document = r#"{
+        "a": [3, 5, 1, 2, 4, 6, {"b": "j"}, {"b": "k"}, {"b": {}}, {"b": "kilo"}],
+        "o": {"p": 1, "q": 2, "r": 3, "s": 5, "t": {"u": 6}},
+        "e": "f"
+    }"#;
+    assert_selects(document, &[
+        ("$.a[?@.b == 'kilo']", r#"[{"b": "kilo"}]"#),
+        ("$.a[?(@.b == 'kilo')]", r#"[{"b": "kilo"}]"#),
+        ("$.a[?@>3.5]", "[5, 4, 6]"),
+        (
+            "$.a[?@.b]",
+            r#"[{"b": "j"}, {"b": "k"}, {"b": {}}, {"b": "kilo"}]"#,
+        ),
+        (
+            "$[?@.*]",
+            r#"[[3, 5, 1, 2, 4, 6, {"b": "j"}, {"b": "k"}, {"b": {}}, {"b": "kilo"}],
+                {"p": 1, "q": 2, "r": 3, "s": 5, "t": {"u": 6}}]"#,
+        ),
+        (
+            "$[?@[?@.b]]",
+            r#"[[3, 5, 1, 2, 4, 6, {"b": "j"}, {"b": "k"}, {"b": {}}, {"b": "kilo"}]]"#,
+        ),
+        ("$.o[?@<3, ?@<3]", "[1, 2, 1, 2]"),
+        (r#"$.a[?@<2 || @.b == "k"]"#, r#"[1, {"b": "k"}]"#),
+        ("$.o[?@>1 && @<4]", "[2, 3]"),
+        ("$.o[?@.u || @.x]", r#"[{"u": 6}]"#),
+        ("$.a[?@.b == $.x]", "[3, 5, 1, 2, 4, 6]"),
+        (
+            "$.a[?@ == @]",
+            r#"[3, 5, 1, 2, 4, 6, {"b": "j"}, {"b": "k"}, {"b": {}}, {"b": "kilo"}]"#,
+        ),
+        ("$.a[?!@.b]", "[3, 5, 1, 2, 4, 6]"),
+    ]);
+}
+
+#[test]
+fn comparison_examples() {
+    // Section 2.3.5.2.2, table 11. $[?...] selects both members of the document when the
+    // comparison is true and nothing when it is false.
+    let document = json::parse(r#"{"obj": {"x": "y"}, "arr": [2, 3]}"#).unwrap();
+    let cases = [
+        ("$.absent1 == $.absent2", true),
+        ("$.absent1 <= $.absent2", true),
+        ("$.absent == 'g'", false),
+        ("$.absent1 != $.absent2", false),
+        ("$.absent != 'g'", true),
+        ("1 <= 2", true),
+        ("1 > 2", false),
+        ("13 == '13'", false),
+        ("'a' <= 'b'", true),
+        ("'a' > 'b'", false),
+        ("$.obj == $.arr", false),
+        ("$.obj != $.arr", true),
+        ("$.obj == $.obj", true),
+        ("$.obj != $.obj", false),
+        ("$.arr == $.arr", true),
+        ("$.arr != $.arr", false),
+        ("$.obj == 17", false),
+        ("$.obj != 17", true),
+        ("$.obj <= $.arr", false),
+        ("$.obj < $.arr", false),
+        ("$.obj <= $.obj", true),
+        ("$.arr <= $.arr", true),
+        ("1 <= $.arr", false),
+        ("1 >= $.arr", false),
+        ("1 > $.arr", false),
+        ("1 < $.arr", false),
+        ("true <= true", true),
+        ("true > true", false),
+    ];
+    for (comparison, expected) in cases {
+        let expression = format!("$[?{}]", comparison);
+        let selected = query::select(&document, &expression)
+            .unwrap_or_else(|error| panic!("{}: {}", expression, error));
+        assert_eq!(
+            selected.len(),
+            if expected { 2 } else { 0 },
+            "{}",
+            comparison
+        );
+    }
+}
+
+#[test]
+fn child_and_descendant_segment_examples() {
+    // Section 2.5.1.3.
+    assert_selects(r#"["a", "b", "c", "d", "e", "f", "g"]"#, &[
+        ("$[0, 3]", r#"["a", "d"]"#),
+        ("$[0:2, 5]", r#"["a", "b", "f"]"#),
+        ("$[0, 0]", r#"["a", "a"]"#),
+    ]);
+    // Section 2.5.2.3. Members come in document order, so where the RFC allows several orders
+    // there is only one here.
+    assert_selects(
+        r#"{"o": {"j": 1, "k": 2}, "a": [5, 3, [{"j": 4}, {"k": 6}]]}"#,
+        &[
+            ("$..j", "[1, 4]"),
+            ("$..[0]", r#"[5, {"j": 4}]"#),
+            (
+                "$..[*]",
+                r#"[{"j": 1, "k": 2}, [5, 3, [{"j": 4}, {"k": 6}]], 1, 2, 5, 3,
+                    [{"j": 4}, {"k": 6}], {"j": 4}, {"k": 6}, 4, 6]"#,
+            ),
+            (
+                "$..*",
+                r#"[{"j": 1, "k": 2}, [5, 3, [{"j": 4}, {"k": 6}]], 1, 2, 5, 3,
+                    [{"j": 4}, {"k": 6}], {"j": 4}, {"k": 6}, 4, 6]"#,
+            ),
+            ("$..o", r#"[{"j": 1, "k": 2}]"#),
+            ("$.o..[*, *]", "[1, 2, 1, 2]"),
+            ("$.a..[0, 1]", r#"[5, 3, {"j": 4}, {"k": 6}]"#),
+        ],
+    );
+}
+
+#[test]
+fn null_semantics_examples() {
+    // Section 2.6.1.
+    assert_selects(r#"{"a": null, "b": [null], "c": [{}], "null": 1}"#, &[
+        ("$.a", "[null]"),
+        ("$.a[0]", "[]"),
+        ("$.a.d", "[]"),
+        ("$.b[0]", "[null]"),
+        ("$.b[*]", "[null]"),
+        ("$.b[?@]", "[null]"),
+        ("$.b[?@==null]", "[null]"),
+        ("$.c[?@.d==null]", "[]"),
+        ("$.null", "[1]"),
+    ]);
+}
+
+#[test]
+fn returns_pointers_to_the_selected_values() {
+    let document = json::parse(r#"{"a": [{"b/c": 1}, {"~": 2}], "d": 3}"#).unwrap();
+    let query = query::compile("$..*[?@ > 0]").unwrap();
+    let pointers = query
+        .select_with_pointers(&document)
+        .into_iter()
+        .map(|(pointer, value)| format!("{}={}", pointer, value))
+        .collect::<Vec<_>>();
+    assert_eq!(pointers, ["/a/0/b~1c=1", "/a/1/~0=2"]);
+}
+
+#[test]
+fn reports_where_a_query_is_invalid() {
+    let cases = [
+        ("", 0, "a query must start with '$'"),
+        ("@.a", 0, "a query must start with '$'"),
+        ("$.", 2, "unexpected end of query"),
+        ("$[", 2, "unexpected end of query"),
+        ("$.a b", 3, "unexpected character ' '"),
+        ("$[1 2]", 4, "unexpected character '2'"),
+        ("$['a'", 5, "unexpected end of query"),
+        ("$['a", 4, "unexpected end of query"),
+        // A backslash at the very end must not report an offset past the query.
+        ("$['a\\", 5, "unexpected end of query"),
+        ("$['\\", 4, "unexpected end of query"),
+        ("$['\\q']", 2, "invalid literal"),
+        ("$[01]", 2, "invalid literal"),
+        ("$[9007199254740992]", 2, "index out of range"),
+        (
+            "$[?@.* == 1]",
+            3,
+            "only queries for a single value can be compared",
+        ),
+        (
+            "$[?@..a == 1]",
+            3,
+            "only queries for a single value can be compared",
+        ),
+    ];
+    for (expression, offset, message) in cases {
+        let error = query::compile(expression)
+            .err()
+            .unwrap_or_else(|| panic!("{} compiled", expression));
+        assert!(error.offset() <= expression.len(), "{}", expression);
+        assert_eq!(
+            (error.offset(), error.kind().to_string().as_str()),
+            (offset, message),
+            "{}",
+            expression
+        );
+    }
+
+    let nested = format!("$[?{}@{}]", "(".repeat(1000), ")".repeat(1000));
+    assert!(matches!(
+        query::compile(&nested).unwrap_err().kind(),
+        QueryErrorKind::TooDeeplyNested
+    ));
+}
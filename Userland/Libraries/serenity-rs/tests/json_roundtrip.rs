@@ -0,0 +1,224 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// parse(serialize(value)) has to give back the value it started with, in both the compact and the
+// pretty form. "The same" is stricter than ==: every number has to keep its representation and
+// every f64 its bits, and object members their order.
+
+use serenity::json::{self, Map, Number, Value};
+
+// xorshift64*, so every run checks the same values and a failure can be reproduced.
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545f4914f6cdd1d)
+    }
+
+    fn below(&mut self, bound: u64) -> u64 { self.next() % bound }
+}
+
+const INTERESTING_INTEGERS: &[i64] = &[0, 1, -1, i64::MIN, i64::MAX, i32::MIN as i64, 1 << 53];
+const INTERESTING_FLOATS: &[f64] = &[
+    0.0,
+    -0.0,
+    0.1,
+    -1.5,
+    1e21,
+    1e300,
+    -1e300,
+    1e-7,
+    2.5e-300,
+    5e-324,
+    f64::MAX,
+    f64::MIN,
+    f64::MIN_POSITIVE,
+    f64::EPSILON,
+    9007199254740993.0,
+];
+const INTERESTING_STRINGS: &[&str] = &[
+    "",
+    "plain",
+    "\"quoted\"",
+    "back\\slash",
+    "tab\tnewline\nreturn\r",
+    "\u{0}\u{1}\u{8}\u{c}\u{1f}\u{7f}",
+    "caf\u{e9}",
+    "\u{65e5}\u{672c}\u{8a9e}",
+    "\u{1f600} \u{1d11e}",
+    "\u{2028}\u{2029}",
+    "\u{ffff}\u{10ffff}",
+    "</script>",
+];
+
+fn random_number(random: &mut Random) -> Number {
+    match random.below(6) {
+        0 => Number::Integer64(random.next() as i64),
+        1 => Number::Integer64(INTERESTING_INTEGERS[random.below(7) as usize]),
+        2 => Number::UInt64(u64::MAX - random.below(1 << 20)),
+        3 => Number::UInt64(i64::MAX as u64 + 1),
+        4 => Number::Float64(INTERESTING_FLOATS[random.below(15) as usize]),
+        _ => loop {
+            // Any finite bit pattern, subnormals included.
+            let value = f64::from_bits(random.next());
+            if value.is_finite() {
+                break Number::Float64(value);
+            }
+        },
+    }
+}
+
+fn random_string(random: &mut Random) -> String {
+    if random.below(2) == 0 {
+        return INTERESTING_STRINGS[random.below(12) as usize].to_string();
+    }
+    let length = random.below(12);
+    (0..length)
+        .map(|_| loop {
+            let code_point = match random.below(4) {
+                0 => random.below(0x20),
+                1 => random.below(0x80),
+                2 => random.below(0x800),
+                _ => random.below(0x110000),
+            };
+            if let Some(ch) = char::from_u32(code_point as u32) {
+                break ch;
+            }
+        })
+        .collect()
+}
+
+fn random_value(random: &mut Random, depth: usize) -> Value {
+    let kinds = if depth == 0 { 4 } else { 6 };
+    match random.below(kinds) {
+        0 => Value::Null,
+        1 => Value::Bool(random.below(2) == 0),
+        2 => Value::Number(random_number(random)),
+        3 => Value::String(random_string(random)),
+        4 => {
+            let length = random.below(5);
+            Value::Array(
+                (0..length)
+                    .map(|_| random_value(random, depth - 1))
+                    .collect(),
+            )
+        }
+        _ => {
+            let mut object = Map::new();
+            for _ in 0..random.below(5) {
+                object.insert(random_string(random), random_value(random, depth - 1));
+            }
+            Value::Object(object)
+        }
+    }
+}
+
+fn assert_same(a: &Value, b: &Value, path: &str) {
+    match (a, b) {
+        (Value::Null, Value::Null) => {}
+        (Value::Bool(a), Value::Bool(b)) if a == b => {}
+        (Value::String(a), Value::String(b)) if a == b => {}
+        (Value::Number(a), Value::Number(b)) => match (a, b) {
+            (Number::Integer64(a), Number::Integer64(b)) if a == b => {}
+            (Number::UInt64(a), Number::UInt64(b)) if a == b => {}
+            (Number::Float64(a), Number::Float64(b)) if a.to_bits() == b.to_bits() => {}
+            (Number::Raw(a), Number::Raw(b)) if a == b => {}
+            _ => panic!("{}: {:?} came back as {:?}", path, a, b),
+        },
+        (Value::Array(a), Value::Array(b)) if a.len() == b.len() => {
+            for (index, (a, b)) in a.iter().zip(b).enumerate() {
+                assert_same(a, b, &format!("{}/{}", path, index));
+            }
+        }
+        (Value::Object(a), Value::Object(b)) if a.len() == b.len() => {
+            for ((key_a, a), (key_b, b)) in a.iter().zip(b) {
+                assert_eq!(key_a, key_b, "{}: member order changed", path);
+                assert_same(a, b, &format!("{}/{}", path, key_a));
+            }
+        }
+        _ => panic!("{}: {} came back as {}", path, a, b),
+    }
+}
+
+fn assert_round_trip(value: &Value) {
+    for serialized in [json::serialize(value), json::serialize_pretty(value)] {
+        let parsed = json::parse(&serialized)
+            .unwrap_or_else(|error| panic!("{} does not parse: {}", serialized, error));
+        assert_same(value, &parsed, "");
+        assert_eq!(*value, parsed);
+    }
+}
+
+#[test]
+fn generated_values_round_trip() {
+    let mut random = Random(0x5e7e_21d1_7b0c_a11e);
+    for _ in 0..5000 {
+        let value = random_value(&mut random, 4);
+        assert_round_trip(&value);
+    }
+}
+
+#[test]
+fn edge_case_numbers_round_trip() {
+    for &value in INTERESTING_INTEGERS {
+        assert_round_trip(&Value::from(value));
+    }
+    for &value in INTERESTING_FLOATS {
+        assert_round_trip(&Value::from(value));
+    }
+    assert_round_trip(&Value::from(u64::MAX));
+    assert_round_trip(&Value::from(i64::MAX as u64 + 1));
+}
+
+#[test]
+fn edge_case_strings_round_trip() {
+    for string in INTERESTING_STRINGS {
+        assert_round_trip(&Value::from(*string));
+    }
+    let every_control_character: String = (0..0x20u8).map(char::from).collect();
+    assert_round_trip(&Value::from(every_control_character));
+}
+
+#[test]
+fn deep_nesting_round_trips() {
+    // As deep as the parser accepts by default.
+    let mut value = Value::from("bottom");
+    for depth in 0..json::DEFAULT_MAX_DEPTH {
+        value = if depth % 2 == 0 {
+            Value::Array(vec![value])
+        } else {
+            Value::Object(Map::from_iter([("nested".to_string(), value)]))
+        };
+    }
+    assert_round_trip(&value);
+}
+
+#[test]
+fn floats_keep_exponents_short() {
+    let cases = [
+        (1e300, "1e300"),
+        (-1e300, "-1e300"),
+        (1.5e-7, "1.5e-7"),
+        (5e-324, "5e-324"),
+        (f64::MAX, "1.7976931348623157e308"),
+        (1e21, "1e21"),
+        (1e20, "100000000000000000000.0"),
+        (1e-6, "0.000001"),
+        (0.0, "0.0"),
+        (-0.0, "-0.0"),
+        (2.0, "2.0"),
+        (0.1, "0.1"),
+    ];
+    for (value, expected) in cases {
+        assert_eq!(json::serialize(&Value::from(value)), expected);
+    }
+    // Infinities and NaN have no JSON representation.
+    assert_eq!(json::serialize(&Value::from(f64::INFINITY)), "null");
+    assert_eq!(json::serialize(&Value::from(f64::NAN)), "null");
+}
@@ -0,0 +1,392 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// The fixtures in fixtures/json-schema are the draft 2020-12 cases of the JSON-Schema-Test-Suite
+// (https://github.com/json-schema-org/JSON-Schema-Test-Suite) for the keywords json::schema
+// supports, leaving out groups that depend on keywords it ignores.
+
+use std::path::Path;
+
+use serenity::json::schema::{self, SchemaErrorKind, ValidationErrorKind};
+use serenity::json::{self, Value};
+
+fn compile(schema: &str) -> schema::Schema {
+    schema::compile(&json::parse(schema).unwrap()).unwrap()
+}
+
+fn errors(schema: &str, value: &str) -> Vec<(String, String)> {
+    match compile(schema).validate(&json::parse(value).unwrap()) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors
+            .iter()
+            .map(|error| (error.path().to_string(), error.kind().to_string()))
+            .collect(),
+    }
+}
+
+#[test]
+fn passes_the_test_suite() {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/json-schema");
+    let mut files = std::fs::read_dir(&directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    files.sort();
+    assert!(!files.is_empty());
+
+    let mut failures = Vec::new();
+    for file in files {
+        let name = file.file_name().unwrap().to_string_lossy().into_owned();
+        let groups = json::parse(&std::fs::read_to_string(&file).unwrap()).unwrap();
+        for group in groups.as_array().unwrap() {
+            let description = group["description"].as_str().unwrap();
+            let schema = schema::compile(&group["schema"])
+                .unwrap_or_else(|error| panic!("{}: {}: {}", name, description, error));
+            for test in group["tests"].as_array().unwrap() {
+                let expected = test["valid"].as_bool().unwrap();
+                if schema.is_valid(&test["data"]) != expected {
+                    failures.push(format!(
+                        "{}: {}: {}",
+                        name,
+                        description,
+                        test["description"].as_str().unwrap()
+                    ));
+                }
+            }
+        }
+    }
+    assert!(failures.is_empty(), "failed:\n{}", failures.join("\n"));
+}
+
+#[test]
+fn reports_every_violation_with_its_location() {
+    let schema = r##"{
+        "type": "object",
+        "required": ["name", "pid"],
+        "properties": {
+            "name": {"type": "string", "pattern": "^[A-Z]"},
+            "threads": {"items": {"$ref": "#/$defs/thread"}}
+        },
+        "$defs": {
+            "thread": {"properties": {"cpu": {"minimum": 0, "exclusiveMaximum": 4}}}
+        }
+    }"##;
+    let value = r#"{"name": "cat", "threads": [{"cpu": 1}, {"cpu": 4}, {"cpu": -1}]}"#;
+    assert_eq!(errors(schema, value), [
+        (
+            String::new(),
+            "missing required property \"pid\"".to_string()
+        ),
+        (
+            "/name".to_string(),
+            "string does not match the pattern \"^[A-Z]\"".to_string()
+        ),
+        (
+            "/threads/1/cpu".to_string(),
+            "value must be less than 4".to_string()
+        ),
+        (
+            "/threads/2/cpu".to_string(),
+            "value is less than the minimum of 0".to_string()
+        ),
+    ]);
+
+    assert_eq!(errors(r#"{"type": ["string", "null"]}"#, "1.5"), [(
+        String::new(),
+        "expected string or null, found number".to_string()
+    )]);
+    assert_eq!(errors(r#"{"type": "string"}"#, "2"), [(
+        String::new(),
+        "expected string, found integer".to_string()
+    )]);
+    assert_eq!(
+        errors(r#"{"properties": {"a/b~c": false}}"#, r#"{"a/b~c": 1}"#),
+        [(
+            "/a~1b~0c".to_string(),
+            "no value is allowed here".to_string()
+        )]
+    );
+}
+
+#[test]
+fn compares_numbers_by_value() {
+    let schema = compile(r#"{"enum": [1, 2.5, 18446744073709551615], "maximum": 1e30}"#);
+    for valid in ["1", "1.0", "2.5", "18446744073709551615"] {
+        assert!(schema.is_valid(&json::parse(valid).unwrap()), "{}", valid);
+    }
+    for invalid in ["2", "-1", "18446744073709551614"] {
+        assert!(
+            !schema.is_valid(&json::parse(invalid).unwrap()),
+            "{}",
+            invalid
+        );
+    }
+
+    // Bounds beyond i64 and u64 still compare exactly.
+    let schema = compile(r#"{"maximum": 18446744073709551615, "minimum": -9223372036854775808}"#);
+    assert!(schema.is_valid(&Value::from(u64::MAX)));
+    assert!(schema.is_valid(&Value::from(i64::MIN)));
+    assert!(!schema.is_valid(&json::parse("18446744073709551616").unwrap()));
+    assert!(!schema.is_valid(&Value::from(-9.3e18)));
+}
+
+#[test]
+fn stops_reference_loops() {
+    let schema = compile(
+        r##"{"$defs": {"a": {"$ref": "#/$defs/b"}, "b": {"$ref": "#/$defs/a"}}, "$ref": "#/$defs/a"}"##,
+    );
+    let errors = schema.validate(&Value::Null).unwrap_err();
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0].kind(),
+        ValidationErrorKind::ReferenceLoop
+    ));
+
+    // Recursion through nested values is fine, however deep.
+    let schema = compile(r##"{"items": {"$ref": "#"}, "type": "array"}"##);
+    let mut value = Value::Array(Vec::new());
+    for _ in 0..100 {
+        value = Value::Array(vec![value]);
+    }
+    assert!(schema.is_valid(&value));
+    assert!(!schema.is_valid(&json::parse("[[[1]]]").unwrap()));
+}
+
+#[test]
+fn rejects_invalid_schemas() {
+    let cases = [
+        ("1", "", "a schema must be an object or a boolean"),
+        (
+            r#"{"type": "float"}"#,
+            "/type",
+            "\"type\" must be a type name or an array of type names",
+        ),
+        (
+            r#"{"type": [1]}"#,
+            "/type",
+            "\"type\" must be a type name or an array of type names",
+        ),
+        (r#"{"enum": 1}"#, "/enum", "\"enum\" must be an array"),
+        (
+            r#"{"minimum": "1"}"#,
+            "/minimum",
+            "\"minimum\" must be a number",
+        ),
+        (
+            r#"{"exclusiveMaximum": true}"#,
+            "/exclusiveMaximum",
+            "\"exclusiveMaximum\" must be a number",
+        ),
+        (
+            r#"{"pattern": 1}"#,
+            "/pattern",
+            "\"pattern\" must be a string",
+        ),
+        (
+            r#"{"pattern": "(a"}"#,
+            "/pattern",
+            "invalid pattern: unmatched parenthesis",
+        ),
+        (
+            r#"{"properties": []}"#,
+            "/properties",
+            "\"properties\" must be an object",
+        ),
+        (
+            r#"{"properties": {"a": 1}}"#,
+            "/properties/a",
+            "a schema must be an object or a boolean",
+        ),
+        (
+            r#"{"required": [1]}"#,
+            "/required",
+            "\"required\" must be an array of strings",
+        ),
+        (r#"{"items": [{}]}"#, "/items", "\"items\" must be a schema"),
+        (
+            r#"{"items": {"$ref": 1}}"#,
+            "/items/$ref",
+            "\"$ref\" must be a string",
+        ),
+        (
+            r##"{"$ref": "#/$defs/missing"}"##,
+            "/$ref",
+            "cannot resolve reference \"#/$defs/missing\"",
+        ),
+        (
+            r#"{"$ref": "other.json#"}"#,
+            "/$ref",
+            "reference \"other.json#\" does not point into this document",
+        ),
+        (
+            r##"{"$ref": "#anchor"}"##,
+            "/$ref",
+            "reference \"#anchor\" does not point into this document",
+        ),
+    ];
+    for (schema, path, message) in cases {
+        let error = schema::compile(&json::parse(schema).unwrap())
+            .err()
+            .unwrap_or_else(|| panic!("{} compiled", schema));
+        assert_eq!(
+            (error.path(), error.kind().to_string().as_str()),
+            (path, message),
+            "{}",
+            schema
+        );
+    }
+}
+
+// Compiles {"pattern": source}, or returns why the pattern was rejected.
+fn pattern(source: &str) -> Result<schema::Schema, String> {
+    let schema = json::ObjectBuilder::new().insert("pattern", source).build();
+    schema::compile(&schema).map_err(|error| match error.kind() {
+        SchemaErrorKind::InvalidPattern(error) => error.to_string(),
+        kind => panic!("{}: {}", source, kind),
+    })
+}
+
+// Each pattern with the strings it has to match and the ones it must not.
+fn assert_matches(cases: &[(&str, &[&str], &[&str])]) {
+    for (source, matching, other) in cases {
+        let schema = pattern(source).unwrap_or_else(|error| panic!("{}: {}", source, error));
+        for text in *matching {
+            assert!(
+                schema.is_valid(&Value::from(*text)),
+                "{} should match {:?}",
+                source,
+                text
+            );
+        }
+        for text in *other {
+            assert!(
+                !schema.is_valid(&Value::from(*text)),
+                "{} should not match {:?}",
+                source,
+                text
+            );
+        }
+    }
+}
+
+#[test]
+fn regex_classes() {
+    assert_matches(&[
+        ("^[abc]$", &["a", "c"], &["d", "", "ab"]),
+        ("^[^abc]$", &["d", "\u{e9}", "\n"], &["a", ""]),
+        ("^[a-cx-z]+$", &["abczyx"], &["abd", "w"]),
+        ("^[-a]$", &["-", "a"], &["b"]),
+        ("^[a-]$", &["-", "a"], &["b"]),
+        ("^[\\d-z]$", &["5", "-", "z"], &["a"]),
+        ("^[\\]\\\\^]$", &["]", "\\", "^"], &["a"]),
+        ("^[\\b]$", &["\u{8}"], &["b"]),
+        ("^[\\u00e0-\\u00ff]$", &["\u{e9}", "\u{ff}"], &[
+            "e", "\u{100}",
+        ]),
+        ("^[.]$", &["."], &["a"]),
+        ("^\\d+$", &["0123456789"], &["12a", "\u{660}", ""]),
+        ("^\\D$", &["a", "\u{660}"], &["1"]),
+        ("^\\w+$", &["snake_case_9"], &["kebab-case", "\u{e9}"]),
+        ("^\\W$", &["-", "\u{e9}"], &["_", "a"]),
+        (
+            "^\\s+$",
+            &[
+                " \t\n\r\u{b}\u{c}",
+                "\u{a0}\u{2003}\u{2028}\u{feff}\u{3000}",
+            ],
+            &["\u{200b}", "a"],
+        ),
+        ("^\\S$", &["a"], &[" ", "\u{a0}"]),
+        ("^[\\s\\d]+$", &["1 2\t3"], &["1 a"]),
+        ("^[^\\d\\s]$", &["a"], &["1", " "]),
+        ("^.$", &["a", "\u{1f600}", "\t"], &[
+            "\n", "\r", "\u{2028}", "\u{2029}", "",
+        ]),
+        ("^\\x41\\u0042\\cJ\\t\\0$", &["AB\n\t\0"], &["AB"]),
+        ("^\\.\\*\\/\\$$", &[".*/$"], &["a*/$"]),
+    ]);
+}
+
+#[test]
+fn regex_anchors() {
+    assert_matches(&[
+        ("abc", &["abc", "xabcx"], &["ab", "acb"]),
+        ("^abc", &["abc", "abcx"], &["xabc"]),
+        ("abc$", &["abc", "xabc"], &["abcx", "abc\n"]),
+        ("^$", &[""], &["\n", " "]),
+        ("^a|b$", &["ax", "xb"], &["xa", "bx"]),
+        ("^(a|b)$", &["a", "b"], &["ab", "xb"]),
+        ("\\bfoo\\b", &["foo", "a foo.", "(foo)"], &[
+            "foobar", "_foo",
+        ]),
+        ("\\Boo\\B", &["foods"], &["oo", "a oo b"]),
+        ("^\\b$", &[], &["", " "]),
+        ("(^|,)b(,|$)", &["b", "a,b", "a,b,c", "b,c"], &[
+            "ab", "a,bc",
+        ]),
+    ]);
+}
+
+#[test]
+fn regex_quantifiers() {
+    assert_matches(&[
+        ("^a*$", &["", "a", "aaaa"], &["b", "ab"]),
+        ("^a+$", &["a", "aaa"], &["", "b"]),
+        ("^ab?c$", &["ac", "abc"], &["abbc"]),
+        ("^a{3}$", &["aaa"], &["aa", "aaaa"]),
+        ("^a{2,}$", &["aa", "aaaaaa"], &["a"]),
+        ("^a{2,3}$", &["aa", "aaa"], &["a", "aaaa"]),
+        ("^a{0}b$", &["b"], &["ab"]),
+        ("^(ab){2}$", &["abab"], &["ab", "ababab"]),
+        ("^(?:a|bc)+$", &["abca", "bcbc"], &["", "abcb"]),
+        ("^a+?b*?c??$", &["ab", "aaabbb", "ac"], &["b"]),
+        ("^a{,2}$", &["a{,2}"], &["aa"]),
+        ("^a{x}$", &["a{x}"], &["ax"]),
+        ("^x{2}{$", &["xx{"], &["xx"]),
+        ("^(a*)*$", &["", "aaaa"], &["aaab"]),
+        ("^(a|a)*b$", &["aaaaaaaaaaaaaaaaaaaaaaaaaaab"], &[
+            "aaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+        ]),
+        ("^(a?){25}a{25}$", &["aaaaaaaaaaaaaaaaaaaaaaaaa"], &[
+            "aaaaaaaaaaaaaaaaaaaaaaaa",
+        ]),
+    ]);
+}
+
+#[test]
+fn regex_errors() {
+    let cases = [
+        ("(a", "unmatched parenthesis"),
+        ("a)", "unmatched parenthesis"),
+        ("*a", "quantifier without anything to repeat"),
+        ("a**", "quantifier without anything to repeat"),
+        ("^*", "quantifier without anything to repeat"),
+        ("{2}", "quantifier without anything to repeat"),
+        ("a{3,2}", "invalid range"),
+        ("[z-a]", "invalid range"),
+        ("[a", "unexpected end of pattern"),
+        ("a\\", "unexpected end of pattern"),
+        ("\\x4", "unexpected end of pattern"),
+        ("\\xg0", "invalid escape sequence"),
+        ("\\q", "invalid escape sequence"),
+        ("\\c1", "invalid escape sequence"),
+        ("(a)\\1", "backreferences are not supported"),
+        ("a(?=b)", "lookahead assertions are not supported"),
+        ("a(?!b)", "lookahead assertions are not supported"),
+        ("(?<=a)b", "lookbehind assertions are not supported"),
+        ("(?<!a)b", "lookbehind assertions are not supported"),
+        ("(?<name>a)", "named groups are not supported"),
+        ("\\ud800", "surrogate escapes are not supported"),
+        ("a{99999999999}", "pattern is too large"),
+        ("(a{1000}){1000}", "pattern is too large"),
+    ];
+    for (source, message) in cases {
+        match pattern(source) {
+            Ok(_) => panic!("{} compiled", source),
+            Err(error) => assert_eq!(error, message, "{}", source),
+        }
+    }
+}
@@ -0,0 +1,261 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// The pci.ids parser against a few entries of the real database, laid out like the full file
+// with a section it doesn't know at the end, and against hand-written files for the ways one can
+// be broken.
+
+use serenity::pcidb::{Database, PciDatabaseError, PciDatabaseErrorKind};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/pci.ids");
+
+fn recorded() -> Database { Database::open(FIXTURE).unwrap() }
+
+fn fixture() -> String { std::fs::read_to_string(FIXTURE).unwrap() }
+
+// The kind of error and the line it was on.
+fn error(input: &str) -> (String, usize) {
+    let error: PciDatabaseError = Database::parse(input).unwrap_err();
+    let kind = match error.kind() {
+        PciDatabaseErrorKind::InvalidId => "invalid",
+        PciDatabaseErrorKind::Orphan => "orphan",
+        PciDatabaseErrorKind::Io(error) => panic!("unexpected I/O error {}", error),
+    };
+    (kind.to_string(), error.line())
+}
+
+#[test]
+fn parses_the_database() {
+    let database = recorded();
+    assert_eq!(database.vendor_count(), 4);
+    assert_eq!(database.device_count(), 9);
+    assert_eq!(database.class_count(), 6);
+}
+
+#[test]
+fn looks_up_vendors_and_devices() {
+    let database = recorded();
+    assert_eq!(database.vendor(0x8086), Some("Intel Corporation"));
+    assert_eq!(database.vendor(0x1af4), Some("Red Hat, Inc."));
+    // Vendors without devices are still known.
+    assert_eq!(database.vendor(0x0001), Some("SafeNet (wrong ID)"));
+    assert_eq!(database.vendor(0x10de), None);
+
+    assert_eq!(
+        database.device(0x8086, 0x7010),
+        Some("82371SB PIIX3 IDE [Natoma/Triton II]")
+    );
+    assert_eq!(
+        database.device(0x1234, 0x1111),
+        Some("QEMU Virtual Video Controller")
+    );
+    // Devices belong to their vendor.
+    assert_eq!(database.device(0x1af4, 0x1111), None);
+    assert_eq!(database.device(0x8086, 0xffff), None);
+}
+
+#[test]
+fn looks_up_subsystems() {
+    let database = recorded();
+    assert_eq!(
+        database.subsystem(0x8086, 0x100e, 0x1af4, 0x1100),
+        Some("QEMU Virtual Machine")
+    );
+    assert_eq!(
+        database.subsystem(0x8086, 0x100e, 0x8086, 0x001e),
+        Some("PRO/1000 MT Desktop Adapter")
+    );
+    assert_eq!(
+        database.subsystem(0x1af4, 0x1000, 0x1af4, 0x0001),
+        Some("Virtio network device")
+    );
+    // Subsystems belong to their device.
+    assert_eq!(database.subsystem(0x8086, 0x2922, 0x8086, 0x001e), None);
+    assert_eq!(database.subsystem(0x8086, 0x7010, 0x1af4, 0x1100), None);
+}
+
+#[test]
+fn looks_up_classes() {
+    let database = recorded();
+    assert_eq!(database.class(0x01), Some("Mass storage controller"));
+    assert_eq!(database.class(0xff), Some("Unassigned class"));
+    assert_eq!(database.class(0x04), None);
+
+    assert_eq!(database.subclass(0x06, 0x80), Some("Bridge"));
+    assert_eq!(database.subclass(0x01, 0x06), Some("SATA controller"));
+    assert_eq!(database.subclass(0x02, 0x06), None);
+
+    assert_eq!(
+        database.programming_interface(0x01, 0x01, 0x80),
+        Some("ISA Compatibility mode-only controller, supports bus mastering")
+    );
+    assert_eq!(
+        database.programming_interface(0x01, 0x06, 0x01),
+        Some("AHCI 1.0")
+    );
+    assert_eq!(
+        database.programming_interface(0x03, 0x00, 0x00),
+        Some("VGA controller")
+    );
+    assert_eq!(database.programming_interface(0x01, 0x06, 0x80), None);
+    assert_eq!(database.programming_interface(0x06, 0x00, 0x00), None);
+}
+
+#[test]
+fn skips_unknown_sections() {
+    let database = recorded();
+    // "V 0001" and the lines under it don't turn into a vendor, device or subsystem.
+    assert_eq!(database.vendor(0x0001), Some("SafeNet (wrong ID)"));
+    assert_eq!(database.device(0x0001, 0x0001), None);
+
+    // Known sections may follow unknown ones.
+    let input = "\
+L 0409  English (US)
+\tnot an ID
+C 02  Network controller
+\t00  Ethernet controller
+1af4  Red Hat, Inc.
+";
+    let database = Database::parse(input).unwrap();
+    assert_eq!(database.subclass(0x02, 0x00), Some("Ethernet controller"));
+    assert_eq!(database.vendor(0x1af4), Some("Red Hat, Inc."));
+    assert_eq!(database.vendor_count(), 1);
+}
+
+#[test]
+fn does_not_depend_on_the_order() {
+    let input = "\
+8086  Intel Corporation
+\t7010  PIIX3 IDE
+1234  Technical Corp.
+\t1111  QEMU VGA
+8086  Intel Corporation, again
+\t1237  440FX
+";
+    let database = Database::parse(input).unwrap();
+    assert_eq!(database.vendor(0x1234), Some("Technical Corp."));
+    assert_eq!(database.device(0x1234, 0x1111), Some("QEMU VGA"));
+    // A vendor that comes back gets more devices, but keeps its first name.
+    assert_eq!(database.vendor(0x8086), Some("Intel Corporation"));
+    assert_eq!(database.device(0x8086, 0x7010), Some("PIIX3 IDE"));
+    assert_eq!(database.device(0x8086, 0x1237), Some("440FX"));
+    assert_eq!(database.vendor_count(), 2);
+}
+
+#[test]
+fn keeps_the_first_duplicate() {
+    let input = "\
+8086  Intel Corporation
+\t7010  PIIX3 IDE
+\t\t1af4 1100  QEMU
+\t\t1af4 1100  QEMU, again
+\t7010  PIIX3 IDE, again
+8086  Intel, again
+C 01  Mass storage controller
+\t01  IDE interface
+\t\t80  Bus mastering
+\t\t80  Bus mastering, again
+\t01  IDE, again
+C 01  Storage, again
+";
+    let database = Database::parse(input).unwrap();
+    assert_eq!(database.vendor(0x8086), Some("Intel Corporation"));
+    assert_eq!(database.device(0x8086, 0x7010), Some("PIIX3 IDE"));
+    assert_eq!(
+        database.subsystem(0x8086, 0x7010, 0x1af4, 0x1100),
+        Some("QEMU")
+    );
+    assert_eq!(database.class(0x01), Some("Mass storage controller"));
+    assert_eq!(database.subclass(0x01, 0x01), Some("IDE interface"));
+    assert_eq!(
+        database.programming_interface(0x01, 0x01, 0x80),
+        Some("Bus mastering")
+    );
+    assert_eq!(
+        (
+            database.vendor_count(),
+            database.device_count(),
+            database.class_count()
+        ),
+        (1, 1, 1)
+    );
+}
+
+#[test]
+fn reports_invalid_ids() {
+    // The fixture parses, so the malformed line is the only problem. Lines count from 1.
+    let contents = fixture();
+    let mut lines: Vec<&str> = contents.lines().collect();
+    let intel = lines
+        .iter()
+        .position(|line| line.starts_with("8086"))
+        .unwrap();
+    lines.insert(intel + 1, "\t10OE  82540EM with an O for a 0");
+    assert_eq!(error(&lines.join("\n")), ("invalid".to_string(), intel + 2));
+
+    assert_eq!(
+        error("8086  Intel\n\t7010PIIX3\n"),
+        ("invalid".to_string(), 2)
+    );
+    assert_eq!(error("808  Intel\n"), ("invalid".to_string(), 1));
+    assert_eq!(
+        error("8086  Intel\n\t7010  PIIX3\n\t\t1af4  QEMU\n"),
+        ("invalid".to_string(), 3)
+    );
+    assert_eq!(error("C 1  Storage\n"), ("invalid".to_string(), 1));
+    assert_eq!(
+        error("C 01  Storage\n\t100  IDE\n"),
+        ("invalid".to_string(), 2)
+    );
+    // Three digits where a programming interface has two.
+    assert_eq!(
+        error("# Classes\n\nC 01  Storage\n\t01  IDE\n\t\t1ff  Bus mastering\n"),
+        ("invalid".to_string(), 5)
+    );
+}
+
+#[test]
+fn reports_orphans() {
+    // Before anything they could belong to.
+    assert_eq!(
+        error("# Devices\n\t7010  PIIX3 IDE\n"),
+        ("orphan".to_string(), 2)
+    );
+    assert_eq!(error("\t\t1af4 1100  QEMU\n"), ("orphan".to_string(), 1));
+    // A subsystem under a vendor without devices doesn't end up under the last vendor's device.
+    assert_eq!(
+        error("1af4  Red Hat, Inc.\n\t1000  Virtio\n0001  SafeNet\n\t\t1af4 0001  Virtio\n"),
+        ("orphan".to_string(), 4)
+    );
+    // Likewise for programming interfaces under a class without subclasses.
+    assert_eq!(
+        error("C 01  Storage\n\t01  IDE\nC 02  Network controller\n\t\t00  Ethernet\n"),
+        ("orphan".to_string(), 4)
+    );
+    assert_eq!(
+        error("1af4  Red Hat, Inc.\n\t1000  Virtio\nC 02  Network controller\n\t\t00  Ethernet\n"),
+        ("orphan".to_string(), 4)
+    );
+}
+
+#[test]
+fn formats_errors() {
+    let error = Database::open("/nonexistent/pci.ids").unwrap_err();
+    assert!(matches!(error.kind(), PciDatabaseErrorKind::Io(_)));
+    assert_eq!(error.line(), 0);
+    assert!(!error.to_string().contains("line"));
+
+    let error = Database::parse("C 01  Storage\n\t01  IDE\n\tzz  Bogus\n").unwrap_err();
+    assert_eq!(error.to_string(), "invalid ID on line 3");
+}
+
+#[test]
+fn parses_empty_files() {
+    let database = Database::parse("").unwrap();
+    assert_eq!(database.vendor_count(), 0);
+    assert_eq!(database.vendor(0x8086), None);
+    assert_eq!(Database::parse("# Nothing\n\n").unwrap().class_count(), 0);
+}
@@ -0,0 +1,414 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// The readers against nodes recorded from a running system, and against hand-edited nodes for
+// the members some kernels leave out and for the ways a node can be broken.
+
+#![cfg(feature = "serde")]
+
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serenity::procfs::{
+    ArpEntry,
+    Device,
+    DeviceType,
+    LocalSocket,
+    MemoryStatus,
+    PciDevice,
+    ProcFS,
+    ProcFSError,
+    ProcFSErrorKind,
+    SocketOrigin,
+};
+
+fn recorded() -> ProcFS {
+    ProcFS::with_root(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/proc"))
+}
+
+// A root holding a single node with the given contents, private to the calling test.
+fn with_node(test: &str, node: &str, contents: &str) -> ProcFS {
+    let root =
+        std::env::temp_dir().join(format!("serenity-procfs-{}-{}", std::process::id(), test));
+    let path = root.join(node);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+    ProcFS::with_root(root)
+}
+
+fn invalid(error: ProcFSError) -> String {
+    match error.kind() {
+        ProcFSErrorKind::Invalid(invalid) => invalid.to_string(),
+        kind => panic!("expected an invalid member, got {:?}", kind),
+    }
+}
+
+#[test]
+fn reads_all() {
+    let all = recorded().all().unwrap();
+    assert_eq!(all.total_time, 35896088);
+    assert_eq!(all.total_time_kernel, 27741078);
+    assert_eq!(all.processes.len(), 33);
+
+    let colonel = &all.processes[0];
+    assert_eq!((colonel.pid, colonel.name.as_str()), (0, "colonel"));
+    assert!(colonel.kernel);
+    assert_eq!(colonel.threads.len(), 4);
+    assert_eq!(colonel.threads[3].name, "idle thread #3");
+
+    let cat = all.processes.last().unwrap();
+    assert_eq!((cat.pid, cat.ppid, cat.pgp, cat.sid), (36, 35, 36, 35));
+    assert_eq!((cat.uid, cat.gid), (100, 100));
+    assert_eq!(cat.name, "cat");
+    assert_eq!(cat.tty, "/dev/pts/0");
+    assert_eq!(cat.veil, "None");
+    assert!(!cat.kernel);
+    assert_eq!(cat.threads[0].tid, 36);
+    assert_eq!(cat.threads[0].state, "Running");
+
+    let web_content = all
+        .processes
+        .iter()
+        .find(|process| process.pid == 29)
+        .unwrap();
+    assert_eq!(web_content.threads.len(), 6);
+    assert_eq!(web_content.threads[0].tid, 29);
+}
+
+#[test]
+fn reads_cpuinfo() {
+    let processors = recorded().cpuinfo().unwrap();
+    assert_eq!(processors.len(), 4);
+    for (index, processor) in processors.iter().enumerate() {
+        assert_eq!(processor.processor, index as u32);
+    }
+    let processor = &processors[0];
+    assert_eq!(processor.vendor_id, "GenuineIntel");
+    assert_eq!(processor.hypervisor_vendor_id.as_deref(), Some("KVMKVMKVM"));
+    assert_eq!(processor.brand, "Intel(R) Core(TM) i7-8700 CPU @ 3.20GHz");
+    assert_eq!(
+        (processor.family, processor.model, processor.stepping),
+        (6, 158, 10)
+    );
+    assert_eq!(processor.processor_type, 0);
+    assert_eq!(processor.features.len(), 62);
+    assert_eq!(&processor.features[..3], ["fpu", "vme", "de"]);
+}
+
+#[test]
+fn reads_optional_cpuinfo_members() {
+    let procfs = with_node(
+        "cpuinfo",
+        "cpuinfo",
+        r#"[{"processor":0,"vendor_id":"AuthenticAMD","family":23,"features":["fpu"],
+            "model":113,"stepping":0,"type":0,"brand":"AMD Ryzen 5 3600"}]"#,
+    );
+    let processor = &procfs.cpuinfo().unwrap()[0];
+    assert_eq!(processor.hypervisor_vendor_id, None);
+}
+
+#[test]
+fn reads_interrupts() {
+    let handlers = recorded().interrupts().unwrap();
+    assert_eq!(handlers.len(), 7);
+    let shared = &handlers[3];
+    assert_eq!(shared.purpose, "SharedIRQHandler");
+    assert_eq!(shared.interrupt_line, 11);
+    assert_eq!(shared.controller, "IOAPIC");
+    assert_eq!((shared.cpu_handler, shared.device_sharing), (0, 2));
+    assert_eq!(shared.call_count, 48213);
+    assert!(handlers
+        .iter()
+        .all(|handler| handler.per_cpu_call_counts.is_none()));
+
+    let procfs = with_node(
+        "interrupts",
+        "interrupts",
+        r#"[{"purpose":"PIT","interrupt_line":0,"controller":"i8259","cpu_handler":0,
+            "device_sharing":1,"call_count":30,"per_cpu_call_counts":[10,20]}]"#,
+    );
+    assert_eq!(
+        procfs.interrupts().unwrap()[0].per_cpu_call_counts,
+        Some(vec![10, 20])
+    );
+}
+
+#[test]
+fn reads_memstat() {
+    assert_eq!(recorded().memstat().unwrap(), MemoryStatus {
+        kmalloc_allocated: 18650176,
+        kmalloc_available: 4063680,
+        user_physical_allocated: 109325,
+        user_physical_available: 399478,
+        user_physical_committed: 1342,
+        user_physical_uncommitted: 398136,
+        super_physical_allocated: 258,
+        super_physical_available: 254,
+        kmalloc_call_count: 4821931,
+        kfree_call_count: 4760402,
+    });
+}
+
+#[test]
+fn reads_df() {
+    let file_systems = recorded().df().unwrap();
+    assert_eq!(file_systems.len(), 6);
+    let root = &file_systems[0];
+    assert_eq!(
+        (root.class_name.as_str(), root.mount_point.as_str()),
+        ("Ext2FS", "/")
+    );
+    assert_eq!(root.source, "/dev/hda");
+    assert_eq!(
+        (root.total_block_count, root.free_block_count),
+        (613376, 284001)
+    );
+    assert_eq!(
+        (root.total_inode_count, root.free_inode_count),
+        (153344, 139911)
+    );
+    assert_eq!(root.block_size, 4096);
+    assert!(!root.readonly);
+    assert_eq!(file_systems[1].source, "none");
+    assert!(file_systems[5].readonly);
+    assert_eq!(file_systems[5].mount_flags, 8);
+}
+
+#[test]
+fn reads_uptime() {
+    assert_eq!(recorded().uptime().unwrap(), Duration::from_secs(4863));
+    let error = with_node("uptime", "uptime", "soon\n")
+        .uptime()
+        .unwrap_err();
+    assert!(matches!(error.kind(), ProcFSErrorKind::UnexpectedShape));
+}
+
+#[test]
+fn reads_pci() {
+    let devices = recorded().pci().unwrap();
+    assert_eq!(devices.len(), 7);
+    assert_eq!(devices[5], PciDevice {
+        domain: 0,
+        bus: 0,
+        device: 3,
+        function: 0,
+        vendor_id: 0x8086,
+        device_id: 0x100e,
+        revision_id: 3,
+        class: 2,
+        subclass: 0,
+        subsystem_id: 0x1100,
+        subsystem_vendor_id: 0x1af4,
+    });
+    assert_eq!((devices[3].function, devices[3].subclass), (3, 0x80));
+}
+
+#[test]
+fn reads_devices() {
+    let devices = recorded().devices().unwrap();
+    assert_eq!(devices.len(), 7);
+    assert_eq!(devices[3], Device {
+        major: 3,
+        minor: 0,
+        class_name: "IDEDiskDevice".to_string(),
+        device_type: DeviceType::Block,
+    });
+    assert_eq!(devices[0].device_type, DeviceType::Character);
+
+    let procfs = with_node(
+        "devices",
+        "devices",
+        r#"[{"major":1,"minor":3,"class_name":"NullDevice","type":"fifo"}]"#,
+    );
+    let message = invalid(procfs.devices().unwrap_err());
+    assert!(message.starts_with("unknown variant `fifo`"), "{}", message);
+}
+
+#[test]
+fn reads_net_adapters() {
+    let adapters = recorded().net_adapters().unwrap();
+    assert_eq!(adapters.len(), 3);
+
+    let loopback = &adapters[0];
+    assert_eq!(loopback.ipv4_address, Some(Ipv4Addr::LOCALHOST));
+    assert_eq!(loopback.ipv4_netmask, Some(Ipv4Addr::new(255, 0, 0, 0)));
+    assert_eq!(loopback.ipv4_gateway, None);
+
+    let ethernet = &adapters[1];
+    assert_eq!(ethernet.name, "ep0s3");
+    assert_eq!(ethernet.class_name, "E1000NetworkAdapter");
+    assert_eq!(ethernet.mac_address, "52:54:00:12:34:56");
+    assert_eq!(ethernet.ipv4_address, Some(Ipv4Addr::new(10, 0, 2, 15)));
+    assert_eq!(ethernet.ipv4_gateway, Some(Ipv4Addr::new(10, 0, 2, 2)));
+    assert_eq!((ethernet.packets_in, ethernet.bytes_in), (3821, 4518337));
+    assert_eq!((ethernet.packets_out, ethernet.bytes_out), (2290, 201442));
+    assert!(ethernet.link_up && ethernet.link_full_duplex);
+    assert_eq!((ethernet.link_speed, ethernet.mtu), (1000, 1500));
+
+    // Unconfigured and without a link.
+    let unconfigured = &adapters[2];
+    assert_eq!(unconfigured.ipv4_address, None);
+    assert_eq!(unconfigured.ipv4_netmask, None);
+    assert!(!unconfigured.link_up);
+    assert_eq!(unconfigured.link_speed, -1);
+}
+
+#[test]
+fn reads_net_arp() {
+    assert_eq!(recorded().net_arp().unwrap(), [
+        ArpEntry {
+            ip_address: Ipv4Addr::new(10, 0, 2, 2),
+            mac_address: "52:55:0a:00:02:02".to_string(),
+        },
+        ArpEntry {
+            ip_address: Ipv4Addr::new(10, 0, 2, 3),
+            mac_address: "52:55:0a:00:02:03".to_string(),
+        },
+    ]);
+}
+
+#[test]
+fn reads_net_tcp() {
+    let sockets = recorded().net_tcp().unwrap();
+    assert_eq!(sockets.len(), 3);
+    assert_eq!(sockets[0].state, "Listen");
+    assert_eq!(
+        (sockets[0].local_address, sockets[0].local_port),
+        (Ipv4Addr::UNSPECIFIED, 8000)
+    );
+
+    let established = &sockets[1];
+    assert_eq!(established.state, "Established");
+    assert_eq!(established.local_address, Ipv4Addr::new(10, 0, 2, 15));
+    assert_eq!(established.local_port, 49153);
+    assert_eq!(established.peer_address, Ipv4Addr::new(93, 184, 216, 34));
+    assert_eq!(established.peer_port, 443);
+    assert_eq!(established.ack_number, 2871120483);
+    assert_eq!(established.sequence_number, 1207925519);
+    assert_eq!((established.packets_in, established.bytes_in), (41, 38820));
+    assert_eq!((established.packets_out, established.bytes_out), (23, 2960));
+    assert_eq!(
+        established.origin,
+        Some(SocketOrigin {
+            pid: 11,
+            uid: 100,
+            gid: 100,
+        })
+    );
+
+    // Someone else's socket, seen by a user that is not root.
+    assert_eq!(sockets[2].state, "TimeWait");
+    assert_eq!(sockets[2].origin, None);
+}
+
+#[test]
+fn reads_net_udp() {
+    let sockets = recorded().net_udp().unwrap();
+    assert_eq!(sockets.len(), 3);
+    assert_eq!(sockets[1].local_port, 50123);
+    assert_eq!(
+        (sockets[1].peer_address, sockets[1].peer_port),
+        (Ipv4Addr::new(10, 0, 2, 3), 53)
+    );
+    assert_eq!(
+        sockets[1].origin,
+        Some(SocketOrigin {
+            pid: 13,
+            uid: 0,
+            gid: 0,
+        })
+    );
+    assert_eq!(sockets[2].origin, None);
+}
+
+#[test]
+fn reads_net_local() {
+    let sockets = recorded().net_local().unwrap();
+    assert_eq!(sockets.len(), 4);
+    assert_eq!(sockets[2], LocalSocket {
+        path: "/tmp/portal/request".to_string(),
+        origin_pid: 11,
+        origin_uid: 100,
+        origin_gid: 100,
+        acceptor_pid: 29,
+        acceptor_uid: 100,
+        acceptor_gid: 100,
+    });
+    assert_eq!(sockets[3].acceptor_pid, 0);
+}
+
+#[test]
+fn reports_where_a_node_is_invalid() {
+    let cases = [
+        (
+            "interrupts",
+            r#"[{"purpose":"PIT","controller":"i8259","cpu_handler":0,"device_sharing":1,"call_count":1}]"#,
+            "missing field `interrupt_line` at [0]",
+        ),
+        (
+            "interrupts",
+            r#"[{"purpose":"PIT","interrupt_line":"0","controller":"i8259","cpu_handler":0,"device_sharing":1,"call_count":1}]"#,
+            "invalid type: string \"0\", expected u32 at [0].interrupt_line",
+        ),
+        (
+            "pci",
+            r#"[{"domain":0,"bus":256,"device":0,"function":0,"vendor_id":1,"device_id":1,"revision_id":0,"class":0,"subclass":0,"subsystem_id":0,"subsystem_vendor_id":0}]"#,
+            "invalid value: integer `256`, expected u8 at [0].bus",
+        ),
+        (
+            "memstat",
+            "true",
+            "invalid type: boolean `true`, expected struct MemoryStatus",
+        ),
+        (
+            "net/arp",
+            r#"[{"mac_address":"52:55:0a:00:02:02","ip_address":"10.0.2"}]"#,
+            "invalid IPv4 address syntax at [0].ip_address",
+        ),
+        (
+            "net/tcp",
+            r#"[{"local_address":"0.0.0.0","local_port":80,"peer_address":"0.0.0.0","peer_port":0,"state":"Listen","ack_number":0,"sequence_number":0,"packets_in":0,"bytes_in":0,"packets_out":0,"bytes_out":0,"origin_pid":1,"origin_gid":0}]"#,
+            "missing field `origin_uid` at [0]",
+        ),
+        (
+            "net/udp",
+            r#"[{"local_address":"0.0.0.0","local_port":80,"peer_address":"0.0.0.0","peer_port":0,"origin_pid":"1","origin_uid":0,"origin_gid":0}]"#,
+            "invalid type: string \"1\", expected i32 at [0]",
+        ),
+    ];
+    for (index, (node, contents, message)) in cases.into_iter().enumerate() {
+        let procfs = with_node(&format!("invalid-{}", index), node, contents);
+        let error = match node {
+            "interrupts" => procfs.interrupts().map(drop),
+            "pci" => procfs.pci().map(drop),
+            "memstat" => procfs.memstat().map(drop),
+            "net/arp" => procfs.net_arp().map(drop),
+            "net/tcp" => procfs.net_tcp().map(drop),
+            "net/udp" => procfs.net_udp().map(drop),
+            _ => unreachable!(),
+        }
+        .unwrap_err();
+        assert_eq!(error.path(), procfs.path(node));
+        assert_eq!(invalid(error), message, "{}", node);
+    }
+}
+
+#[test]
+fn reports_unreadable_nodes() {
+    let procfs = with_node("unreadable", "memstat", "{\"kmalloc_allocated\": ");
+    let error = procfs.memstat().unwrap_err();
+    assert!(matches!(error.kind(), ProcFSErrorKind::Parse(_)));
+    assert!(error
+        .to_string()
+        .starts_with(&format!("{}: ", procfs.path("memstat").display())));
+
+    let error = ProcFS::with_root(PathBuf::from("/nonexistent"))
+        .cpuinfo()
+        .unwrap_err();
+    assert!(matches!(error.kind(), ProcFSErrorKind::Io(_)));
+    assert_eq!(error.path(), PathBuf::from("/nonexistent/cpuinfo"));
+}
@@ -0,0 +1,161 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Turns a /proc/memstat reading into the used, free and total amounts of kernel heap and
+// physical memory, and prints them as a table in the chosen unit or as JSON.
+
+#[cfg(test)]
+mod tests;
+
+use std::io::Write;
+
+use serenity::json::{ObjectBuilder, Value};
+use serenity::procfs::{MemoryStatus, PAGE_SIZE};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unit {
+    // The largest unit that keeps the number at or above 1, like "1.5 GiB".
+    Human,
+    Bytes,
+    KiB,
+    MiB,
+}
+
+// Sizes are in bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Usage {
+    pub total: u64,
+    pub used: u64,
+    pub free: u64,
+}
+
+impl Usage {
+    fn new(used: u64, free: u64) -> Usage {
+        Usage {
+            total: used + free,
+            used,
+            free,
+        }
+    }
+
+    fn from_pages(used: u64, free: u64) -> Usage { Usage::new(used * PAGE_SIZE, free * PAGE_SIZE) }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Report {
+    // Pages the kernel hands out to user space.
+    pub physical: Usage,
+    // Physical pages the kernel keeps for itself.
+    pub supervisor: Usage,
+    pub kernel_heap: Usage,
+    // Physical memory promised to user space but not allocated yet, and what is left to promise.
+    pub committed: u64,
+    pub uncommitted: u64,
+    pub kmalloc_calls: u64,
+    pub kfree_calls: u64,
+}
+
+impl Report {
+    pub fn new(status: &MemoryStatus) -> Report {
+        Report {
+            physical: Usage::from_pages(
+                status.user_physical_allocated,
+                status.user_physical_available,
+            ),
+            supervisor: Usage::from_pages(
+                status.super_physical_allocated,
+                status.super_physical_available,
+            ),
+            kernel_heap: Usage::new(status.kmalloc_allocated, status.kmalloc_available),
+            committed: status.user_physical_committed * PAGE_SIZE,
+            uncommitted: status.user_physical_uncommitted * PAGE_SIZE,
+            kmalloc_calls: status.kmalloc_call_count,
+            kfree_calls: status.kfree_call_count,
+        }
+    }
+}
+
+// Like AK's human_readable_size(), with one truncated decimal.
+pub fn format_size(bytes: u64, unit: Unit) -> String {
+    const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+    match unit {
+        Unit::Bytes => bytes.to_string(),
+        Unit::KiB => (bytes / 1024).to_string(),
+        Unit::MiB => (bytes / (1024 * 1024)).to_string(),
+        Unit::Human => {
+            if bytes < 1024 {
+                return format!("{} B", bytes);
+            }
+            let mut size = 1024;
+            let mut index = 0;
+            while index + 1 < UNITS.len() && bytes / size >= 1024 {
+                size *= 1024;
+                index += 1;
+            }
+            let tenths = bytes % size * 10 / size;
+            format!("{}.{} {}", bytes / size, tenths, UNITS[index])
+        }
+    }
+}
+
+pub fn print_table(output: &mut impl Write, report: &Report, unit: Unit) -> std::io::Result<()> {
+    let size = |bytes| format_size(bytes, unit);
+    writeln!(
+        output,
+        "{:12} {:>11} {:>11} {:>11} {:>11} {:>11}",
+        "", "total", "used", "free", "committed", "uncommitted"
+    )?;
+    writeln!(
+        output,
+        "{:12} {:>11} {:>11} {:>11} {:>11} {:>11}",
+        "Physical:",
+        size(report.physical.total),
+        size(report.physical.used),
+        size(report.physical.free),
+        size(report.committed),
+        size(report.uncommitted)
+    )?;
+    for (name, usage) in [
+        ("Supervisor:", report.supervisor),
+        ("Kernel heap:", report.kernel_heap),
+    ] {
+        writeln!(
+            output,
+            "{:12} {:>11} {:>11} {:>11}",
+            name,
+            size(usage.total),
+            size(usage.used),
+            size(usage.free)
+        )?;
+    }
+    Ok(())
+}
+
+fn usage_json(usage: Usage) -> ObjectBuilder {
+    ObjectBuilder::new()
+        .insert("total", usage.total)
+        .insert("used", usage.used)
+        .insert("free", usage.free)
+}
+
+// Always in bytes, whatever unit the table would use.
+pub fn to_json(report: &Report) -> Value {
+    ObjectBuilder::new()
+        .insert(
+            "physical",
+            usage_json(report.physical)
+                .insert("committed", report.committed)
+                .insert("uncommitted", report.uncommitted),
+        )
+        .insert("supervisor", usage_json(report.supervisor))
+        .insert(
+            "kernel_heap",
+            usage_json(report.kernel_heap)
+                .insert("kmalloc_calls", report.kmalloc_calls)
+                .insert("kfree_calls", report.kfree_calls),
+        )
+        .build()
+}
@@ -0,0 +1,111 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Shows how much physical memory and kernel heap is in use, from /proc/memstat.
+
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use free::{Report, Unit};
+use serenity::{procfs, sys};
+
+const USAGE: &str = "usage: free [-b|-k|-m] [--json] [-s seconds]";
+
+struct Options {
+    unit: Unit,
+    json: bool,
+    interval: Option<Duration>,
+}
+
+fn parse_arguments() -> Result<Options, Box<dyn std::error::Error>> {
+    let mut options = Options {
+        unit: Unit::Human,
+        json: false,
+        interval: None,
+    };
+    let mut arguments = std::env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "-b" | "--bytes" => options.unit = Unit::Bytes,
+            "-k" | "--kib" => options.unit = Unit::KiB,
+            "-m" | "--mib" => options.unit = Unit::MiB,
+            "--json" => options.json = true,
+            "-s" | "--seconds" => {
+                let interval = arguments
+                    .next()
+                    .and_then(|seconds| seconds.parse::<f64>().ok())
+                    .filter(|seconds| seconds.is_finite() && *seconds > 0.0)
+                    .ok_or(USAGE)?;
+                options.interval = Some(Duration::from_secs_f64(interval));
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                println!("  -b, --bytes        show sizes in bytes");
+                println!("  -k, --kib          show sizes in KiB");
+                println!("  -m, --mib          show sizes in MiB");
+                println!("      --json         print JSON, always in bytes");
+                println!("  -s, --seconds N    print again every N seconds until ^C");
+                std::process::exit(0);
+            }
+            _ => return Err(format!("unknown argument '{}'\n{}", argument, USAGE).into()),
+        }
+    }
+    Ok(options)
+}
+
+fn print(
+    output: &mut impl Write,
+    status: &procfs::MemoryStatus,
+    options: &Options,
+) -> std::io::Result<()> {
+    let report = Report::new(status);
+    if options.json {
+        writeln!(output, "{:#}", free::to_json(&report))?;
+    } else {
+        free::print_table(output, &report, options.unit)?;
+    }
+    output.flush()
+}
+
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let procfs = procfs::ProcFS::new();
+    sys::pledge("stdio rpath")?;
+    sys::unveil(&procfs.path("memstat").to_string_lossy(), "r")?;
+    sys::lock_veil()?;
+    let options = parse_arguments()?;
+    let mut output = std::io::stdout().lock();
+
+    let interval = match options.interval {
+        Some(interval) => interval,
+        None => {
+            let status = procfs.memstat()?;
+            sys::pledge("stdio")?;
+            return Ok(print(&mut output, &status, &options)?);
+        }
+    };
+
+    // Keeps the rpath promise to re-read the node.
+    sys::catch_interrupt()?;
+    let mut next = Instant::now();
+    loop {
+        print(&mut output, &procfs.memstat()?, &options)?;
+        next += interval;
+        while !sys::interrupted() && Instant::now() < next {
+            std::thread::sleep((next - Instant::now()).min(Duration::from_millis(100)));
+        }
+        if sys::interrupted() {
+            return Ok(());
+        }
+        writeln!(output)?;
+    }
+}
+
+pub fn main() {
+    if let Err(error) = run() {
+        eprintln!("free: {}", error);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,114 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// The output for memstat nodes, compared with the files in fixtures/expected. "desktop" has the
+// numbers of a machine sitting at the desktop, "exhausted" those of one that ran out of physical
+// memory.
+
+use serenity::procfs::{MemoryStatus, ProcFS, PAGE_SIZE};
+
+use crate::{format_size, print_table, to_json, Report, Unit, Usage};
+
+fn status(fixture: &str) -> MemoryStatus {
+    ProcFS::with_root(format!(
+        "{}/fixtures/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        fixture
+    ))
+    .memstat()
+    .unwrap()
+}
+
+fn table(fixture: &str, unit: Unit) -> String {
+    let mut output = Vec::new();
+    print_table(&mut output, &Report::new(&status(fixture)), unit).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn prints_tables_in_every_unit() {
+    assert_eq!(
+        table("desktop", Unit::Human),
+        include_str!("fixtures/expected/desktop.txt")
+    );
+    assert_eq!(
+        table("desktop", Unit::Bytes),
+        include_str!("fixtures/expected/desktop-bytes.txt")
+    );
+    assert_eq!(
+        table("desktop", Unit::KiB),
+        include_str!("fixtures/expected/desktop-kib.txt")
+    );
+    assert_eq!(
+        table("desktop", Unit::MiB),
+        include_str!("fixtures/expected/desktop-mib.txt")
+    );
+    assert_eq!(
+        table("exhausted", Unit::Human),
+        include_str!("fixtures/expected/exhausted.txt")
+    );
+}
+
+#[test]
+fn prints_json_in_bytes() {
+    let json = format!("{:#}\n", to_json(&Report::new(&status("desktop"))));
+    assert_eq!(json, include_str!("fixtures/expected/desktop.json"));
+}
+
+#[test]
+fn adds_up_the_totals() {
+    let report = Report::new(&status("desktop"));
+    // The physical counters are in pages, the kernel heap ones in bytes.
+    assert_eq!(report.physical, Usage {
+        total: (109325 + 399478) * PAGE_SIZE,
+        used: 109325 * PAGE_SIZE,
+        free: 399478 * PAGE_SIZE,
+    });
+    assert_eq!(report.supervisor, Usage {
+        total: 512 * PAGE_SIZE,
+        used: 258 * PAGE_SIZE,
+        free: 254 * PAGE_SIZE,
+    });
+    assert_eq!(report.kernel_heap, Usage {
+        total: 18650176 + 4063680,
+        used: 18650176,
+        free: 4063680,
+    });
+    assert_eq!(report.committed, 1342 * PAGE_SIZE);
+    assert_eq!(report.uncommitted, 398136 * PAGE_SIZE);
+    // Free pages are either committed to a process or still left to promise.
+    assert_eq!(report.committed + report.uncommitted, report.physical.free);
+    assert_eq!(
+        (report.kmalloc_calls, report.kfree_calls),
+        (4821931, 4760402)
+    );
+
+    let report = Report::new(&status("exhausted"));
+    assert_eq!(report.physical.free, 0);
+    assert_eq!(report.physical.total, report.physical.used);
+    assert_eq!(report.supervisor.total, 2 * 1024 * 1024);
+}
+
+#[test]
+fn formats_sizes() {
+    assert_eq!(format_size(0, Unit::Human), "0 B");
+    assert_eq!(format_size(1023, Unit::Human), "1023 B");
+    assert_eq!(format_size(1024, Unit::Human), "1.0 KiB");
+    // Truncated, not rounded.
+    assert_eq!(format_size(1535, Unit::Human), "1.4 KiB");
+    assert_eq!(format_size(1536, Unit::Human), "1.5 KiB");
+    assert_eq!(format_size(1024 * 1024 - 1, Unit::Human), "1023.9 KiB");
+    assert_eq!(format_size(3 << 30, Unit::Human), "3.0 GiB");
+    // PiB is the largest unit.
+    assert_eq!(format_size(2048 << 50, Unit::Human), "2048.0 PiB");
+    assert_eq!(format_size(u64::MAX, Unit::Human), "16383.9 PiB");
+
+    assert_eq!(format_size(1536, Unit::Bytes), "1536");
+    assert_eq!(format_size(1536, Unit::KiB), "1");
+    assert_eq!(format_size(1023, Unit::KiB), "0");
+    assert_eq!(format_size(5 << 20, Unit::MiB), "5");
+    assert_eq!(format_size((5 << 20) - 1, Unit::MiB), "4");
+}
@@ -0,0 +1,101 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Prints the values a JSONPath query selects from a JSON document, one per line:
+//
+//     jpath '$[?@.purpose == "PS2 Keyboard"].call_count' /proc/interrupts
+//     cat /proc/all | jpath -p '$.processes[?@.uid == 0].name'
+
+use std::io::Read;
+
+use serenity::{json, sys};
+
+const USAGE: &str = "usage: jpath [-p] [-P] <query> [file]";
+
+struct Options {
+    pretty: bool,
+    pointers: bool,
+    query: String,
+    // Standard input if not given, or given as "-".
+    path: Option<String>,
+}
+
+fn parse_arguments() -> Result<Options, Box<dyn std::error::Error>> {
+    let mut options = Options {
+        pretty: false,
+        pointers: false,
+        query: String::new(),
+        path: None,
+    };
+    let mut positional = Vec::new();
+    for argument in std::env::args().skip(1) {
+        match argument.as_str() {
+            "-p" | "--pretty" => options.pretty = true,
+            "-P" | "--pointers" => options.pointers = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                println!("  -p, --pretty    print each value indented over several lines");
+                println!("  -P, --pointers  print the JSON Pointer of each value before it");
+                std::process::exit(0);
+            }
+            _ if argument.starts_with('-') && argument != "-" => {
+                return Err(format!("unknown option '{}'\n{}", argument, USAGE).into());
+            }
+            _ => positional.push(argument),
+        }
+    }
+    let mut positional = positional.into_iter();
+    options.query = positional.next().ok_or(USAGE)?;
+    options.path = positional.next().filter(|path| path != "-");
+    if positional.next().is_some() {
+        return Err(USAGE.into());
+    }
+    Ok(options)
+}
+
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    sys::pledge("stdio rpath")?;
+    let options = parse_arguments()?;
+    // Compile first, so a typo in the query is reported before waiting on stdin.
+    let query = json::query::compile(&options.query)?;
+
+    // The input file is all jpath needs to see, and reading stdin needs no paths at all.
+    if let Some(path) = &options.path {
+        sys::unveil(path, "r")?;
+    }
+    sys::lock_veil()?;
+
+    let mut input = String::new();
+    match &options.path {
+        Some(path) => {
+            std::fs::File::open(path)?.read_to_string(&mut input)?;
+        }
+        None => {
+            std::io::stdin().read_to_string(&mut input)?;
+        }
+    }
+    sys::pledge("stdio")?;
+    let document = json::parse(&input)?;
+
+    for (pointer, value) in query.select_with_pointers(&document) {
+        if options.pointers {
+            print!("{}\t", pointer);
+        }
+        if options.pretty {
+            println!("{:#}", value);
+        } else {
+            println!("{}", value);
+        }
+    }
+    Ok(())
+}
+
+pub fn main() {
+    if let Err(error) = run() {
+        eprintln!("jpath: {}", error);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,158 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// The summary, the table with a row per CPU, the JSON output and the feature check of lscpu.
+// Identical processors are shown once, with their features sorted into categories.
+
+pub mod summary;
+#[cfg(test)]
+mod tests;
+
+use std::io::Write;
+
+use serenity::json::{ArrayBuilder, ObjectBuilder, Value};
+use serenity::procfs::Processor;
+use summary::Group;
+
+fn print_field(
+    output: &mut impl Write,
+    name: &str,
+    value: impl std::fmt::Display,
+) -> std::io::Result<()> {
+    writeln!(output, "{:20}{}", format!("{}:", name), value)
+}
+
+pub fn print_summary(output: &mut impl Write, processors: &[Processor]) -> std::io::Result<()> {
+    print_field(output, "CPU(s)", processors.len())?;
+    let hypervisors = summary::hypervisors(processors);
+    if !hypervisors.is_empty() {
+        print_field(output, "Hypervisor vendor", hypervisors.join(", "))?;
+    }
+
+    for group in summary::group(processors) {
+        let processor = group.model;
+        writeln!(output)?;
+        print_field(output, "Vendor ID", &processor.vendor_id)?;
+        print_field(output, "Brand", &processor.brand)?;
+        print_field(output, "Family", processor.family)?;
+        print_field(output, "Model", processor.model)?;
+        print_field(output, "Stepping", processor.stepping)?;
+        print_field(output, "Type", processor.processor_type)?;
+        print_field(output, "CPU list", summary::format_list(&group.processors))?;
+        for (category, features) in summary::categorized_features(processor) {
+            if !features.is_empty() {
+                print_field(output, category.name(), features.join(" "))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn print_extended(output: &mut impl Write, processors: &[Processor]) -> std::io::Result<()> {
+    writeln!(
+        output,
+        "{:>3} {:>6} {:>5} {:>8}  {:12}  BRAND",
+        "CPU", "FAMILY", "MODEL", "STEPPING", "VENDOR"
+    )?;
+    for processor in processors {
+        writeln!(
+            output,
+            "{:>3} {:>6} {:>5} {:>8}  {:12}  {}",
+            processor.processor,
+            processor.family,
+            processor.model,
+            processor.stepping,
+            processor.vendor_id,
+            processor.brand
+        )?;
+    }
+    Ok(())
+}
+
+fn features_json(processor: &Processor) -> Value {
+    summary::categorized_features(processor)
+        .into_iter()
+        .fold(ObjectBuilder::new(), |object, (category, features)| {
+            object.insert(category.key(), features)
+        })
+        .build()
+}
+
+fn processor_json(processor: &Processor) -> ObjectBuilder {
+    ObjectBuilder::new()
+        .insert("vendor_id", processor.vendor_id.as_str())
+        .insert("brand", processor.brand.as_str())
+        .insert("family", processor.family)
+        .insert("model", processor.model)
+        .insert("stepping", processor.stepping)
+        .insert("type", processor.processor_type)
+        .insert("features", features_json(processor))
+}
+
+fn group_json(group: &Group) -> Value {
+    processor_json(group.model)
+        .insert("processors", group.processors.clone())
+        .build()
+}
+
+pub fn print_json(
+    output: &mut impl Write,
+    processors: &[Processor],
+    extended: bool,
+) -> std::io::Result<()> {
+    let value = if extended {
+        let array = processors.iter().map(|processor| {
+            processor_json(processor)
+                .insert("processor", processor.processor)
+                .insert_optional(
+                    "hypervisor_vendor_id",
+                    processor.hypervisor_vendor_id.as_deref(),
+                )
+                .build()
+        });
+        ArrayBuilder::new().extend(array).build()
+    } else {
+        let hypervisors = summary::hypervisors(processors);
+        ObjectBuilder::new()
+            .insert("cpus", processors.len())
+            .insert("hypervisor_vendor_ids", hypervisors)
+            .insert(
+                "groups",
+                ArrayBuilder::new().extend(summary::group(processors).iter().map(group_json)),
+            )
+            .build()
+    };
+    writeln!(output, "{:#}", value)
+}
+
+// Whether every processor has the flag, saying which ones lack it.
+pub fn check_feature(
+    output: &mut impl Write,
+    processors: &[Processor],
+    feature: &str,
+) -> std::io::Result<bool> {
+    let missing: Vec<u32> = processors
+        .iter()
+        .filter(|processor| !processor.features.iter().any(|flag| flag == feature))
+        .map(|processor| processor.processor)
+        .collect();
+    if missing.is_empty() {
+        writeln!(
+            output,
+            "{}: supported by all {} CPU(s)",
+            feature,
+            processors.len()
+        )?;
+    } else {
+        writeln!(
+            output,
+            "{}: not supported by CPU(s) {}",
+            feature,
+            summary::format_list(&missing)
+        )?;
+    }
+    Ok(missing.is_empty())
+}
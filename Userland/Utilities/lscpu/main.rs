@@ -4,59 +4,73 @@
  * SPDX-License-Identifier: BSD-2-Clause
  */
 
-use std::collections::HashMap;
+use lscpu::{check_feature, print_extended, print_json, print_summary};
+use serenity::{procfs, sys};
 
-use serenity::{json, sys};
+const USAGE: &str = "usage: lscpu [-e|--extended] [--json] [--features FLAG]";
 
-fn print_cpu_info(processor: &HashMap<String, json::Value>) {
-    let processor_id = processor.get("processor").unwrap();
-    let vendor_id = processor.get("vendor_id").unwrap();
-    let brand = processor.get("brand").unwrap();
-    let family = processor.get("family").unwrap();
-    let model = processor.get("model").unwrap();
-    let stepping = processor.get("stepping").unwrap();
-    let r#type = processor.get("type").unwrap();
-    let features = processor.get("features").unwrap();
+struct Options {
+    extended: bool,
+    json: bool,
+    feature: Option<String>,
+}
 
-    println!("CPU {}:", processor_id.to_string());
-    println!("\tVendor ID: {}", vendor_id.to_string());
-    if processor.contains_key("hypervisor_vendor_id") {
-        let hypervisor_vendor_id = processor.get("hypervisor_vendor_id").unwrap();
-        println!(
-            "\tHypervisor Vendor ID: {}",
-            hypervisor_vendor_id.to_string(),
-        );
+fn parse_arguments() -> Result<Options, Box<dyn std::error::Error>> {
+    let mut options = Options {
+        extended: false,
+        json: false,
+        feature: None,
+    };
+    let mut arguments = std::env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "-e" | "--extended" => options.extended = true,
+            "--json" => options.json = true,
+            "--features" => options.feature = Some(arguments.next().ok_or(USAGE)?),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                println!("  -e, --extended   print one row per CPU instead of a summary");
+                println!("      --json       print JSON instead of text");
+                println!("      --features FLAG");
+                println!("                   exit with 0 if every CPU supports FLAG, 1 otherwise");
+                std::process::exit(0);
+            }
+            _ => return Err(format!("unknown argument '{}'\n{}", argument, USAGE).into()),
+        }
     }
-    println!("\tBrand: {}", brand.to_string());
-    println!("\tFamily: {}", family.to_string());
-    println!("\tModel: {}", model.to_string());
-    println!("\tStepping: {}", stepping.to_string());
-    println!("\tType: {}", r#type.to_string());
-    println!(
-        "\tFeatures: {}",
-        features
-            .as_array()
-            .iter()
-            .map(|value| value.to_string())
-            .collect::<Vec<_>>()
-            .join(" ")
-    );
+    Ok(options)
 }
 
-pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+fn run() -> Result<bool, Box<dyn std::error::Error>> {
+    let procfs = procfs::ProcFS::new();
     sys::pledge("stdio rpath")?;
-    sys::unveil("/proc/cpuinfo", "r")?;
+    sys::unveil(&procfs.path("cpuinfo").to_string_lossy(), "r")?;
     sys::lock_veil()?;
-    let json = json::parse(std::fs::read_to_string("/proc/cpuinfo")?.as_str())?;
+    let options = parse_arguments()?;
+    let processors = procfs.cpuinfo()?;
     sys::pledge("stdio")?;
-    let processors = json.as_array();
-    let mut it = processors.iter().peekable();
-    while let Some(value) = it.next() {
-        let processor = value.as_object();
-        print_cpu_info(processor);
-        if !it.peek().is_none() {
-            println!();
+
+    let mut output = std::io::stdout().lock();
+    if let Some(feature) = &options.feature {
+        return Ok(check_feature(&mut output, &processors, feature)?);
+    }
+    if options.json {
+        print_json(&mut output, &processors, options.extended)?;
+    } else if options.extended {
+        print_extended(&mut output, &processors)?;
+    } else {
+        print_summary(&mut output, &processors)?;
+    }
+    Ok(true)
+}
+
+pub fn main() {
+    match run() {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(error) => {
+            eprintln!("lscpu: {}", error);
+            std::process::exit(1);
         }
     }
-    Ok(())
 }
@@ -0,0 +1,194 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// What lscpu shows, worked out from the parsed processors alone.
+
+use std::collections::BTreeSet;
+
+use serenity::procfs::Processor;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    Simd,
+    Virtualization,
+    Security,
+    Other,
+}
+
+impl Category {
+    pub const ALL: [Category; 4] = [
+        Category::Simd,
+        Category::Virtualization,
+        Category::Security,
+        Category::Other,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Category::Simd => "SIMD",
+            Category::Virtualization => "Virtualization",
+            Category::Security => "Security",
+            Category::Other => "Other",
+        }
+    }
+
+    // The key used in the JSON output.
+    pub fn key(self) -> &'static str {
+        match self {
+            Category::Simd => "simd",
+            Category::Virtualization => "virtualization",
+            Category::Security => "security",
+            Category::Other => "other",
+        }
+    }
+}
+
+// The flags use the names the kernel gives them in Arch/x86/common/CPUID.cpp.
+const SIMD: &[&str] = &[
+    "mmx",
+    "mmxext",
+    "3dnow",
+    "3dnowext",
+    "3dnowprefetch",
+    "sse",
+    "sse2",
+    "sse3",
+    "ssse3",
+    "sse4_1",
+    "sse4_2",
+    "sse4a",
+    "misalignsse",
+    "avx",
+    "avx2",
+    "fma",
+    "fma4",
+    "f16c",
+    "xop",
+    "gfni",
+    "vaes",
+    "vpclmulqdq",
+];
+const VIRTUALIZATION: &[&str] = &["vmx", "svm", "hypervisor"];
+const SECURITY: &[&str] = &[
+    "nx",
+    "smep",
+    "smap",
+    "umip",
+    "pku",
+    "ospke",
+    "pks",
+    "cet_ss",
+    "cet_ibt",
+    "spec_ctrl",
+    "stibp",
+    "ssbd",
+    "l1d_flush",
+    "md_clear",
+    "srbds_ctrl",
+    "tsx_force_abort",
+    "rtm_always_abort",
+    "ia32_arch_capabilities",
+];
+
+pub fn categorize(feature: &str) -> Category {
+    if SIMD.contains(&feature) || feature.starts_with("avx512_") || feature.starts_with("amx_") {
+        Category::Simd
+    } else if VIRTUALIZATION.contains(&feature) {
+        Category::Virtualization
+    } else if SECURITY.contains(&feature) {
+        Category::Security
+    } else {
+        Category::Other
+    }
+}
+
+// A processor's features sorted by name, in the order of Category::ALL.
+pub fn categorized_features(processor: &Processor) -> Vec<(Category, Vec<&str>)> {
+    let features: BTreeSet<&str> = processor.features.iter().map(String::as_str).collect();
+    Category::ALL
+        .iter()
+        .map(|category| {
+            let members = features
+                .iter()
+                .copied()
+                .filter(|feature| categorize(feature) == *category)
+                .collect();
+            (*category, members)
+        })
+        .collect()
+}
+
+// Processors that only differ in their number.
+#[derive(Clone, Debug)]
+pub struct Group<'a> {
+    pub model: &'a Processor,
+    pub processors: Vec<u32>,
+}
+
+fn identical(a: &Processor, b: &Processor) -> bool {
+    let features = |processor: &Processor| -> BTreeSet<String> {
+        processor.features.iter().cloned().collect()
+    };
+    a.vendor_id == b.vendor_id
+        && a.brand == b.brand
+        && a.family == b.family
+        && a.model == b.model
+        && a.stepping == b.stepping
+        && a.processor_type == b.processor_type
+        && features(a) == features(b)
+}
+
+// Groups in the order their first processor appears, like the big and little cores of a hybrid
+// part.
+pub fn group(processors: &[Processor]) -> Vec<Group<'_>> {
+    let mut groups: Vec<Group> = Vec::new();
+    for processor in processors {
+        match groups
+            .iter_mut()
+            .find(|group| identical(group.model, processor))
+        {
+            Some(group) => group.processors.push(processor.processor),
+            None => groups.push(Group {
+                model: processor,
+                processors: vec![processor.processor],
+            }),
+        }
+    }
+    groups
+}
+
+// The hypervisor vendors in use, usually one or none.
+pub fn hypervisors(processors: &[Processor]) -> Vec<&str> {
+    let vendors: BTreeSet<&str> = processors
+        .iter()
+        .filter_map(|processor| processor.hypervisor_vendor_id.as_deref())
+        .collect();
+    vendors.into_iter().collect()
+}
+
+// Processor numbers as ranges, like "0-3,6".
+pub fn format_list(numbers: &[u32]) -> String {
+    let mut numbers = numbers.to_vec();
+    numbers.sort_unstable();
+    numbers.dedup();
+    let mut ranges: Vec<String> = Vec::new();
+    let mut index = 0;
+    while index < numbers.len() {
+        let start = numbers[index];
+        let mut end = start;
+        while index + 1 < numbers.len() && numbers[index + 1] == end + 1 {
+            index += 1;
+            end = numbers[index];
+        }
+        if start == end {
+            ranges.push(start.to_string());
+        } else {
+            ranges.push(format!("{}-{}", start, end));
+        }
+        index += 1;
+    }
+    ranges.join(",")
+}
@@ -0,0 +1,201 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// The output for a cpuinfo node with the members ProcFSCPUInformation writes, for a four-CPU
+// KVM guest, compared with the files in fixtures/expected.
+
+use serenity::procfs::{ProcFS, Processor};
+
+use crate::summary::{self, Category};
+use crate::{check_feature, print_extended, print_json, print_summary};
+
+fn processors(fixture: &str) -> Vec<Processor> {
+    ProcFS::with_root(format!(
+        "{}/fixtures/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        fixture
+    ))
+    .cpuinfo()
+    .unwrap()
+}
+
+fn render(
+    print: impl Fn(&mut Vec<u8>, &[Processor]) -> std::io::Result<()>,
+    processors: &[Processor],
+) -> String {
+    let mut output = Vec::new();
+    print(&mut output, processors).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+// The kvm processors, with AVX2 taken from the last two.
+fn mixed() -> Vec<Processor> {
+    let mut processors = processors("kvm");
+    for processor in &mut processors[2..] {
+        processor.features.retain(|feature| feature != "avx2");
+    }
+    processors
+}
+
+fn print_summary_json(output: &mut Vec<u8>, processors: &[Processor]) -> std::io::Result<()> {
+    print_json(output, processors, false)
+}
+
+fn print_extended_json(output: &mut Vec<u8>, processors: &[Processor]) -> std::io::Result<()> {
+    print_json(output, processors, true)
+}
+
+#[test]
+fn prints_summaries() {
+    assert_eq!(
+        render(print_summary, &processors("kvm")),
+        include_str!("fixtures/expected/kvm.txt")
+    );
+    // Processors that differ are shown apart.
+    assert_eq!(
+        render(print_summary, &mixed()),
+        include_str!("fixtures/expected/kvm-mixed.txt")
+    );
+}
+
+#[test]
+fn prints_extended_tables() {
+    assert_eq!(
+        render(print_extended, &processors("kvm")),
+        include_str!("fixtures/expected/kvm-extended.txt")
+    );
+}
+
+#[test]
+fn prints_json() {
+    assert_eq!(
+        render(print_summary_json, &processors("kvm")),
+        include_str!("fixtures/expected/kvm.json")
+    );
+    assert_eq!(
+        render(print_extended_json, &processors("kvm")),
+        include_str!("fixtures/expected/kvm-extended.json")
+    );
+    assert_eq!(
+        render(print_summary_json, &mixed()),
+        include_str!("fixtures/expected/kvm-mixed.json")
+    );
+}
+
+fn feature_check(processors: &[Processor], feature: &str) -> (bool, String) {
+    let mut output = Vec::new();
+    let supported = check_feature(&mut output, processors, feature).unwrap();
+    (supported, String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn checks_features() {
+    let mut processors = processors("kvm");
+    assert_eq!(
+        feature_check(&processors, "avx2"),
+        (true, "avx2: supported by all 4 CPU(s)\n".to_string())
+    );
+    assert_eq!(
+        feature_check(&processors, "avx512_f"),
+        (false, "avx512_f: not supported by CPU(s) 0-3\n".to_string())
+    );
+    // Flags are matched whole.
+    assert!(feature_check(&processors, "avx").0);
+    assert!(!feature_check(&processors, "av").0);
+
+    for processor in &mut processors[1..3] {
+        processor.features.retain(|feature| feature != "avx2");
+    }
+    assert_eq!(
+        feature_check(&processors, "avx2"),
+        (false, "avx2: not supported by CPU(s) 1-2\n".to_string())
+    );
+}
+
+#[test]
+fn categorizes_features() {
+    for feature in [
+        "sse4_1",
+        "avx2",
+        "fma4",
+        "avx512_vnni",
+        "amx_tile",
+        "vpclmulqdq",
+    ] {
+        assert_eq!(summary::categorize(feature), Category::Simd, "{}", feature);
+    }
+    for feature in ["vmx", "svm", "hypervisor"] {
+        assert_eq!(
+            summary::categorize(feature),
+            Category::Virtualization,
+            "{}",
+            feature
+        );
+    }
+    for feature in [
+        "nx",
+        "smep",
+        "md_clear",
+        "spec_ctrl",
+        "ia32_arch_capabilities",
+    ] {
+        assert_eq!(
+            summary::categorize(feature),
+            Category::Security,
+            "{}",
+            feature
+        );
+    }
+    // Only the kernel's own names are known.
+    for feature in ["fpu", "hybrid", "sse4.1", "pni"] {
+        assert_eq!(summary::categorize(feature), Category::Other, "{}", feature);
+    }
+
+    let processor = &processors("kvm")[0];
+    let categorized = summary::categorized_features(processor);
+    let categories: Vec<Category> = categorized.iter().map(|(category, _)| *category).collect();
+    assert_eq!(categories, Category::ALL);
+    assert_eq!(categorized[1].1, ["hypervisor"], "virtualization features");
+    // Every flag is in exactly one category, in order of name.
+    let mut all: Vec<&str> = categorized
+        .iter()
+        .flat_map(|(_, features)| features.iter().copied())
+        .collect();
+    assert_eq!(all.len(), processor.features.len());
+    for (_, features) in &categorized {
+        assert!(features.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+    all.sort_unstable();
+    let mut features: Vec<&str> = processor.features.iter().map(String::as_str).collect();
+    features.sort_unstable();
+    assert_eq!(all, features);
+}
+
+#[test]
+fn groups_identical_processors() {
+    let lists = |processors: &[Processor]| -> Vec<Vec<u32>> {
+        summary::group(processors)
+            .into_iter()
+            .map(|group| group.processors)
+            .collect()
+    };
+    assert_eq!(lists(&processors("kvm")), [vec![0, 1, 2, 3]]);
+    assert_eq!(lists(&mixed()), [vec![0, 1], vec![2, 3]]);
+    // The order of the flags doesn't matter.
+    let mut processors = processors("kvm");
+    processors[1].features.reverse();
+    processors[3].stepping += 1;
+    assert_eq!(lists(&processors), [vec![0, 1, 2], vec![3]]);
+    assert_eq!(summary::group(&[]).len(), 0);
+}
+
+#[test]
+fn formats_processor_lists() {
+    assert_eq!(summary::format_list(&[]), "");
+    assert_eq!(summary::format_list(&[3]), "3");
+    assert_eq!(summary::format_list(&[0, 1, 2, 3]), "0-3");
+    assert_eq!(summary::format_list(&[6, 0, 1, 2, 2, 4]), "0-2,4,6");
+}
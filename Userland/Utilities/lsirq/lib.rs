@@ -0,0 +1,136 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Filters and sorts the interrupt handlers, and prints them as a table with a column per CPU, as
+// JSON or as CSV. The rates shown by --watch are computed in watch.rs.
+
+#[cfg(test)]
+mod tests;
+pub mod watch;
+
+use std::cmp::Reverse;
+use std::io::Write;
+
+use serenity::json::{ArrayBuilder, ObjectBuilder};
+use serenity::procfs::InterruptHandler;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortKey {
+    // The order the kernel lists the handlers in.
+    None,
+    // Busiest first.
+    Count,
+    Line,
+    Controller,
+}
+
+// One count per CPU. Without per-CPU counts, which the kernel doesn't report yet, the total is
+// shown as CPU0.
+pub fn call_counts(handler: &InterruptHandler) -> Vec<u64> {
+    match &handler.per_cpu_call_counts {
+        Some(counts) => counts.clone(),
+        None => vec![handler.call_count],
+    }
+}
+
+// Keeps the handlers whose controller contains `controller`, ignoring case, and sorts them.
+pub fn select(
+    mut handlers: Vec<InterruptHandler>,
+    sort: SortKey,
+    controller: Option<&str>,
+) -> Vec<InterruptHandler> {
+    if let Some(controller) = controller {
+        let controller = controller.to_lowercase();
+        handlers.retain(|handler| handler.controller.to_lowercase().contains(&controller));
+    }
+    // Sorting is stable, so handlers that compare equal stay in kernel order.
+    match sort {
+        SortKey::None => {}
+        SortKey::Count => handlers.sort_by_key(|handler| Reverse(handler.call_count)),
+        SortKey::Line => handlers.sort_by_key(|handler| handler.interrupt_line),
+        SortKey::Controller => handlers.sort_by(|a, b| a.controller.cmp(&b.controller)),
+    }
+    handlers
+}
+
+fn cpu_count(handlers: &[InterruptHandler]) -> usize {
+    handlers
+        .iter()
+        .map(|handler| call_counts(handler).len())
+        .max()
+        .unwrap_or(1)
+}
+
+pub fn print_table(output: &mut impl Write, handlers: &[InterruptHandler]) -> std::io::Result<()> {
+    let cpu_count = cpu_count(handlers);
+    let mut header = String::from("      ");
+    for cpu in 0..cpu_count {
+        header.push_str(&format!("{:10} ", format!("CPU{}", cpu)));
+    }
+    writeln!(output, "{}", header.trim_end())?;
+
+    for handler in handlers {
+        write!(output, "{:>4}: ", handler.interrupt_line)?;
+        let counts = call_counts(handler);
+        for cpu in 0..cpu_count {
+            let count = counts.get(cpu).map(u64::to_string).unwrap_or_default();
+            write!(output, "{:10} ", count)?;
+        }
+        writeln!(output, "{:10}  {}", handler.controller, handler.purpose)?;
+    }
+    Ok(())
+}
+
+pub fn print_json(output: &mut impl Write, handlers: &[InterruptHandler]) -> std::io::Result<()> {
+    let array = handlers.iter().map(|handler| {
+        ObjectBuilder::new()
+            .insert("interrupt_line", handler.interrupt_line)
+            .insert("purpose", handler.purpose.as_str())
+            .insert("controller", handler.controller.as_str())
+            .insert("device_sharing", handler.device_sharing)
+            .insert("call_count", handler.call_count)
+            .insert(
+                "call_counts",
+                ArrayBuilder::new().extend(call_counts(handler)),
+            )
+            .build()
+    });
+    writeln!(output, "{:#}", ArrayBuilder::new().extend(array).build())
+}
+
+// Fields are quoted as RFC 4180 describes, when they need to be.
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn print_csv(output: &mut impl Write, handlers: &[InterruptHandler]) -> std::io::Result<()> {
+    let cpu_count = cpu_count(handlers);
+    let cpu_columns: Vec<String> = (0..cpu_count).map(|cpu| format!("cpu{}", cpu)).collect();
+    writeln!(
+        output,
+        "interrupt_line,{},controller,purpose",
+        cpu_columns.join(",")
+    )?;
+    for handler in handlers {
+        let counts = call_counts(handler);
+        let counts: Vec<String> = (0..cpu_count)
+            .map(|cpu| counts.get(cpu).map(u64::to_string).unwrap_or_default())
+            .collect();
+        writeln!(
+            output,
+            "{},{},{},{}",
+            handler.interrupt_line,
+            counts.join(","),
+            csv_field(&handler.controller),
+            csv_field(&handler.purpose)
+        )?;
+    }
+    Ok(())
+}
@@ -4,30 +4,162 @@
  * SPDX-License-Identifier: BSD-2-Clause
  */
 
-use serenity::{json, sys};
+use std::io::Write;
+use std::time::{Duration, Instant};
 
-pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+use lsirq::{print_csv, print_json, print_table, select, watch, SortKey};
+use serenity::{procfs, sys};
+
+const USAGE: &str =
+    "usage: lsirq [-s count|line|controller] [-c controller] [--json|--csv|--watch seconds]";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Table,
+    Json,
+    Csv,
+}
+
+struct Options {
+    // Unset, the table keeps kernel order and --watch puts the busiest handlers first.
+    sort: Option<SortKey>,
+    controller: Option<String>,
+    format: Format,
+    watch: Option<Duration>,
+}
+
+fn parse_arguments() -> Result<Options, Box<dyn std::error::Error>> {
+    let mut options = Options {
+        sort: None,
+        controller: None,
+        format: Format::Table,
+        watch: None,
+    };
+    let mut arguments = std::env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "-s" | "--sort" => {
+                options.sort = Some(match arguments.next().as_deref() {
+                    Some("count") => SortKey::Count,
+                    Some("line") => SortKey::Line,
+                    Some("controller") => SortKey::Controller,
+                    _ => return Err(USAGE.into()),
+                })
+            }
+            "-c" | "--controller" => options.controller = Some(arguments.next().ok_or(USAGE)?),
+            "--json" => options.format = Format::Json,
+            "--csv" => options.format = Format::Csv,
+            "-w" | "--watch" => {
+                let interval = arguments
+                    .next()
+                    .and_then(|seconds| seconds.parse::<f64>().ok())
+                    .filter(|seconds| seconds.is_finite() && *seconds > 0.0)
+                    .ok_or(USAGE)?;
+                options.watch = Some(Duration::from_secs_f64(interval));
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                println!("  -s, --sort KEY         sort by call count, line or controller");
+                println!("  -c, --controller NAME  only show controllers whose name contains NAME");
+                println!("      --json             print a JSON array instead of a table");
+                println!("      --csv              print comma-separated values");
+                println!("  -w, --watch SECONDS    redraw interrupt rates every SECONDS until ^C");
+                std::process::exit(0);
+            }
+            _ => return Err(format!("unknown argument '{}'\n{}", argument, USAGE).into()),
+        }
+    }
+    if options.watch.is_some() && options.format != Format::Table {
+        return Err("--watch can't be combined with --json or --csv".into());
+    }
+    Ok(options)
+}
+
+// Hides the cursor while redrawing, and shows it again however the watch ends.
+struct HiddenCursor;
+
+impl HiddenCursor {
+    fn hide(output: &mut impl Write) -> std::io::Result<Self> {
+        write!(output, "\x1b[?25l")?;
+        Ok(Self)
+    }
+}
+
+impl Drop for HiddenCursor {
+    fn drop(&mut self) {
+        let mut output = std::io::stdout();
+        let _ = writeln!(output, "\x1b[?25h");
+        let _ = output.flush();
+    }
+}
+
+// Redraws the rates in place until SIGINT. Keeps the rpath promise to re-read the node.
+fn watch(
+    procfs: &procfs::ProcFS,
+    options: &Options,
+    interval: Duration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    sys::catch_interrupt()?;
+    let sort = options.sort.unwrap_or(SortKey::Count);
+    let controller = options.controller.as_deref();
+    let mut output = std::io::stdout().lock();
+    let _cursor = HiddenCursor::hide(&mut output)?;
+
+    // The rates are sorted, not the counts they come from.
+    let mut previous = select(procfs.interrupts()?, SortKey::None, controller);
+    let mut previous_time = Instant::now();
+    loop {
+        let deadline = previous_time + interval;
+        while !sys::interrupted() && Instant::now() < deadline {
+            std::thread::sleep((deadline - Instant::now()).min(Duration::from_millis(100)));
+        }
+        if sys::interrupted() {
+            return Ok(());
+        }
+
+        let current = select(procfs.interrupts()?, SortKey::None, controller);
+        let now = Instant::now();
+        let mut rates = watch::compute_rates(&previous, &current, now - previous_time);
+        watch::sort_rates(&mut rates, sort);
+        previous = current;
+        previous_time = now;
+
+        // Move home and clear the screen, then draw the table.
+        write!(output, "\x1b[H\x1b[2J")?;
+        writeln!(output, "Every {:.1}s, ^C to quit\n", interval.as_secs_f64())?;
+        watch::print_rates(&mut output, &rates)?;
+        output.flush()?;
+    }
+}
+
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let procfs = procfs::ProcFS::new();
     sys::pledge("stdio rpath")?;
-    sys::unveil("/proc/interrupts", "r")?;
+    sys::unveil(&procfs.path("interrupts").to_string_lossy(), "r")?;
     sys::lock_veil()?;
-    let json = json::parse(std::fs::read_to_string("/proc/interrupts")?.as_str())?;
+    let options = parse_arguments()?;
+    if let Some(interval) = options.watch {
+        return watch(&procfs, &options, interval);
+    }
+    let handlers = select(
+        procfs.interrupts()?,
+        options.sort.unwrap_or(SortKey::None),
+        options.controller.as_deref(),
+    );
     sys::pledge("stdio")?;
-    println!("      CPU0");
-
-    for value in json.as_array() {
-        let handler = value.as_object();
-        let purpose = handler.get("purpose").unwrap();
-        let interrupt_line = handler.get("interrupt_line").unwrap();
-        let controller = handler.get("controller").unwrap();
-        let call_count = handler.get("call_count").unwrap();
-
-        println!(
-            "{:>4}: {:10} {:10}  {:30}",
-            interrupt_line.to_string(),
-            call_count.to_string(),
-            controller.to_string(),
-            purpose.to_string()
-        );
+
+    let mut output = std::io::stdout().lock();
+    match options.format {
+        Format::Table => print_table(&mut output, &handlers)?,
+        Format::Json => print_json(&mut output, &handlers)?,
+        Format::Csv => print_csv(&mut output, &handlers)?,
     }
     Ok(())
 }
+
+pub fn main() {
+    if let Err(error) = run() {
+        eprintln!("lsirq: {}", error);
+        std::process::exit(1);
+    }
+}
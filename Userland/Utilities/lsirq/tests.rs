@@ -0,0 +1,282 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// The output for readings of /proc/interrupts, compared with the files in fixtures/expected.
+// "uniprocessor" has only the members ProcFSInterrupts writes, so calls are counted in total.
+// "smp" is synthetic: no kernel counts calls per CPU yet, so it assumes a "per_cpu_call_counts"
+// array on each handler, which is what InterruptHandler reads.
+
+use std::time::Duration;
+
+use serenity::procfs::{InterruptHandler, ProcFS};
+
+use crate::watch::{compute_rates, print_rates, sort_rates, Rate};
+use crate::{csv_field, print_csv, print_json, print_table, select, SortKey};
+
+fn handlers(fixture: &str) -> Vec<InterruptHandler> {
+    ProcFS::with_root(format!(
+        "{}/fixtures/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        fixture
+    ))
+    .interrupts()
+    .unwrap()
+}
+
+fn render(
+    print: fn(&mut Vec<u8>, &[InterruptHandler]) -> std::io::Result<()>,
+    handlers: &[InterruptHandler],
+) -> String {
+    let mut output = Vec::new();
+    print(&mut output, handlers).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn prints_tables() {
+    let uniprocessor = handlers("uniprocessor");
+    assert_eq!(
+        render(print_table, &uniprocessor),
+        include_str!("fixtures/expected/uniprocessor.txt")
+    );
+    // CPUs a handler has no count for are left blank.
+    assert_eq!(
+        render(print_table, &handlers("smp")),
+        include_str!("fixtures/expected/smp.txt")
+    );
+    assert_eq!(
+        render(print_table, &[]),
+        include_str!("fixtures/expected/empty.txt")
+    );
+}
+
+#[test]
+fn prints_json() {
+    assert_eq!(
+        render(print_json, &handlers("uniprocessor")),
+        include_str!("fixtures/expected/uniprocessor.json")
+    );
+    assert_eq!(
+        render(print_json, &handlers("smp")),
+        include_str!("fixtures/expected/smp.json")
+    );
+}
+
+#[test]
+fn prints_csv() {
+    assert_eq!(
+        render(print_csv, &handlers("uniprocessor")),
+        include_str!("fixtures/expected/uniprocessor.csv")
+    );
+    assert_eq!(
+        render(print_csv, &handlers("smp")),
+        include_str!("fixtures/expected/smp.csv")
+    );
+}
+
+#[test]
+fn quotes_csv_fields_that_need_it() {
+    assert_eq!(csv_field("IOAPIC"), "IOAPIC");
+    assert_eq!(csv_field("NVMe, queue 1"), "\"NVMe, queue 1\"");
+    assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+}
+
+fn lines(handlers: &[InterruptHandler]) -> Vec<u32> {
+    handlers
+        .iter()
+        .map(|handler| handler.interrupt_line)
+        .collect()
+}
+
+#[test]
+fn sorts_handlers() {
+    let uniprocessor = handlers("uniprocessor");
+    assert_eq!(lines(&select(uniprocessor.clone(), SortKey::None, None)), [
+        0, 1, 8, 11, 12, 14, 15
+    ]);
+    assert_eq!(
+        lines(&select(uniprocessor.clone(), SortKey::Count, None)),
+        [0, 14, 11, 12, 1, 15, 8]
+    );
+    assert_eq!(lines(&select(uniprocessor, SortKey::Line, None)), [
+        0, 1, 8, 11, 12, 14, 15
+    ]);
+    // Ties keep kernel order.
+    assert_eq!(
+        lines(&select(handlers("smp"), SortKey::Controller, None)),
+        [0, 1, 11, 24, 25, 26]
+    );
+    let mut reversed = handlers("smp");
+    reversed.reverse();
+    assert_eq!(lines(&select(reversed, SortKey::Controller, None)), [
+        11, 1, 0, 25, 24, 26
+    ]);
+    assert_eq!(
+        render(print_table, &select(handlers("smp"), SortKey::Count, None)),
+        include_str!("fixtures/expected/smp-by-count.txt")
+    );
+}
+
+#[test]
+fn filters_by_controller() {
+    // Any part of the name, ignoring case.
+    assert_eq!(
+        lines(&select(handlers("smp"), SortKey::None, Some("msi"))),
+        [24, 25, 26]
+    );
+    assert_eq!(
+        lines(&select(handlers("smp"), SortKey::None, Some("MSI-X"))),
+        [26]
+    );
+    assert!(select(handlers("uniprocessor"), SortKey::None, Some("IOAPIC")).is_empty());
+    assert_eq!(
+        render(
+            print_csv,
+            &select(handlers("smp"), SortKey::Count, Some("msi")),
+        ),
+        include_str!("fixtures/expected/smp-msi-by-count.csv")
+    );
+}
+
+fn handler(
+    interrupt_line: u32,
+    controller: &str,
+    purpose: &str,
+    call_count: u64,
+) -> InterruptHandler {
+    InterruptHandler {
+        purpose: purpose.to_string(),
+        interrupt_line,
+        controller: controller.to_string(),
+        cpu_handler: 0,
+        device_sharing: 0,
+        call_count,
+        per_cpu_call_counts: None,
+    }
+}
+
+fn deltas(rates: &[Rate]) -> Vec<(u32, &str, u64)> {
+    rates
+        .iter()
+        .map(|rate| (rate.interrupt_line, rate.purpose.as_str(), rate.delta))
+        .collect()
+}
+
+#[test]
+fn computes_rates_from_counter_deltas() {
+    let previous = [
+        handler(0, "IOAPIC", "PIT", 100),
+        handler(1, "IOAPIC", "PS2", 7),
+    ];
+    let current = [
+        handler(0, "IOAPIC", "PIT", 600),
+        handler(1, "IOAPIC", "PS2", 7),
+    ];
+    let rates = compute_rates(&previous, &current, Duration::from_millis(500));
+    assert_eq!(deltas(&rates), [(0, "PIT", 500), (1, "PS2", 0)]);
+    assert_eq!(rates[0].per_second, 1000.0);
+    assert_eq!(rates[0].call_count, 600);
+    assert_eq!(rates[1].per_second, 0.0);
+}
+
+#[test]
+fn computes_rates_for_new_and_vanished_handlers() {
+    let previous = [
+        handler(0, "IOAPIC", "PIT", 100),
+        handler(11, "IOAPIC", "E1000", 50),
+    ];
+    let current = [
+        handler(0, "IOAPIC", "PIT", 110),
+        handler(14, "IOAPIC", "IDE", 30),
+    ];
+    let rates = compute_rates(&previous, &current, Duration::from_secs(1));
+    // The new handler counts from zero, and the vanished one is left out.
+    assert_eq!(deltas(&rates), [(0, "PIT", 10), (14, "IDE", 30)]);
+}
+
+#[test]
+fn tells_handlers_sharing_a_line_apart() {
+    let previous = [
+        handler(11, "IOAPIC", "E1000", 10),
+        handler(11, "IOAPIC", "AHCI", 1000),
+    ];
+    let current = [
+        handler(11, "IOAPIC", "AHCI", 1001),
+        handler(11, "IOAPIC", "E1000", 20),
+    ];
+    let rates = compute_rates(&previous, &current, Duration::from_secs(1));
+    assert_eq!(deltas(&rates), [(11, "AHCI", 1), (11, "E1000", 10)]);
+}
+
+#[test]
+fn computes_no_rate_without_elapsed_time() {
+    let previous = [handler(0, "IOAPIC", "PIT", 100)];
+    let current = [handler(0, "IOAPIC", "PIT", 150)];
+    let rates = compute_rates(&previous, &current, Duration::ZERO);
+    assert_eq!(deltas(&rates), [(0, "PIT", 50)]);
+    assert_eq!(rates[0].per_second, 0.0);
+}
+
+#[test]
+fn computes_rates_across_wraparound() {
+    // The kernel's counts are 32 bits wide.
+    let previous = [handler(0, "IOAPIC", "PIT", 4294967290)];
+    let current = [handler(0, "IOAPIC", "PIT", 5)];
+    let rates = compute_rates(&previous, &current, Duration::from_secs(1));
+    assert_eq!(deltas(&rates), [(0, "PIT", 11)]);
+    assert_eq!(rates[0].per_second, 11.0);
+
+    // A count that can't have wrapped belongs to a handler that was replaced.
+    let previous = [handler(0, "IOAPIC", "PIT", 1 << 40)];
+    let rates = compute_rates(&previous, &current, Duration::from_secs(1));
+    assert_eq!(deltas(&rates), [(0, "PIT", 5)]);
+}
+
+#[test]
+fn sorts_rates() {
+    let previous = [
+        handler(14, "IOAPIC", "IDE", 0),
+        handler(0, "i8259", "PIT", 0),
+        handler(1, "IOAPIC", "PS2", 0),
+        handler(11, "IOAPIC", "E1000", 0),
+    ];
+    let current = [
+        handler(14, "IOAPIC", "IDE", 20),
+        handler(0, "i8259", "PIT", 100),
+        handler(1, "IOAPIC", "PS2", 3),
+        handler(11, "IOAPIC", "E1000", 20),
+    ];
+    let rates = compute_rates(&previous, &current, Duration::from_secs(1));
+    let order = |sort| {
+        let mut rates = rates.clone();
+        sort_rates(&mut rates, sort);
+        rates
+            .iter()
+            .map(|rate| rate.interrupt_line)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(order(SortKey::None), [14, 0, 1, 11]);
+    // By the calls since the previous reading, not the totals. Ties stay in kernel order.
+    assert_eq!(order(SortKey::Count), [0, 14, 11, 1]);
+    assert_eq!(order(SortKey::Line), [0, 1, 11, 14]);
+    assert_eq!(order(SortKey::Controller), [14, 1, 11, 0]);
+}
+
+#[test]
+fn prints_rates() {
+    let previous = handlers("smp");
+    let mut current = previous.clone();
+    for (index, handler) in current.iter_mut().enumerate() {
+        handler.call_count += 10 * index as u64;
+    }
+    let mut rates = compute_rates(&previous, &current, Duration::from_secs(2));
+    sort_rates(&mut rates, SortKey::Count);
+    let mut output = Vec::new();
+    print_rates(&mut output, &rates).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(output, include_str!("fixtures/expected/smp-rates.txt"));
+}
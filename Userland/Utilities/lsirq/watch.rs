@@ -0,0 +1,106 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Interrupt rates between two readings of /proc/interrupts, for --watch, and the table they are
+// shown in.
+
+use std::cmp::Reverse;
+use std::io::Write;
+use std::time::Duration;
+
+use serenity::procfs::InterruptHandler;
+
+use crate::SortKey;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rate {
+    pub interrupt_line: u32,
+    pub controller: String,
+    pub purpose: String,
+    // Calls since the previous reading.
+    pub delta: u64,
+    pub per_second: f64,
+    pub call_count: u64,
+}
+
+// A line can have several handlers, so a handler is identified by its line and purpose together.
+// Handlers missing from the previous reading count from zero, and vanished ones are left out.
+pub fn compute_rates(
+    previous: &[InterruptHandler],
+    current: &[InterruptHandler],
+    elapsed: Duration,
+) -> Vec<Rate> {
+    current
+        .iter()
+        .map(|handler| {
+            let previous_count = previous
+                .iter()
+                .find(|previous| {
+                    previous.interrupt_line == handler.interrupt_line
+                        && previous.purpose == handler.purpose
+                })
+                .map_or(0, |previous| previous.call_count);
+            let delta = calls_between(previous_count, handler.call_count);
+            let seconds = elapsed.as_secs_f64();
+            Rate {
+                interrupt_line: handler.interrupt_line,
+                controller: handler.controller.clone(),
+                purpose: handler.purpose.clone(),
+                delta,
+                per_second: if seconds > 0.0 {
+                    delta as f64 / seconds
+                } else {
+                    0.0
+                },
+                call_count: handler.call_count,
+            }
+        })
+        .collect()
+}
+
+// The kernel reports call counts as 32-bit values, so a count that went down has wrapped around.
+// One that could not have wrapped belongs to a replaced handler and counts from zero.
+fn calls_between(previous: u64, current: u64) -> u64 {
+    if current >= previous {
+        current - previous
+    } else if previous <= u64::from(u32::MAX) {
+        current + (u64::from(u32::MAX) - previous) + 1
+    } else {
+        current
+    }
+}
+
+// Count sorts by the calls since the previous reading, busiest first, and the other keys sort as
+// they do for a single reading. Ties stay in kernel order.
+pub fn sort_rates(rates: &mut [Rate], sort: SortKey) {
+    match sort {
+        SortKey::None => {}
+        SortKey::Count => rates.sort_by_key(|rate| Reverse(rate.delta)),
+        SortKey::Line => rates.sort_by_key(|rate| rate.interrupt_line),
+        SortKey::Controller => rates.sort_by(|a, b| a.controller.cmp(&b.controller)),
+    }
+}
+
+pub fn print_rates(output: &mut impl Write, rates: &[Rate]) -> std::io::Result<()> {
+    writeln!(
+        output,
+        "{:>4}  {:>10} {:>10} {:>12}  {:10}  PURPOSE",
+        "LINE", "RATE/S", "DELTA", "TOTAL", "CONTROLLER"
+    )?;
+    for rate in rates {
+        writeln!(
+            output,
+            "{:>4}  {:>10.1} {:>10} {:>12}  {:10}  {}",
+            rate.interrupt_line,
+            rate.per_second,
+            rate.delta,
+            rate.call_count,
+            rate.controller,
+            rate.purpose
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,187 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Prints the sockets, the ARP table and the network interfaces as aligned tables or as JSON.
+// Host names are looked up by the caller, which passes in a resolver.
+
+pub mod sockets;
+#[cfg(test)]
+mod tests;
+
+use std::io::Write;
+use std::net::Ipv4Addr;
+
+use serenity::json::{ArrayBuilder, ObjectBuilder, Value};
+use serenity::procfs::{ArpEntry, NetworkAdapter};
+
+// Columns are as wide as their widest cell. The last one is not padded.
+pub fn print_table(
+    output: &mut impl Write,
+    titles: &[&str],
+    rows: &[Vec<String>],
+) -> std::io::Result<()> {
+    let widths: Vec<usize> = (0..titles.len())
+        .map(|index| {
+            rows.iter()
+                .map(|row| row[index].chars().count())
+                .chain([titles[index].len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let mut print_row = |cells: Vec<&str>| -> std::io::Result<()> {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect();
+        writeln!(output, "{}", line.join("  ").trim_end())
+    };
+    print_row(titles.to_vec())?;
+    for row in rows {
+        print_row(row.iter().map(String::as_str).collect())?;
+    }
+    Ok(())
+}
+
+// Addresses are shown with the names from `resolve`, where it has one.
+pub fn print_sockets(
+    output: &mut impl Write,
+    sockets: &[sockets::Socket],
+    json: bool,
+    mut resolve: impl FnMut(Ipv4Addr) -> Option<String>,
+) -> std::io::Result<()> {
+    if json {
+        let array = ArrayBuilder::new().extend(sockets.iter().map(sockets::to_json));
+        writeln!(output, "{:#}", array.build())?;
+        return Ok(());
+    }
+    let rows: Vec<Vec<String>> = sockets
+        .iter()
+        .map(|socket| {
+            vec![
+                socket.protocol.name().to_string(),
+                sockets::format_endpoint(&socket.local, &mut resolve),
+                socket.peer.as_ref().map_or("-".to_string(), |peer| {
+                    sockets::format_endpoint(peer, &mut resolve)
+                }),
+                socket.state.clone().unwrap_or_else(|| "-".to_string()),
+                socket.pid.map_or("-".to_string(), |pid| pid.to_string()),
+            ]
+        })
+        .collect();
+    print_table(
+        output,
+        &["Proto", "Local Address", "Peer Address", "State", "PID"],
+        &rows,
+    )?;
+    Ok(())
+}
+
+fn arp_json(entry: &ArpEntry) -> Value {
+    ObjectBuilder::new()
+        .insert("ip_address", entry.ip_address.to_string())
+        .insert("mac_address", entry.mac_address.as_str())
+        .build()
+}
+
+pub fn print_arp(
+    output: &mut impl Write,
+    entries: &[ArpEntry],
+    json: bool,
+    mut resolve: impl FnMut(Ipv4Addr) -> Option<String>,
+) -> std::io::Result<()> {
+    if json {
+        let array = ArrayBuilder::new().extend(entries.iter().map(arp_json));
+        writeln!(output, "{:#}", array.build())?;
+        return Ok(());
+    }
+    let rows: Vec<Vec<String>> = entries
+        .iter()
+        .map(|entry| {
+            let address = resolve(entry.ip_address).unwrap_or(entry.ip_address.to_string());
+            vec![address, entry.mac_address.clone()]
+        })
+        .collect();
+    print_table(output, &["Address", "MAC Address"], &rows)?;
+    Ok(())
+}
+
+fn adapter_json(adapter: &NetworkAdapter) -> Value {
+    let address = |address: Option<Ipv4Addr>| address.map(|address| address.to_string());
+    ObjectBuilder::new()
+        .insert("name", adapter.name.as_str())
+        .insert("class_name", adapter.class_name.as_str())
+        .insert("mac_address", adapter.mac_address.as_str())
+        .insert_optional("ipv4_address", address(adapter.ipv4_address))
+        .insert_optional("ipv4_netmask", address(adapter.ipv4_netmask))
+        .insert_optional("ipv4_gateway", address(adapter.ipv4_gateway))
+        .insert("link_up", adapter.link_up)
+        .insert_optional(
+            "link_speed",
+            (adapter.link_speed >= 0).then_some(adapter.link_speed),
+        )
+        .insert("link_full_duplex", adapter.link_full_duplex)
+        .insert("mtu", adapter.mtu)
+        .insert("packets_in", adapter.packets_in)
+        .insert("bytes_in", adapter.bytes_in)
+        .insert("packets_out", adapter.packets_out)
+        .insert("bytes_out", adapter.bytes_out)
+        .build()
+}
+
+pub fn link(adapter: &NetworkAdapter) -> String {
+    if !adapter.link_up {
+        return "down".to_string();
+    }
+    if adapter.link_speed < 0 {
+        return "up".to_string();
+    }
+    let duplex = if adapter.link_full_duplex {
+        "full"
+    } else {
+        "half"
+    };
+    format!("{} Mb/s {}", adapter.link_speed, duplex)
+}
+
+pub fn print_interfaces(
+    output: &mut impl Write,
+    adapters: &[NetworkAdapter],
+    json: bool,
+) -> std::io::Result<()> {
+    if json {
+        let array = ArrayBuilder::new().extend(adapters.iter().map(adapter_json));
+        writeln!(output, "{:#}", array.build())?;
+        return Ok(());
+    }
+    let rows: Vec<Vec<String>> = adapters
+        .iter()
+        .map(|adapter| {
+            vec![
+                adapter.name.clone(),
+                adapter.mac_address.clone(),
+                adapter
+                    .ipv4_address
+                    .map_or("-".to_string(), |address| address.to_string()),
+                adapter.mtu.to_string(),
+                link(adapter),
+                adapter.packets_in.to_string(),
+                adapter.bytes_in.to_string(),
+                adapter.packets_out.to_string(),
+                adapter.bytes_out.to_string(),
+            ]
+        })
+        .collect();
+    print_table(
+        output,
+        &[
+            "Name", "MAC", "IPv4", "MTU", "Link", "RX-Pkts", "RX-Bytes", "TX-Pkts", "TX-Bytes",
+        ],
+        &rows,
+    )?;
+    Ok(())
+}
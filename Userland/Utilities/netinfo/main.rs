@@ -0,0 +1,165 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Shows the sockets, ARP table and network adapters from /proc/net:
+//
+//     netinfo -t -l
+//     netinfo --arp
+//     netinfo --interfaces --json
+
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+
+use netinfo::sockets::{self, Filter, States};
+use netinfo::{print_arp, print_interfaces, print_sockets};
+use serenity::{procfs, sys};
+
+const USAGE: &str = "usage: netinfo [-t] [-u] [-x] [-l|-a] [-n] [--arp|--interfaces] [--json]";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Sockets,
+    Arp,
+    Interfaces,
+}
+
+struct Options {
+    mode: Mode,
+    filter: Filter,
+    numeric: bool,
+    json: bool,
+}
+
+fn parse_arguments() -> Result<Options, Box<dyn std::error::Error>> {
+    let mut options = Options {
+        mode: Mode::Sockets,
+        filter: Filter {
+            tcp: false,
+            udp: false,
+            unix: false,
+            states: States::NotListening,
+        },
+        numeric: false,
+        json: false,
+    };
+    for argument in std::env::args().skip(1) {
+        match argument.as_str() {
+            "-t" | "--tcp" => options.filter.tcp = true,
+            "-u" | "--udp" => options.filter.udp = true,
+            "-x" | "--unix" => options.filter.unix = true,
+            "-l" | "--listening" => options.filter.states = States::Listening,
+            "-a" | "--all" => options.filter.states = States::All,
+            "-n" | "--numeric" => options.numeric = true,
+            "--arp" => options.mode = Mode::Arp,
+            "-i" | "--interfaces" => options.mode = Mode::Interfaces,
+            "--json" => options.json = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                println!("  -t, --tcp          show TCP sockets");
+                println!("  -u, --udp          show UDP sockets");
+                println!("  -x, --unix         show local sockets");
+                println!("  -l, --listening    only show listening TCP sockets");
+                println!("  -a, --all          show listening and non-listening TCP sockets");
+                println!("  -n, --numeric      don't look up host names");
+                println!("      --arp          show the ARP table");
+                println!("  -i, --interfaces   show the network adapters");
+                println!("      --json         print JSON instead of a table");
+                std::process::exit(0);
+            }
+            _ => return Err(format!("unknown argument '{}'\n{}", argument, USAGE).into()),
+        }
+    }
+    // Without a protocol, show them all.
+    let filter = &mut options.filter;
+    if !filter.tcp && !filter.udp && !filter.unix {
+        (filter.tcp, filter.udp, filter.unix) = (true, true, true);
+    }
+    Ok(options)
+}
+
+// Looks host names up once per address. Gives up on the unspecified address, which has none.
+fn resolver(numeric: bool) -> impl FnMut(Ipv4Addr) -> Option<String> {
+    let mut names: HashMap<Ipv4Addr, Option<String>> = HashMap::new();
+    move |address| {
+        if numeric || address.is_unspecified() {
+            return None;
+        }
+        names
+            .entry(address)
+            .or_insert_with(|| sys::host_name(address))
+            .clone()
+    }
+}
+
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let options = parse_arguments()?;
+    let procfs = procfs::ProcFS::new();
+    // Host names come from LookupServer, over its portal.
+    let resolve = !options.numeric && !options.json && options.mode != Mode::Interfaces;
+    if resolve {
+        sys::pledge("stdio rpath unix")?;
+        sys::unveil("/tmp/portal/lookup", "rw")?;
+    } else {
+        sys::pledge("stdio rpath")?;
+    }
+    sys::unveil(&procfs.path("net").to_string_lossy(), "r")?;
+    sys::lock_veil()?;
+
+    // Everything is read up front, so that only the lookups are left to do.
+    let drop_rpath = || sys::pledge(if resolve { "stdio unix" } else { "stdio" });
+    let mut output = std::io::stdout().lock();
+    match options.mode {
+        Mode::Sockets => {
+            let filter = &options.filter;
+            let tcp = if filter.tcp {
+                procfs.net_tcp()?
+            } else {
+                Vec::new()
+            };
+            let udp = if filter.udp {
+                procfs.net_udp()?
+            } else {
+                Vec::new()
+            };
+            let local = if filter.unix {
+                procfs.net_local()?
+            } else {
+                Vec::new()
+            };
+            drop_rpath()?;
+            let sockets = sockets::collect(&tcp, &udp, &local, filter);
+            print_sockets(
+                &mut output,
+                &sockets,
+                options.json,
+                resolver(options.numeric),
+            )?;
+        }
+        Mode::Arp => {
+            let entries = procfs.net_arp()?;
+            drop_rpath()?;
+            print_arp(
+                &mut output,
+                &entries,
+                options.json,
+                resolver(options.numeric),
+            )?;
+        }
+        Mode::Interfaces => {
+            let adapters = procfs.net_adapters()?;
+            drop_rpath()?;
+            print_interfaces(&mut output, &adapters, options.json)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn main() {
+    if let Err(error) = run() {
+        eprintln!("netinfo: {}", error);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,159 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// The TCP, UDP and local sockets of /proc/net as one list, filtered by protocol and state.
+
+use std::net::Ipv4Addr;
+
+use serenity::json::{ObjectBuilder, Value};
+use serenity::procfs::{LocalSocket, TcpSocket, UdpSocket};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+    Unix,
+}
+
+impl Protocol {
+    pub fn name(self) -> &'static str {
+        match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Udp => "udp",
+            Protocol::Unix => "unix",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Endpoint {
+    Inet(Ipv4Addr, u16),
+    Path(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Socket {
+    pub protocol: Protocol,
+    pub local: Endpoint,
+    // Local sockets have none.
+    pub peer: Option<Endpoint>,
+    // Only TCP sockets have a state.
+    pub state: Option<String>,
+    // The process that opened the socket, if the kernel shows it to us.
+    pub pid: Option<i32>,
+}
+
+impl Socket {
+    pub fn is_listening(&self) -> bool { self.state.as_deref() == Some("Listen") }
+}
+
+// Which sockets to show. Only TCP sockets listen, so the states only filter those, like netstat.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum States {
+    NotListening,
+    Listening,
+    All,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Filter {
+    pub tcp: bool,
+    pub udp: bool,
+    pub unix: bool,
+    pub states: States,
+}
+
+impl Filter {
+    fn accepts(&self, socket: &Socket) -> bool {
+        let protocol = match socket.protocol {
+            Protocol::Tcp => self.tcp,
+            Protocol::Udp => self.udp,
+            Protocol::Unix => self.unix,
+        };
+        let state = socket.protocol != Protocol::Tcp
+            || match self.states {
+                States::NotListening => !socket.is_listening(),
+                States::Listening => socket.is_listening(),
+                States::All => true,
+            };
+        protocol && state
+    }
+}
+
+// The sockets the filter accepts, ordered by protocol and then by local port, like netstat.
+pub fn collect(
+    tcp: &[TcpSocket],
+    udp: &[UdpSocket],
+    local: &[LocalSocket],
+    filter: &Filter,
+) -> Vec<Socket> {
+    let mut tcp: Vec<&TcpSocket> = tcp.iter().collect();
+    tcp.sort_by_key(|socket| socket.local_port);
+    let mut udp: Vec<&UdpSocket> = udp.iter().collect();
+    udp.sort_by_key(|socket| socket.local_port);
+
+    let tcp = tcp.into_iter().map(|socket| Socket {
+        protocol: Protocol::Tcp,
+        local: Endpoint::Inet(socket.local_address, socket.local_port),
+        peer: Some(Endpoint::Inet(socket.peer_address, socket.peer_port)),
+        state: Some(socket.state.clone()),
+        pid: socket.origin.map(|origin| origin.pid),
+    });
+    let udp = udp.into_iter().map(|socket| Socket {
+        protocol: Protocol::Udp,
+        local: Endpoint::Inet(socket.local_address, socket.local_port),
+        peer: Some(Endpoint::Inet(socket.peer_address, socket.peer_port)),
+        state: None,
+        pid: socket.origin.map(|origin| origin.pid),
+    });
+    let local = local.iter().map(|socket| Socket {
+        protocol: Protocol::Unix,
+        local: Endpoint::Path(socket.path.clone()),
+        peer: None,
+        state: None,
+        pid: Some(socket.origin_pid),
+    });
+    tcp.chain(udp)
+        .chain(local)
+        .filter(|socket| filter.accepts(socket))
+        .collect()
+}
+
+// Addresses are shown with the name from `resolve`, if it has one.
+pub fn format_endpoint(
+    endpoint: &Endpoint,
+    resolve: &mut impl FnMut(Ipv4Addr) -> Option<String>,
+) -> String {
+    match endpoint {
+        Endpoint::Inet(address, port) => {
+            let host = resolve(*address).unwrap_or_else(|| address.to_string());
+            format!("{}:{}", host, port)
+        }
+        Endpoint::Path(path) => path.clone(),
+    }
+}
+
+fn endpoint_json(object: ObjectBuilder, prefix: &str, endpoint: &Endpoint) -> ObjectBuilder {
+    match endpoint {
+        Endpoint::Inet(address, port) => object
+            .insert(format!("{}_address", prefix), address.to_string())
+            .insert(format!("{}_port", prefix), *port),
+        Endpoint::Path(path) => object.insert(format!("{}_path", prefix), path.as_str()),
+    }
+}
+
+pub fn to_json(socket: &Socket) -> Value {
+    let object = ObjectBuilder::new().insert("protocol", socket.protocol.name());
+    let object = endpoint_json(object, "local", &socket.local);
+    let object = match &socket.peer {
+        Some(peer) => endpoint_json(object, "peer", peer),
+        None => object,
+    };
+    object
+        .insert_optional("state", socket.state.as_deref())
+        .insert_optional("pid", socket.pid)
+        .build()
+}
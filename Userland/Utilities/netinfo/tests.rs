@@ -0,0 +1,244 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// The output for /proc/net nodes, compared with the files in fixtures/expected. "qemu" shows
+// QEMU's user networking, with a web server on port 8000 that a local client is talking to.
+// "bare-metal" has two Realtek cards and no sockets; the RTL8168 didn't report its speed.
+
+use std::net::Ipv4Addr;
+
+use serenity::procfs::{NetworkAdapter, ProcFS};
+
+use crate::sockets::{self, Endpoint, Filter, Protocol, Socket, States};
+use crate::{link, print_arp, print_interfaces, print_sockets, print_table};
+
+fn procfs(fixture: &str) -> ProcFS {
+    ProcFS::with_root(format!(
+        "{}/fixtures/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        fixture
+    ))
+}
+
+fn filter(protocols: &str, states: States) -> Filter {
+    Filter {
+        tcp: protocols.contains('t'),
+        udp: protocols.contains('u'),
+        unix: protocols.contains('x'),
+        states,
+    }
+}
+
+fn collect(fixture: &str, filter: &Filter) -> Vec<Socket> {
+    let procfs = procfs(fixture);
+    sockets::collect(
+        &procfs.net_tcp().unwrap(),
+        &procfs.net_udp().unwrap(),
+        &procfs.net_local().unwrap(),
+        filter,
+    )
+}
+
+fn numeric(_: Ipv4Addr) -> Option<String> { None }
+
+// Stands in for LookupServer, which only knows the loopback address and QEMU's DNS server.
+fn lookup(address: Ipv4Addr) -> Option<String> {
+    match address.octets() {
+        [127, 0, 0, 1] => Some("localhost".to_string()),
+        [10, 0, 2, 3] => Some("dns.qemu".to_string()),
+        _ => None,
+    }
+}
+
+fn render(print: impl FnOnce(&mut Vec<u8>) -> std::io::Result<()>) -> String {
+    let mut output = Vec::new();
+    print(&mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+fn socket_table(fixture: &str, filter: &Filter, resolve: fn(Ipv4Addr) -> Option<String>) -> String {
+    let sockets = collect(fixture, filter);
+    render(|output| print_sockets(output, &sockets, false, resolve))
+}
+
+#[test]
+fn prints_socket_tables() {
+    // Like netstat, listening sockets are left out unless asked for.
+    assert_eq!(
+        socket_table("qemu", &filter("tux", States::NotListening), numeric),
+        include_str!("fixtures/expected/qemu.txt")
+    );
+    assert_eq!(
+        socket_table("qemu", &filter("t", States::Listening), numeric),
+        include_str!("fixtures/expected/qemu-listening.txt")
+    );
+    assert_eq!(
+        socket_table("qemu", &filter("tux", States::All), lookup),
+        include_str!("fixtures/expected/qemu-all-resolved.txt")
+    );
+    // Just the titles.
+    assert_eq!(
+        socket_table("bare-metal", &filter("tux", States::All), lookup),
+        include_str!("fixtures/expected/bare-metal.txt")
+    );
+}
+
+#[test]
+fn prints_sockets_as_json() {
+    let sockets = collect("qemu", &filter("tux", States::All));
+    // The addresses stay numeric, whatever the resolver knows.
+    assert_eq!(
+        render(|output| print_sockets(output, &sockets, true, lookup)),
+        include_str!("fixtures/expected/qemu-all.json")
+    );
+}
+
+fn ports(sockets: &[Socket]) -> Vec<(Protocol, u16)> {
+    sockets
+        .iter()
+        .filter_map(|socket| match socket.local {
+            Endpoint::Inet(_, port) => Some((socket.protocol, port)),
+            Endpoint::Path(_) => None,
+        })
+        .collect()
+}
+
+#[test]
+fn filters_sockets() {
+    use Protocol::{Tcp, Udp};
+
+    // Ordered by protocol and then by local port.
+    assert_eq!(ports(&collect("qemu", &filter("tu", States::All))), [
+        (Tcp, 8000),
+        (Tcp, 8000),
+        (Tcp, 49152),
+        (Tcp, 49153),
+        (Tcp, 49154),
+        (Udp, 68),
+        (Udp, 5353),
+        (Udp, 50123),
+    ]);
+    assert_eq!(
+        ports(&collect("qemu", &filter("t", States::NotListening))),
+        [(Tcp, 8000), (Tcp, 49152), (Tcp, 49153), (Tcp, 49154)]
+    );
+    assert_eq!(ports(&collect("qemu", &filter("t", States::Listening))), [
+        (Tcp, 8000)
+    ]);
+    // Only TCP sockets have states, so the others are never left out for them.
+    assert_eq!(ports(&collect("qemu", &filter("u", States::Listening))), [
+        (Udp, 68),
+        (Udp, 5353),
+        (Udp, 50123)
+    ]);
+    let local = collect("qemu", &filter("x", States::Listening));
+    assert_eq!(local.len(), 4);
+    assert!(local.iter().all(|socket| socket.peer.is_none()));
+    assert_eq!(local[2], Socket {
+        protocol: Protocol::Unix,
+        local: Endpoint::Path("/tmp/portal/request".to_string()),
+        peer: None,
+        state: None,
+        pid: Some(11),
+    });
+    assert!(collect("qemu", &filter("", States::All)).is_empty());
+
+    // The PID is left out where the kernel doesn't give one.
+    let udp = collect("qemu", &filter("u", States::All));
+    let pids: Vec<Option<i32>> = udp.iter().map(|socket| socket.pid).collect();
+    assert_eq!(pids, [Some(14), None, Some(13)]);
+}
+
+#[test]
+fn formats_endpoints() {
+    let mut resolve = lookup;
+    let loopback = Endpoint::Inet(Ipv4Addr::LOCALHOST, 8000);
+    assert_eq!(
+        sockets::format_endpoint(&loopback, &mut resolve),
+        "localhost:8000"
+    );
+    let unknown = Endpoint::Inet(Ipv4Addr::new(93, 184, 216, 34), 443);
+    assert_eq!(
+        sockets::format_endpoint(&unknown, &mut resolve),
+        "93.184.216.34:443"
+    );
+    let path = Endpoint::Path("/tmp/portal/lookup".to_string());
+    assert_eq!(
+        sockets::format_endpoint(&path, &mut resolve),
+        "/tmp/portal/lookup"
+    );
+}
+
+#[test]
+fn prints_the_arp_table() {
+    let entries = procfs("qemu").net_arp().unwrap();
+    assert_eq!(
+        render(|output| print_arp(output, &entries, false, numeric)),
+        include_str!("fixtures/expected/qemu-arp.txt")
+    );
+    assert_eq!(
+        render(|output| print_arp(output, &entries, false, lookup)),
+        include_str!("fixtures/expected/qemu-arp-resolved.txt")
+    );
+    assert_eq!(
+        render(|output| print_arp(output, &entries, true, lookup)),
+        include_str!("fixtures/expected/qemu-arp.json")
+    );
+    assert_eq!(
+        render(|output| print_arp(output, &[], false, lookup)),
+        "Address  MAC Address\n"
+    );
+}
+
+fn adapters(fixture: &str) -> Vec<NetworkAdapter> { procfs(fixture).net_adapters().unwrap() }
+
+#[test]
+fn prints_interfaces() {
+    let interfaces = |fixture: &str, json: bool| {
+        let adapters = adapters(fixture);
+        render(|output| print_interfaces(output, &adapters, json))
+    };
+    assert_eq!(
+        interfaces("qemu", false),
+        include_str!("fixtures/expected/qemu-interfaces.txt")
+    );
+    assert_eq!(
+        interfaces("bare-metal", false),
+        include_str!("fixtures/expected/bare-metal-interfaces.txt")
+    );
+    // Addresses and speeds the kernel doesn't have are left out.
+    assert_eq!(
+        interfaces("qemu", true),
+        include_str!("fixtures/expected/qemu-interfaces.json")
+    );
+    assert_eq!(
+        interfaces("bare-metal", true),
+        include_str!("fixtures/expected/bare-metal-interfaces.json")
+    );
+}
+
+#[test]
+fn describes_links() {
+    let qemu = adapters("qemu");
+    assert_eq!(link(&qemu[0]), "1000 Mb/s full");
+    assert_eq!(link(&qemu[2]), "down");
+    let bare_metal = adapters("bare-metal");
+    assert_eq!(link(&bare_metal[1]), "up");
+    assert_eq!(link(&bare_metal[2]), "10 Mb/s half");
+}
+
+#[test]
+fn pads_table_columns() {
+    let rows = vec![
+        vec!["tcp".to_string(), "ünïcode".to_string(), "-".to_string()],
+        vec!["udp".to_string(), "x".to_string(), "".to_string()],
+    ];
+    // Widths are counted in characters, and trailing blanks are trimmed.
+    assert_eq!(
+        render(|output| print_table(output, &["Proto", "Path", "State"], &rows)),
+        "Proto  Path     State\ntcp    ünïcode  -\nudp    x\n"
+    );
+}
@@ -0,0 +1,148 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Looks up the names of a PCI device's IDs in pci.ids and prints the device like lspci, on one
+// line or with -v on several, or as JSON. Without a database, only the IDs are shown.
+
+#[cfg(test)]
+mod tests;
+
+use std::io::Write;
+
+use serenity::json::{ObjectBuilder, Value};
+use serenity::pcidb::Database;
+use serenity::procfs::PciDevice;
+
+// The names of a device's IDs, where the database has them.
+#[derive(Default)]
+pub struct Names<'a> {
+    pub vendor: Option<&'a str>,
+    pub device: Option<&'a str>,
+    pub class: Option<&'a str>,
+    pub subclass: Option<&'a str>,
+    pub subsystem_vendor: Option<&'a str>,
+    pub subsystem: Option<&'a str>,
+}
+
+impl<'a> Names<'a> {
+    pub fn of(device: &PciDevice, database: Option<&'a Database>) -> Names<'a> {
+        let database = match database {
+            Some(database) => database,
+            None => return Names::default(),
+        };
+        Names {
+            vendor: database.vendor(device.vendor_id),
+            device: database.device(device.vendor_id, device.device_id),
+            class: database.class(device.class),
+            subclass: database.subclass(device.class, device.subclass),
+            subsystem_vendor: database.vendor(device.subsystem_vendor_id),
+            subsystem: database.subsystem(
+                device.vendor_id,
+                device.device_id,
+                device.subsystem_vendor_id,
+                device.subsystem_id,
+            ),
+        }
+    }
+}
+
+pub fn address(device: &PciDevice) -> String {
+    format!(
+        "{:04x}:{:02x}:{:02x}.{}",
+        device.domain, device.bus, device.device, device.function
+    )
+}
+
+// "name [id]", or just the ID without a name.
+fn labelled(name: Option<&str>, id: String) -> String {
+    match name {
+        Some(name) => format!("{} [{}]", name, id),
+        None => id,
+    }
+}
+
+// Like the C++ lspci, the vendor and device are joined with a colon when showing IDs only, and
+// with a space otherwise, even where a name is missing.
+pub fn print_device(
+    output: &mut impl Write,
+    device: &PciDevice,
+    names: &Names,
+    numeric: bool,
+    verbose: bool,
+) -> std::io::Result<()> {
+    let class = names.class.map_or(
+        format!("{:02x}{:02x}", device.class, device.subclass),
+        str::to_string,
+    );
+    let vendor = names
+        .vendor
+        .map_or(format!("{:04x}", device.vendor_id), str::to_string);
+    let name = names
+        .device
+        .map_or(format!("{:04x}", device.device_id), str::to_string);
+    let separator = if numeric { ":" } else { " " };
+    writeln!(
+        output,
+        "{} {}: {}{}{} (rev {:02x})",
+        address(device),
+        class,
+        vendor,
+        separator,
+        name,
+        device.revision_id
+    )?;
+    if !verbose {
+        return Ok(());
+    }
+    writeln!(
+        output,
+        "\tClass: {}, {}",
+        labelled(names.class, format!("{:02x}", device.class)),
+        labelled(names.subclass, format!("{:02x}", device.subclass))
+    )?;
+    writeln!(
+        output,
+        "\tVendor: {}",
+        labelled(names.vendor, format!("{:04x}", device.vendor_id))
+    )?;
+    writeln!(
+        output,
+        "\tDevice: {}",
+        labelled(names.device, format!("{:04x}", device.device_id))
+    )?;
+    writeln!(
+        output,
+        "\tSubsystem: {}, {}",
+        labelled(
+            names.subsystem_vendor,
+            format!("{:04x}", device.subsystem_vendor_id)
+        ),
+        labelled(names.subsystem, format!("{:04x}", device.subsystem_id))
+    )
+}
+
+pub fn device_json(device: &PciDevice, names: &Names) -> Value {
+    ObjectBuilder::new()
+        .insert("address", address(device))
+        .insert("domain", device.domain)
+        .insert("bus", device.bus)
+        .insert("device", device.device)
+        .insert("function", device.function)
+        .insert("vendor_id", device.vendor_id)
+        .insert("device_id", device.device_id)
+        .insert("revision_id", device.revision_id)
+        .insert("class", device.class)
+        .insert("subclass", device.subclass)
+        .insert("subsystem_vendor_id", device.subsystem_vendor_id)
+        .insert("subsystem_id", device.subsystem_id)
+        .insert_optional("vendor_name", names.vendor)
+        .insert_optional("device_name", names.device)
+        .insert_optional("class_name", names.class)
+        .insert_optional("subclass_name", names.subclass)
+        .insert_optional("subsystem_vendor_name", names.subsystem_vendor)
+        .insert_optional("subsystem_name", names.subsystem)
+        .build()
+}
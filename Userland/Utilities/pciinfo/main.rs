@@ -0,0 +1,110 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Lists the PCI devices in /proc/pci, named after the pci.ids database:
+//
+//     pciinfo
+//     pciinfo -v -d ~/pci.ids
+
+use std::io::Write;
+
+use pciinfo::{device_json, print_device, Names};
+use serenity::json::ArrayBuilder;
+use serenity::pcidb::{self, Database};
+use serenity::{procfs, sys};
+
+const USAGE: &str = "usage: pciinfo [-n] [-v] [--json] [-d database]";
+
+struct Options {
+    numeric: bool,
+    verbose: bool,
+    json: bool,
+    database: String,
+}
+
+fn parse_arguments() -> Result<Options, Box<dyn std::error::Error>> {
+    let mut options = Options {
+        numeric: false,
+        verbose: false,
+        json: false,
+        database: pcidb::DEFAULT_PATH.to_string(),
+    };
+    let mut arguments = std::env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "-n" | "--numeric" => options.numeric = true,
+            "-v" | "--verbose" => options.verbose = true,
+            "--json" => options.json = true,
+            "-d" | "--database" => options.database = arguments.next().ok_or(USAGE)?,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                println!("  -n, --numeric        show IDs instead of names");
+                println!("  -v, --verbose        also show the subclass, subsystem and IDs");
+                println!("      --json           print a JSON array instead of text");
+                println!(
+                    "  -d, --database FILE  read names from FILE instead of {}",
+                    pcidb::DEFAULT_PATH
+                );
+                std::process::exit(0);
+            }
+            _ => return Err(format!("unknown argument '{}'\n{}", argument, USAGE).into()),
+        }
+    }
+    Ok(options)
+}
+
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let options = parse_arguments()?;
+    let procfs = procfs::ProcFS::new();
+    sys::pledge("stdio rpath")?;
+    sys::unveil(&procfs.path("pci").to_string_lossy(), "r")?;
+    if !options.numeric {
+        sys::unveil(&options.database, "r")?;
+    }
+    sys::lock_veil()?;
+
+    let devices = procfs.pci()?;
+    // Without the database, fall back to IDs like the C++ lspci.
+    let database = if options.numeric {
+        None
+    } else {
+        match Database::open(&options.database) {
+            Ok(database) => Some(database),
+            Err(error) => {
+                eprintln!("pciinfo: couldn't open {}: {}", options.database, error);
+                None
+            }
+        }
+    };
+    sys::pledge("stdio")?;
+
+    let mut output = std::io::stdout().lock();
+    if options.json {
+        let array = devices
+            .iter()
+            .map(|device| device_json(device, &Names::of(device, database.as_ref())));
+        writeln!(output, "{:#}", ArrayBuilder::new().extend(array).build())?;
+        return Ok(());
+    }
+    for device in &devices {
+        let names = Names::of(device, database.as_ref());
+        print_device(
+            &mut output,
+            device,
+            &names,
+            options.numeric,
+            options.verbose,
+        )?;
+    }
+    Ok(())
+}
+
+pub fn main() {
+    if let Err(error) = run() {
+        eprintln!("pciinfo: {}", error);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,118 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// The output for a pci node of QEMU's i440FX machine, compared with the files in
+// fixtures/expected. The names come from fixtures/pci.ids, which leaves out the VGA controller,
+// the SATA controller and the SATA subclass, so that both named and unnamed devices are shown.
+
+use serenity::json::ArrayBuilder;
+use serenity::pcidb::Database;
+use serenity::procfs::{PciDevice, ProcFS};
+
+use crate::{address, device_json, print_device, Names};
+
+fn devices() -> Vec<PciDevice> {
+    ProcFS::with_root(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/qemu"))
+        .pci()
+        .unwrap()
+}
+
+fn database() -> Database {
+    Database::open(concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/pci.ids")).unwrap()
+}
+
+fn text(database: Option<&Database>, numeric: bool, verbose: bool) -> String {
+    let mut output = Vec::new();
+    for device in &devices() {
+        let names = Names::of(device, database);
+        print_device(&mut output, device, &names, numeric, verbose).unwrap();
+    }
+    String::from_utf8(output).unwrap()
+}
+
+fn json(database: Option<&Database>) -> String {
+    let devices = devices();
+    let array = devices
+        .iter()
+        .map(|device| device_json(device, &Names::of(device, database)));
+    format!("{:#}\n", ArrayBuilder::new().extend(array).build())
+}
+
+#[test]
+fn prints_devices() {
+    let database = database();
+    assert_eq!(
+        text(Some(&database), false, false),
+        include_str!("fixtures/expected/qemu.txt")
+    );
+    assert_eq!(
+        text(Some(&database), false, true),
+        include_str!("fixtures/expected/qemu-verbose.txt")
+    );
+}
+
+#[test]
+fn prints_ids() {
+    // With -n, the vendor and device IDs are joined with a colon, like "8086:7010".
+    assert_eq!(
+        text(None, true, false),
+        include_str!("fixtures/expected/qemu-numeric.txt")
+    );
+    assert_eq!(
+        text(None, true, true),
+        include_str!("fixtures/expected/qemu-numeric-verbose.txt")
+    );
+    // Without it, they are joined with a space even when no name is known, like the C++ lspci.
+    assert_eq!(
+        text(Some(&Database::default()), false, false),
+        include_str!("fixtures/expected/qemu-unnamed.txt")
+    );
+}
+
+#[test]
+fn prints_json() {
+    let database = database();
+    assert_eq!(
+        json(Some(&database)),
+        include_str!("fixtures/expected/qemu.json")
+    );
+    // Names that aren't known are left out.
+    assert_eq!(
+        json(None),
+        include_str!("fixtures/expected/qemu-numeric.json")
+    );
+}
+
+#[test]
+fn names_devices() {
+    let database = database();
+    let devices = devices();
+    let ide = devices
+        .iter()
+        .find(|device| address(device) == "0000:00:01.1")
+        .unwrap();
+    let names = Names::of(ide, Some(&database));
+    assert_eq!(names.vendor, Some("Intel Corporation"));
+    assert_eq!(names.device, Some("82371SB PIIX3 IDE [Natoma/Triton II]"));
+    assert_eq!(names.class, Some("Mass storage controller"));
+    assert_eq!(names.subclass, Some("IDE interface"));
+    assert_eq!(names.subsystem_vendor, Some("Red Hat, Inc."));
+    assert_eq!(names.subsystem, Some("Qemu virtual machine"));
+
+    // The vendor is known, but not its VGA controller.
+    let vga = devices
+        .iter()
+        .find(|device| address(device) == "0000:00:02.0")
+        .unwrap();
+    let names = Names::of(vga, Some(&database));
+    assert_eq!(names.vendor, Some("Technical Corp."));
+    assert_eq!(names.device, None);
+    assert_eq!(names.subsystem, None);
+
+    let names = Names::of(ide, None);
+    assert_eq!(names.vendor, None);
+    assert_eq!(names.class, None);
+}
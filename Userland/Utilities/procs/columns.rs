@@ -0,0 +1,163 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// The columns -o can select, and how rows are formatted and sorted by them.
+
+use std::cmp::Ordering;
+
+use serenity::procfs::Process;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Column {
+    Pid,
+    Ppid,
+    Pgid,
+    Sid,
+    Uid,
+    Gid,
+    State,
+    Tty,
+    // Virtual and resident memory, in KiB.
+    Vm,
+    Rss,
+    Nfds,
+    Threads,
+    Cpu,
+    Name,
+}
+
+const COLUMNS: [(&str, Column); 14] = [
+    ("pid", Column::Pid),
+    ("ppid", Column::Ppid),
+    ("pgid", Column::Pgid),
+    ("sid", Column::Sid),
+    ("uid", Column::Uid),
+    ("gid", Column::Gid),
+    ("state", Column::State),
+    ("tty", Column::Tty),
+    ("vm", Column::Vm),
+    ("rss", Column::Rss),
+    ("nfds", Column::Nfds),
+    ("threads", Column::Threads),
+    ("cpu", Column::Cpu),
+    ("name", Column::Name),
+];
+
+pub const DEFAULT_COLUMNS: &str = "pid,ppid,uid,state,vm,cpu,name";
+
+// One process as it is shown.
+pub struct Row<'a> {
+    pub process: &'a Process,
+    pub cpu: f64,
+    // How far down the tree the process is, in tree view.
+    pub depth: usize,
+}
+
+impl<'a> Row<'a> {
+    // The state of the main thread, which has the process's ID.
+    fn state(&self) -> &'a str {
+        let threads = &self.process.threads;
+        threads
+            .iter()
+            .find(|thread| thread.tid == self.process.pid)
+            .or(threads.first())
+            .map_or("", |thread| thread.state.as_str())
+    }
+}
+
+impl Column {
+    pub fn parse(name: &str) -> Option<Column> {
+        COLUMNS
+            .iter()
+            .find(|(column_name, _)| *column_name == name)
+            .map(|(_, column)| *column)
+    }
+
+    // A comma-separated list, like "pid,name".
+    pub fn parse_list(list: &str) -> Result<Vec<Column>, String> {
+        list.split(',')
+            .map(|name| Column::parse(name).ok_or_else(|| format!("unknown column '{}'", name)))
+            .collect()
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Column::Pid => "PID",
+            Column::Ppid => "PPID",
+            Column::Pgid => "PGID",
+            Column::Sid => "SID",
+            Column::Uid => "UID",
+            Column::Gid => "GID",
+            Column::State => "STATE",
+            Column::Tty => "TTY",
+            Column::Vm => "VM",
+            Column::Rss => "RSS",
+            Column::Nfds => "FDS",
+            Column::Threads => "THR",
+            Column::Cpu => "%CPU",
+            Column::Name => "NAME",
+        }
+    }
+
+    pub fn is_numeric(self) -> bool { !matches!(self, Column::State | Column::Tty | Column::Name) }
+
+    // Amounts sort biggest first, everything else in ascending order.
+    fn descending(self) -> bool {
+        matches!(
+            self,
+            Column::Vm | Column::Rss | Column::Nfds | Column::Threads | Column::Cpu
+        )
+    }
+
+    pub fn value(self, row: &Row) -> String {
+        let process = row.process;
+        match self {
+            Column::Pid => process.pid.to_string(),
+            Column::Ppid => process.ppid.to_string(),
+            Column::Pgid => process.pgid.to_string(),
+            Column::Sid => process.sid.to_string(),
+            Column::Uid => process.uid.to_string(),
+            Column::Gid => process.gid.to_string(),
+            Column::State => row.state().to_string(),
+            Column::Tty if process.tty.is_empty() => "n/a".to_string(),
+            Column::Tty => process.tty.clone(),
+            Column::Vm => (process.amount_virtual / 1024).to_string(),
+            Column::Rss => (process.amount_resident / 1024).to_string(),
+            Column::Nfds => process.nfds.to_string(),
+            Column::Threads => process.threads.len().to_string(),
+            Column::Cpu => format!("{:.1}", row.cpu),
+            Column::Name if row.depth > 0 => {
+                format!("{}\\_ {}", "  ".repeat(row.depth - 1), process.name)
+            }
+            Column::Name => process.name.clone(),
+        }
+    }
+
+    pub fn compare(self, a: &Row, b: &Row) -> Ordering {
+        let (x, y) = (a.process, b.process);
+        let ordering = match self {
+            Column::Pid => x.pid.cmp(&y.pid),
+            Column::Ppid => x.ppid.cmp(&y.ppid),
+            Column::Pgid => x.pgid.cmp(&y.pgid),
+            Column::Sid => x.sid.cmp(&y.sid),
+            Column::Uid => x.uid.cmp(&y.uid),
+            Column::Gid => x.gid.cmp(&y.gid),
+            Column::State => a.state().cmp(b.state()),
+            Column::Tty => x.tty.cmp(&y.tty),
+            Column::Vm => x.amount_virtual.cmp(&y.amount_virtual),
+            Column::Rss => x.amount_resident.cmp(&y.amount_resident),
+            Column::Nfds => x.nfds.cmp(&y.nfds),
+            Column::Threads => x.threads.len().cmp(&y.threads.len()),
+            Column::Cpu => a.cpu.total_cmp(&b.cpu),
+            Column::Name => x.name.cmp(&y.name),
+        };
+        if self.descending() {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
@@ -0,0 +1,62 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// CPU usage between two readings of /proc/all.
+
+use std::collections::HashMap;
+
+use serenity::procfs::{AllProcesses, Thread};
+
+fn scheduled(thread: &Thread) -> u64 { thread.time_user + thread.time_kernel }
+
+// Each process's share of the time scheduled between the two snapshots, in percent. The total
+// includes the idle threads, so 100 means all processors were busy with that process. Without a
+// previous snapshot this is the share since boot.
+//
+// Threads are matched by PID and TID. Threads missing from the previous snapshot, and counters
+// that went backwards because an ID was reused, count from zero.
+pub fn cpu_percentages(
+    previous: Option<&AllProcesses>,
+    current: &AllProcesses,
+) -> HashMap<i32, f64> {
+    let mut before: HashMap<(i32, i32), u64> = HashMap::new();
+    let mut previous_total = 0;
+    if let Some(previous) = previous {
+        for process in &previous.processes {
+            for thread in &process.threads {
+                before.insert((process.pid, thread.tid), scheduled(thread));
+            }
+        }
+        previous_total = previous.total_time;
+    }
+    let total = current.total_time.saturating_sub(previous_total);
+
+    current
+        .processes
+        .iter()
+        .map(|process| {
+            let delta: u64 = process
+                .threads
+                .iter()
+                .map(|thread| {
+                    let now = scheduled(thread);
+                    let then = before
+                        .get(&(process.pid, thread.tid))
+                        .copied()
+                        .filter(|then| *then <= now)
+                        .unwrap_or(0);
+                    now - then
+                })
+                .sum();
+            let percent = if total > 0 {
+                delta as f64 * 100.0 / total as f64
+            } else {
+                0.0
+            };
+            (process.pid, percent)
+        })
+        .collect()
+}
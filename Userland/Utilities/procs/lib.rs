@@ -0,0 +1,102 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Builds the rows procs shows for a /proc/all snapshot and prints them as a table. The columns,
+// the CPU percentages between two snapshots, the process tree and top's keys have modules of
+// their own.
+
+pub mod columns;
+pub mod cpu;
+#[cfg(test)]
+mod tests;
+pub mod top;
+pub mod tree;
+
+use std::collections::HashMap;
+use std::io::Write;
+
+use columns::{Column, Row};
+use serenity::procfs::Process;
+
+// What the table shows and in which order. In top mode, keys can change it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct View {
+    pub columns: Vec<Column>,
+    pub sort: Option<Column>,
+    pub tree: bool,
+}
+
+pub fn build_rows<'a>(
+    processes: Vec<&'a Process>,
+    cpu: &HashMap<i32, f64>,
+    view: &View,
+) -> Vec<Row<'a>> {
+    let row = |depth, process: &'a Process| Row {
+        process,
+        cpu: cpu.get(&process.pid).copied().unwrap_or(0.0),
+        depth,
+    };
+    let mut rows: Vec<Row> = processes
+        .into_iter()
+        .map(|process| row(0, process))
+        .collect();
+    // Sorting is stable, so rows that compare equal stay in kernel order.
+    if let Some(column) = view.sort {
+        rows.sort_by(|a, b| column.compare(a, b));
+    }
+    if !view.tree {
+        return rows;
+    }
+    let sorted: Vec<&Process> = rows.iter().map(|row| row.process).collect();
+    tree::flatten(&sorted)
+        .into_iter()
+        .map(|(depth, process)| row(depth, process))
+        .collect()
+}
+
+pub fn print_table(
+    output: &mut impl Write,
+    rows: &[Row],
+    columns: &[Column],
+) -> std::io::Result<()> {
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| columns.iter().map(|column| column.value(row)).collect())
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            cells
+                .iter()
+                .map(|row| row[index].chars().count())
+                .chain([column.title().len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let mut print_line = |values: Vec<&str>| -> std::io::Result<()> {
+        let mut line = String::new();
+        for (index, (column, value)) in columns.iter().zip(values).enumerate() {
+            if index > 0 {
+                line.push(' ');
+            }
+            let width = widths[index];
+            if column.is_numeric() {
+                line.push_str(&format!("{:>width$}", value, width = width));
+            } else {
+                line.push_str(&format!("{:width$}", value, width = width));
+            }
+        }
+        writeln!(output, "{}", line.trim_end())
+    };
+    print_line(columns.iter().map(|column| column.title()).collect())?;
+    for row in &cells {
+        print_line(row.iter().map(String::as_str).collect())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,197 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Lists processes from /proc/all, like ps, or keeps redrawing them with their current CPU usage,
+// like top, where keys change the order:
+//
+//     procs -o pid,uid,vm,name -s vm
+//     procs --tree
+//     procs --top -d 2
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use procs::columns::{self, Column};
+use procs::{build_rows, cpu, print_table, top, View};
+use serenity::procfs::{self, AllProcesses};
+use serenity::sys;
+
+const USAGE: &str = "usage: procs [-o columns] [-s column] [-T] [-t [-d seconds]]";
+
+struct Options {
+    view: View,
+    top: bool,
+    delay: Duration,
+}
+
+fn parse_arguments() -> Result<Options, Box<dyn std::error::Error>> {
+    let mut options = Options {
+        view: View {
+            columns: Column::parse_list(columns::DEFAULT_COLUMNS)?,
+            sort: None,
+            tree: false,
+        },
+        top: false,
+        delay: Duration::from_secs(1),
+    };
+    let mut arguments = std::env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "-o" | "--columns" => {
+                options.view.columns = Column::parse_list(&arguments.next().ok_or(USAGE)?)?
+            }
+            "-s" | "--sort" => {
+                let name = arguments.next().ok_or(USAGE)?;
+                let column = Column::parse(&name).ok_or(format!("unknown column '{}'", name))?;
+                options.view.sort = Some(column);
+            }
+            "-T" | "--tree" => options.view.tree = true,
+            "-t" | "--top" => options.top = true,
+            "-d" | "--delay" => {
+                let delay = arguments
+                    .next()
+                    .and_then(|seconds| seconds.parse::<f64>().ok())
+                    .filter(|seconds| seconds.is_finite() && *seconds > 0.0)
+                    .ok_or(USAGE)?;
+                options.delay = Duration::from_secs_f64(delay);
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                println!("  -o, --columns LIST  comma-separated columns to show, out of");
+                println!("                      pid ppid pgid sid uid gid state tty vm rss nfds");
+                println!("                      threads cpu name");
+                println!("  -s, --sort COLUMN   sort by COLUMN, amounts biggest first");
+                println!("  -T, --tree          show children under their parent");
+                println!("  -t, --top           keep redrawing with the CPU usage since the");
+                println!("                      previous reading, until q or ^C. Keys change");
+                println!("                      the sort column and toggle the tree");
+                println!("  -d, --delay SECONDS time between readings in top mode");
+                std::process::exit(0);
+            }
+            _ => return Err(format!("unknown argument '{}'\n{}", argument, USAGE).into()),
+        }
+    }
+    // top shows the busiest processes first unless told otherwise.
+    if options.top && options.view.sort.is_none() {
+        options.view.sort = Some(Column::Cpu);
+    }
+    Ok(options)
+}
+
+// Hides the cursor while redrawing, and shows it again however top mode ends.
+struct HiddenCursor;
+
+impl HiddenCursor {
+    fn hide(output: &mut impl Write) -> std::io::Result<Self> {
+        write!(output, "\x1b[?25l")?;
+        Ok(Self)
+    }
+}
+
+impl Drop for HiddenCursor {
+    fn drop(&mut self) {
+        let mut output = std::io::stdout();
+        let _ = writeln!(output, "\x1b[?25h");
+        let _ = output.flush();
+    }
+}
+
+fn redraw(
+    output: &mut impl Write,
+    snapshot: &AllProcesses,
+    cpu: &HashMap<i32, f64>,
+    options: &Options,
+) -> std::io::Result<()> {
+    // Move home and clear the screen, then draw the table.
+    write!(output, "\x1b[H\x1b[2J")?;
+    top::print_screen(output, snapshot, cpu, &options.view, options.delay)?;
+    output.flush()
+}
+
+// Redraws the table in place until q or SIGINT, and right away when a key changes the view.
+// Keeps the rpath promise to re-read the node, and the tty one to restore the terminal.
+fn top(procfs: &procfs::ProcFS, mut options: Options) -> Result<(), Box<dyn std::error::Error>> {
+    sys::catch_interrupt()?;
+    // Without a terminal on standard input, there are no keys to read.
+    let mut terminal = sys::RawTerminal::enable().ok();
+    let mut output = std::io::stdout().lock();
+    let _cursor = HiddenCursor::hide(&mut output)?;
+
+    let mut previous: AllProcesses = procfs.all()?;
+    // The CPU usage drawn last, to redraw with when a key changes the view.
+    let mut shown_cpu: Option<HashMap<i32, f64>> = None;
+    loop {
+        let deadline = Instant::now() + options.delay;
+        loop {
+            if sys::interrupted() {
+                return Ok(());
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            let wait = (deadline - now).min(Duration::from_millis(100));
+            let keys = match &terminal {
+                Some(keys) => keys,
+                None => {
+                    std::thread::sleep(wait);
+                    continue;
+                }
+            };
+            let key = match keys.read_key(wait) {
+                Ok(key) => key,
+                Err(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => {
+                    terminal = None;
+                    continue;
+                }
+                Err(error) => return Err(error.into()),
+            };
+            let command = match key.and_then(top::command) {
+                Some(command) => command,
+                None => continue,
+            };
+            if !top::apply(command, &mut options.view) {
+                return Ok(());
+            }
+            if let Some(cpu) = &shown_cpu {
+                redraw(&mut output, &previous, cpu, &options)?;
+            }
+        }
+
+        let current = procfs.all()?;
+        let percentages = cpu::cpu_percentages(Some(&previous), &current);
+        redraw(&mut output, &current, &percentages, &options)?;
+        previous = current;
+        shown_cpu = Some(percentages);
+    }
+}
+
+fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let procfs = procfs::ProcFS::new();
+    sys::pledge("stdio rpath tty")?;
+    sys::unveil(&procfs.path("all").to_string_lossy(), "r")?;
+    sys::lock_veil()?;
+    let options = parse_arguments()?;
+    if options.top {
+        return top(&procfs, options);
+    }
+    let snapshot = procfs.all()?;
+    sys::pledge("stdio")?;
+
+    // With a single reading, the CPU usage is the share since boot.
+    let cpu = cpu::cpu_percentages(None, &snapshot);
+    let rows = build_rows(snapshot.processes.iter().collect(), &cpu, &options.view);
+    print_table(&mut std::io::stdout().lock(), &rows, &options.view.columns)?;
+    Ok(())
+}
+
+pub fn main() {
+    if let Err(error) = run() {
+        eprintln!("procs: {}", error);
+        std::process::exit(1);
+    }
+}
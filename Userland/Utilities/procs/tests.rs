@@ -0,0 +1,277 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Replays /proc/all snapshots and compares the output with the files in fixtures/expected.
+// "desktop" is a machine sitting at the desktop with cat running in a terminal. "desktop-later"
+// is the same machine a second later: cat has exited, ls has started, and WebSocket was restarted
+// under the same PID.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serenity::procfs::{AllProcesses, ProcFS, Process};
+
+use crate::columns::{Column, Row, DEFAULT_COLUMNS};
+use crate::cpu::cpu_percentages;
+use crate::top::{self, Command};
+use crate::{build_rows, print_table, tree, View};
+
+fn snapshot(fixture: &str) -> AllProcesses {
+    ProcFS::with_root(format!(
+        "{}/fixtures/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        fixture
+    ))
+    .all()
+    .unwrap()
+}
+
+fn view(columns: &str, sort: Option<Column>, tree: bool) -> View {
+    View {
+        columns: Column::parse_list(columns).unwrap(),
+        sort,
+        tree,
+    }
+}
+
+fn table(snapshot: &AllProcesses, cpu: &HashMap<i32, f64>, view: &View) -> String {
+    let rows = build_rows(snapshot.processes.iter().collect(), cpu, view);
+    let mut output = Vec::new();
+    print_table(&mut output, &rows, &view.columns).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+fn pids(snapshot: &AllProcesses, view: &View) -> Vec<i32> {
+    let cpu = cpu_percentages(None, snapshot);
+    build_rows(snapshot.processes.iter().collect(), &cpu, view)
+        .iter()
+        .map(|row| row.process.pid)
+        .collect()
+}
+
+#[test]
+fn prints_columns() {
+    let desktop = snapshot("desktop");
+    // With a single snapshot, the CPU usage is the share since boot.
+    let cpu = cpu_percentages(None, &desktop);
+    assert_eq!(
+        table(&desktop, &cpu, &view(DEFAULT_COLUMNS, None, false)),
+        include_str!("fixtures/expected/desktop.txt")
+    );
+    let all = "pid,ppid,pgid,sid,uid,gid,state,tty,vm,rss,nfds,threads,cpu,name";
+    assert_eq!(
+        table(&desktop, &cpu, &view(all, None, false)),
+        include_str!("fixtures/expected/desktop-all-columns.txt")
+    );
+}
+
+#[test]
+fn parses_column_lists() {
+    assert_eq!(
+        Column::parse_list("pid,cpu,name"),
+        Ok(vec![Column::Pid, Column::Cpu, Column::Name])
+    );
+    assert_eq!(
+        Column::parse_list("pid,size"),
+        Err("unknown column 'size'".to_string())
+    );
+    assert_eq!(Column::parse_list(""), Err("unknown column ''".to_string()));
+}
+
+#[test]
+fn sorts_rows() {
+    let desktop = snapshot("desktop");
+    let first = |sort| pids(&desktop, &view("pid", Some(sort), false))[..4].to_vec();
+    // Amounts sort biggest first.
+    assert_eq!(first(Column::Vm), [29, 15, 33, 28]);
+    assert_eq!(first(Column::Threads), [29, 33, 0, 18]);
+    // Ties stay in kernel order.
+    assert_eq!(first(Column::Uid), [0, 4, 5, 6]);
+    assert_eq!(first(Column::Name), [18, 28, 17, 10]);
+    // The state is the main thread's.
+    assert_eq!(first(Column::State), [12, 17, 18, 34]);
+    assert_eq!(
+        pids(&desktop, &view("pid", Some(Column::Pid), false)),
+        pids(&desktop, &view("pid", None, false))
+    );
+}
+
+#[test]
+fn computes_cpu_usage_between_snapshots() {
+    let desktop = snapshot("desktop");
+    let later = snapshot("desktop-later");
+    let cpu = cpu_percentages(Some(&desktop), &later);
+    let percent = |pid| (cpu[&pid] * 10.0).round() / 10.0;
+    // Idle time counts towards the kernel's process.
+    assert_eq!(percent(0), 60.0);
+    // Summed over the threads.
+    assert_eq!(percent(29), 15.0);
+    assert_eq!(percent(15), 8.0);
+    assert_eq!(percent(32), 5.0);
+    assert_eq!(percent(27), 2.0);
+    assert_eq!(percent(9), 0.0);
+    // New processes, and ones whose counters went backwards, count from zero.
+    assert_eq!(percent(37), 4.0);
+    assert_eq!(percent(31), 0.2);
+    // Exited ones are left out.
+    assert!(!cpu.contains_key(&36));
+    assert_eq!(cpu.len(), later.processes.len());
+
+    // Without time passing, nobody used any.
+    let cpu = cpu_percentages(Some(&later), &later);
+    assert!(cpu.values().all(|percent| *percent == 0.0));
+}
+
+#[test]
+fn computes_cpu_usage_since_boot() {
+    let desktop = snapshot("desktop");
+    let cpu = cpu_percentages(None, &desktop);
+    let cat = (146069 + 82932) as f64 * 100.0 / desktop.total_time as f64;
+    assert_eq!(cpu[&36], cat);
+    // The sum depends on the order of the map, so it may round to a hair over 100.
+    let total: f64 = cpu.values().sum();
+    assert!(total <= 100.0 + 1e-9, "{}", total);
+}
+
+#[test]
+fn prints_trees() {
+    let desktop = snapshot("desktop");
+    let cpu = cpu_percentages(None, &desktop);
+    assert_eq!(
+        table(&desktop, &cpu, &view("pid,ppid,name", None, true)),
+        include_str!("fixtures/expected/desktop-tree.txt")
+    );
+    // Siblings are sorted, and children still follow their parent.
+    assert_eq!(
+        table(&desktop, &cpu, &view("pid,vm,name", Some(Column::Vm), true)),
+        include_str!("fixtures/expected/desktop-tree-by-vm.txt")
+    );
+}
+
+fn flattened(processes: &[Process]) -> Vec<(usize, i32)> {
+    let processes: Vec<&Process> = processes.iter().collect();
+    tree::flatten(&processes)
+        .into_iter()
+        .map(|(depth, process)| (depth, process.pid))
+        .collect()
+}
+
+#[test]
+fn keeps_processes_in_parent_cycles() {
+    let desktop = snapshot("desktop");
+    let mut processes: Vec<Process> = desktop
+        .processes
+        .into_iter()
+        .filter(|process| [0, 9, 23, 25, 27, 34].contains(&process.pid))
+        .collect();
+    assert_eq!(flattened(&processes), [
+        (0, 0),
+        (1, 9),
+        (2, 23),
+        (3, 25),
+        (4, 27),
+        (4, 34)
+    ]);
+
+    // A snapshot taken while Taskbar was being reparented, with LoginServer its child and
+    // Taskbar LoginServer's.
+    for process in &mut processes {
+        if process.pid == 23 {
+            process.ppid = 25;
+        }
+    }
+    assert_eq!(flattened(&processes), [
+        (0, 0),
+        (1, 9),
+        (0, 23),
+        (1, 25),
+        (2, 27),
+        (2, 34)
+    ]);
+    // A process that is its own parent is a root like the kernel's.
+    for process in &mut processes {
+        if process.pid == 27 {
+            process.ppid = 27;
+        }
+    }
+    assert_eq!(flattened(&processes), [
+        (0, 0),
+        (1, 9),
+        (0, 27),
+        (0, 23),
+        (1, 25),
+        (2, 34)
+    ]);
+    assert_eq!(flattened(&[]), []);
+}
+
+fn screen(previous: &AllProcesses, current: &AllProcesses, view: &View) -> String {
+    let cpu = cpu_percentages(Some(previous), current);
+    let mut output = Vec::new();
+    top::print_screen(&mut output, current, &cpu, view, Duration::from_secs(1)).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn prints_top_screens() {
+    let desktop = snapshot("desktop");
+    let later = snapshot("desktop-later");
+    let mut view = view(DEFAULT_COLUMNS, Some(Column::Cpu), false);
+    assert_eq!(
+        screen(&desktop, &later, &view),
+        include_str!("fixtures/expected/top.txt")
+    );
+
+    assert!(top::apply(Command::ToggleTree, &mut view));
+    assert!(top::apply(Command::Sort(Column::Vm), &mut view));
+    assert_eq!(
+        screen(&desktop, &later, &view),
+        include_str!("fixtures/expected/top-tree-by-vm.txt")
+    );
+}
+
+#[test]
+fn handles_top_keys() {
+    assert_eq!(top::command(b'q'), Some(Command::Quit));
+    assert_eq!(top::command(b'Q'), Some(Command::Quit));
+    assert_eq!(top::command(b'c'), Some(Command::Sort(Column::Cpu)));
+    assert_eq!(top::command(b'v'), Some(Command::Sort(Column::Vm)));
+    assert_eq!(top::command(b'r'), Some(Command::Sort(Column::Rss)));
+    assert_eq!(top::command(b'p'), Some(Command::Sort(Column::Pid)));
+    assert_eq!(top::command(b'n'), Some(Command::Sort(Column::Name)));
+    assert_eq!(top::command(b'T'), Some(Command::ToggleTree));
+    // Including the bytes of escape sequences, like the arrow keys send.
+    for key in [b'x', b' ', 0x1b, b'[', b'A'] {
+        assert_eq!(top::command(key), None);
+    }
+
+    let mut view = view(DEFAULT_COLUMNS, Some(Column::Cpu), false);
+    assert!(top::apply(Command::Sort(Column::Name), &mut view));
+    assert_eq!(view.sort, Some(Column::Name));
+    assert!(top::apply(Command::ToggleTree, &mut view));
+    assert!(view.tree);
+    assert!(top::apply(Command::ToggleTree, &mut view));
+    assert!(!view.tree);
+    assert!(!top::apply(Command::Quit, &mut view));
+}
+
+#[test]
+fn shows_the_main_threads_state() {
+    let desktop = snapshot("desktop");
+    let cpu = HashMap::new();
+    let rows: Vec<Row> = build_rows(
+        desktop.processes.iter().collect(),
+        &cpu,
+        &view("pid,state", None, false),
+    );
+    let state = |pid| {
+        let row = rows.iter().find(|row| row.process.pid == pid).unwrap();
+        Column::State.value(row)
+    };
+    assert_eq!(state(0), "Runnable");
+    assert_eq!(state(11), "Sleeping");
+    assert_eq!(state(36), "Running");
+}
@@ -0,0 +1,76 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// The screen top mode draws, and what the keys pressed in it do.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::time::Duration;
+
+use serenity::procfs::AllProcesses;
+
+use crate::columns::Column;
+use crate::{build_rows, print_table, View};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    Quit,
+    Sort(Column),
+    ToggleTree,
+}
+
+// Shown above the table.
+pub const KEYS: &str = "sort by c)pu v)m r)ss p)id n)ame, T)ree, q)uit";
+
+pub fn command(key: u8) -> Option<Command> {
+    match key {
+        b'q' | b'Q' => Some(Command::Quit),
+        b'c' => Some(Command::Sort(Column::Cpu)),
+        b'v' => Some(Command::Sort(Column::Vm)),
+        b'r' => Some(Command::Sort(Column::Rss)),
+        b'p' => Some(Command::Sort(Column::Pid)),
+        b'n' => Some(Command::Sort(Column::Name)),
+        b'T' => Some(Command::ToggleTree),
+        _ => None,
+    }
+}
+
+// Applies the command to the view, returning whether to keep going.
+pub fn apply(command: Command, view: &mut View) -> bool {
+    match command {
+        Command::Quit => return false,
+        Command::Sort(column) => view.sort = Some(column),
+        Command::ToggleTree => view.tree = !view.tree,
+    }
+    true
+}
+
+// The summary line and the table, without the escape codes that clear the screen. Like top, the
+// kernel's own process is left out, since it is mostly the idle threads.
+pub fn print_screen(
+    output: &mut impl Write,
+    snapshot: &AllProcesses,
+    cpu: &HashMap<i32, f64>,
+    view: &View,
+    delay: Duration,
+) -> std::io::Result<()> {
+    let processes = snapshot
+        .processes
+        .iter()
+        .filter(|process| process.pid != 0)
+        .collect();
+    let rows = build_rows(processes, cpu, view);
+    let thread_count: usize = rows.iter().map(|row| row.process.threads.len()).sum();
+    writeln!(
+        output,
+        "{} processes, {} threads, every {:.1}s",
+        rows.len(),
+        thread_count,
+        delay.as_secs_f64()
+    )?;
+    writeln!(output, "{}\n", KEYS)?;
+    print_table(output, &rows, &view.columns)
+}
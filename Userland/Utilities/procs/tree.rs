@@ -0,0 +1,46 @@
+/*
+ * Copyright (c) 2022, the SerenityOS developers.
+ *
+ * SPDX-License-Identifier: BSD-2-Clause
+ */
+
+// Orders processes so that children follow their parent.
+
+use std::collections::{HashMap, HashSet};
+
+use serenity::procfs::Process;
+
+// The processes in depth-first order, with how deep each one is. Siblings keep the order they are
+// given in, so sorting first sorts each level. Processes whose parent is gone are roots.
+//
+// A snapshot taken while processes were being reparented can have parents form a cycle, which no
+// root leads to. The first process of each such cycle is made a root too, so none go missing.
+pub fn flatten<'a>(processes: &[&'a Process]) -> Vec<(usize, &'a Process)> {
+    let pids: HashSet<i32> = processes.iter().map(|process| process.pid).collect();
+    let mut children: HashMap<i32, Vec<&'a Process>> = HashMap::new();
+    let mut roots = Vec::new();
+    for process in processes {
+        // The kernel's own process is its own parent.
+        if process.ppid == process.pid || !pids.contains(&process.ppid) {
+            roots.push(*process);
+        } else {
+            children.entry(process.ppid).or_default().push(process);
+        }
+    }
+
+    let mut flattened = Vec::with_capacity(processes.len());
+    let mut visited: HashSet<i32> = HashSet::new();
+    for root in roots.into_iter().chain(processes.iter().copied()) {
+        let mut stack = vec![(0, root)];
+        while let Some((depth, process)) = stack.pop() {
+            if !visited.insert(process.pid) {
+                continue;
+            }
+            flattened.push((depth, process));
+            if let Some(children) = children.get(&process.pid) {
+                stack.extend(children.iter().rev().map(|child| (depth + 1, *child)));
+            }
+        }
+    }
+    flattened
+}